@@ -0,0 +1,214 @@
+//! Serializes arbitrary params with arbitrary options, checking the output respects the options and that every
+//! structural character in a string is escaped, so the output parses back to the nesting of the params.
+//! Run with `cargo fuzz run serialize_value` from the repository root.
+#![no_main]
+use std::collections::BTreeMap;
+use std::iter::Peekable;
+use std::str::Chars;
+
+use arbitrary::Arbitrary;
+use icon_utils::icon_hex::to_hex_no_pad;
+use icon_utils::serializer::{serialize_to_string_with_options, FloatMode, NullMode, SerializerOptions, Transaction};
+use libfuzzer_sys::fuzz_target;
+use serde::Serialize;
+use serde_json::Value;
+
+#[derive(Debug, Arbitrary)]
+struct Input {
+    options: Options,
+    params: Params,
+}
+
+#[derive(Debug, Arbitrary)]
+struct Options {
+    sort_fields: bool,
+    omit_nulls: bool,
+    reject_nulls: bool,
+    decimal_floats: bool,
+    max_depth: Option<u8>,
+    max_output_len: Option<u16>,
+}
+
+#[derive(Debug, Arbitrary)]
+enum Params {
+    /// Parsed from raw bytes, for inputs that happen to be JSON
+    Json(Vec<u8>),
+    Tree(Tree),
+}
+
+#[derive(Debug, Arbitrary, Serialize)]
+#[serde(untagged)]
+enum Tree {
+    Null,
+    Bool(bool),
+    Int(i128),
+    Uint(u128),
+    Float(f64),
+    Str(String),
+    Bytes(Vec<u8>),
+    List(Vec<Tree>),
+    Map(BTreeMap<String, Tree>),
+    Struct(Struct),
+}
+
+#[derive(Debug, Arbitrary, Serialize)]
+struct Struct {
+    to: String,
+    value: Option<Box<Tree>>,
+    #[serde(flatten)]
+    extra: BTreeMap<String, Tree>,
+}
+
+struct Tx<'a, P> {
+    method: String,
+    params: &'a P,
+}
+
+impl<'a, P> Transaction for Tx<'a, P> {
+    type Params = &'a P;
+    fn params(&self) -> &&'a P {
+        &self.params
+    }
+    fn method(&self) -> &String {
+        &self.method
+    }
+}
+
+/// Params read back from the serialized format, with every value a string, null, array or map
+#[derive(Debug, PartialEq)]
+enum Node {
+    Null,
+    Str(String),
+    List(Vec<Node>),
+    Map(Vec<(String, Node)>),
+}
+
+/// What a JSON value should parse back to when serialized with `options`
+fn expected(value: &Value, options: SerializerOptions) -> Node {
+    match value {
+        Value::Null => Node::Null,
+        Value::Bool(b) => Node::Str(if *b { "0x1" } else { "0x0" }.to_owned()),
+        Value::Number(n) => Node::Str(match (n.as_u64(), n.as_i64()) {
+            (Some(v), _) => to_hex_no_pad(u128::from(v)),
+            (None, Some(v)) => format!("-{}", to_hex_no_pad(u128::from(v.unsigned_abs()))),
+            // Floats are written with Display, which never uses an exponent
+            _ => n.as_f64().unwrap().to_string(),
+        }),
+        Value::String(s) => Node::Str(s.clone()),
+        Value::Array(values) => match values.as_slice() {
+            // `[""]` and `[]` are both written as `[]`
+            [Value::String(s)] if s.is_empty() => Node::List(Vec::new()),
+            values => Node::List(values.iter().map(|value| expected(value, options)).collect()),
+        },
+        Value::Object(map) => Node::Map(
+            map.iter()
+                .filter(|(_, value)| !(value.is_null() && options.null_mode == NullMode::Omit))
+                .map(|(key, value)| (key.clone(), expected(value, options)))
+                .collect(),
+        ),
+    }
+}
+
+fn parse_node(chars: &mut Peekable<Chars>) -> Node {
+    let close = match chars.peek() {
+        Some('{') => '}',
+        Some('[') => ']',
+        _ => return parse_scalar(chars),
+    };
+    chars.next();
+    let (mut entries, mut values) = (Vec::new(), Vec::new());
+    if chars.peek() == Some(&close) {
+        chars.next();
+    } else {
+        loop {
+            if close == '}' {
+                let Node::Str(key) = parse_scalar(chars) else { panic!("null key") };
+                assert_eq!(chars.next(), Some('.'));
+                entries.push((key, parse_node(chars)));
+            } else {
+                values.push(parse_node(chars));
+            }
+            match chars.next() {
+                Some('.') => continue,
+                Some(c) if c == close => break,
+                c => panic!("expected . or {} but found {:?}", close, c),
+            }
+        }
+    }
+    if close == '}' {
+        Node::Map(entries)
+    } else {
+        Node::List(values)
+    }
+}
+
+fn parse_scalar(chars: &mut Peekable<Chars>) -> Node {
+    let mut value = String::new();
+    let mut null = false;
+    while let Some(&c) = chars.peek() {
+        match c {
+            '.' | '}' | ']' => break,
+            '{' | '[' => panic!("unescaped {}", c),
+            '\\' => {
+                chars.next();
+                match chars.next() {
+                    Some('0') if value.is_empty() && !null => null = true,
+                    Some(c @ ('\\' | '.' | '{' | '}' | '[' | ']')) => value.push(c),
+                    c => panic!("unexpected escape {:?}", c),
+                }
+                continue;
+            }
+            c => value.push(c),
+        }
+        assert!(!null, "null followed by {:?}", value);
+        chars.next();
+    }
+    if null {
+        Node::Null
+    } else {
+        Node::Str(value)
+    }
+}
+
+/// Serializes the params, returning what the output parses back to
+fn check<P: Serialize>(params: &P, options: SerializerOptions) -> Option<Node> {
+    let tx = Tx { method: "icx_sendTransaction".to_owned(), params };
+    let first = serialize_to_string_with_options(&tx, options);
+    let second = serialize_to_string_with_options(&tx, options);
+    assert_eq!(first.as_ref().ok(), second.as_ref().ok());
+    let output = first.ok()?;
+    if let Some(max_len) = options.max_output_len {
+        assert!(output.len() <= max_len);
+    }
+    let mut chars = output.strip_prefix("icx_sendTransaction.params.").unwrap().chars().peekable();
+    let parsed = parse_node(&mut chars);
+    assert_eq!(chars.next(), None, "trailing characters in {}", output);
+    Some(parsed)
+}
+
+fuzz_target!(|input: Input| {
+    let Options { sort_fields, omit_nulls, reject_nulls, decimal_floats, max_depth, max_output_len } = input.options;
+    let options = SerializerOptions {
+        sort_fields,
+        null_mode: match (omit_nulls, reject_nulls) {
+            (true, _) => NullMode::Omit,
+            (false, true) => NullMode::Error,
+            (false, false) => NullMode::EscapedNull,
+        },
+        float_mode: if decimal_floats { FloatMode::Decimal } else { FloatMode::Reject },
+        max_depth: max_depth.map(usize::from),
+        max_output_len: max_output_len.map(usize::from),
+    };
+    match input.params {
+        Params::Json(bytes) => {
+            if let Ok(value) = serde_json::from_slice::<Value>(&bytes) {
+                if let Some(parsed) = check(&value, options) {
+                    assert_eq!(parsed, expected(&value, options));
+                }
+            }
+        }
+        Params::Tree(tree) => {
+            check(&tree, options);
+        }
+    }
+});
@@ -1,45 +1,221 @@
 use proc_macro::{TokenStream};
+use proc_macro2::TokenStream as TokenStream2;
 use quote::quote;
 use syn::{parse_macro_input,DeriveInput};
 
 
-/// Macro used by icon-utils for deriving the Transaction 
-/// 
+/// Macro used by icon-utils for deriving the Transaction
+///
+/// The method can be given as a `method: String` field, or as a constant with the `transaction` attribute.
+/// If the struct has no `params` field, the struct itself is used as the params.
+/// Generic structs are supported, the struct's generics and where clause are carried over to the impl.
+///
+/// It can also be derived for an enum whose variants each wrap one transaction. `method()` dispatches to the current
+/// variant and the enum is its own params, serializing as the params of the current variant through a generated
+/// `{Enum}Params<'a>(&'a Enum)` wrapper. The wrapper can be renamed with `#[transaction(params = "...")]`, and as the
+/// enum implements `Serialize` itself it can't also derive it.
+///
 /// # Example
-/// ```
+/// ```ignore
 /// #[derive(Transaction)]
-/// #[sort]
 /// struct ExampleTransaction {
 ///     method: String,
 ///     params: ExampleParams
 /// }
+///
+/// #[derive(Transaction)]
+/// #[transaction(method = "icx_sendTransaction")]
+/// struct ExampleParams {
+///     from: String,
+///     to: String
+/// }
+///
+/// #[derive(Transaction)]
+/// #[transaction(params = "QueuedParams")]
+/// enum AnyTransaction {
+///     Transfer(ExampleParams),
+///     Other(ExampleTransaction),
+/// }
 /// ```
-#[proc_macro_derive(Transaction)]
+#[proc_macro_derive(Transaction, attributes(transaction))]
 pub fn transaction(input: TokenStream) -> TokenStream {
-    let DeriveInput {ident, attrs, mut data,    ..} = parse_macro_input!(input as DeriveInput);
-    let fields = match &mut data {
-        syn::Data::Struct(syn::DataStruct { fields: syn::Fields::Named(fields), ..}) => &fields.named,
-        _ => panic!("Only structs with named fields are supported"),
-        
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_transaction(input).unwrap_or_else(|e| e.to_compile_error()).into()
+}
+fn expand_transaction(input: DeriveInput) -> syn::Result<TokenStream2> {
+    if let syn::Data::Enum(data) = &input.data {
+        return expand_transaction_enum(&input, data);
+    }
+    let DeriveInput {ident, attrs, data, generics, ..} = input;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let fields = named_fields(&data, &ident, "Transaction")?;
+    let Attributes { method, params } = transaction_attributes(&attrs)?;
+    if let Some(params) = params {
+        return Err(syn::Error::new_spanned(params, "the params attribute names the params wrapper of Transaction enums, structs don't have one"));
+    }
+    let has_field = |name: &str| fields.iter().any(|f| f.ident.as_ref().map_or(false, |i| i == name));
+    let params_field = fields.iter().find(|f| f.ident.as_ref().map_or(false, |i| i == "params"));
+    let (param_type, params_body) = match params_field {
+        Some(field) => {
+            let ty = &field.ty;
+            (quote! { #ty }, quote! { &self.params })
+        }
+        None if method.is_some() => (quote! { #ident #ty_generics }, quote! { self }),
+        None => return Err(syn::Error::new_spanned(
+            &ident,
+            "Transaction structs need a `params` field, or must set the method with #[transaction(method = \"...\")] to use the struct itself as the params",
+        )),
+    };
+    let method_body = match method {
+        Some(method) => quote! {
+            static METHOD: ::std::sync::OnceLock<String> = ::std::sync::OnceLock::new();
+            METHOD.get_or_init(|| #method.to_owned())
+        },
+        None if has_field("method") => quote! { &self.method },
+        None => return Err(syn::Error::new_spanned(
+            &ident,
+            "Transaction structs need a `method: String` field, or must set the method with #[transaction(method = \"...\")]",
+        )),
     };
-    let paramType = fields.iter().find(|f| f.ident.as_ref().unwrap() == "params").unwrap().ty.clone();
-    let output = quote! {
-        impl icon_utils::serializer::Transaction for &#ident {
-            type Params = #paramType;
+    Ok(quote! {
+        impl #impl_generics icon_utils::serializer::Transaction for #ident #ty_generics #where_clause {
+            type Params = #param_type;
             fn params(&self) -> &Self::Params {
-                &self.params
+                #params_body
             }
             fn method(&self) -> &String {
-                &self.method
-            } 
+                #method_body
+            }
+        }
+    })
+}
+/// Implements Transaction for an enum of transactions, dispatching to the wrapped transaction of each variant
+fn expand_transaction_enum(input: &DeriveInput, data: &syn::DataEnum) -> syn::Result<TokenStream2> {
+    let DeriveInput {ident, vis, generics, attrs, ..} = input;
+    let Attributes { method, params } = transaction_attributes(attrs)?;
+    if let Some(method) = method {
+        return Err(syn::Error::new_spanned(method, "Transaction enums take the method of their current variant, it can't be set with an attribute"));
+    }
+    if data.variants.is_empty() {
+        return Err(syn::Error::new_spanned(ident, "Transaction can't be derived for an enum without variants"));
+    }
+    let mut variants = Vec::new();
+    for variant in &data.variants {
+        match &variant.fields {
+            syn::Fields::Unnamed(fields) if fields.unnamed.len() == 1 => variants.push((&variant.ident, &fields.unnamed[0].ty)),
+            _ => return Err(syn::Error::new_spanned(
+                variant,
+                "Transaction enums need variants that each wrap one transaction, like `Transfer(TransferTransaction)`",
+            )),
         }
+    }
+    let params_ident = match params {
+        Some(params) => params.parse::<syn::Ident>()?,
+        None => quote::format_ident!("{}Params", ident),
     };
-    output.into()
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let variant_names = variants.iter().map(|(name, _)| *name).collect::<Vec<_>>();
+    let variant_types = variants.iter().map(|(_, ty)| *ty).collect::<Vec<_>>();
+
+    let mut transaction_generics = generics.clone();
+    let predicates = &mut transaction_generics.make_where_clause().predicates;
+    for ty in &variant_types {
+        predicates.push(syn::parse_quote! { #ty: icon_utils::serializer::Transaction });
+    }
+    let transaction_where = &transaction_generics.where_clause;
+    let mut serialize_generics = transaction_generics.clone();
+    let predicates = &mut serialize_generics.make_where_clause().predicates;
+    for ty in &variant_types {
+        predicates.push(syn::parse_quote! { <#ty as icon_utils::serializer::Transaction>::Params: ::serde::Serialize });
+    }
+    let serialize_where = &serialize_generics.where_clause;
+    let mut params_generics = generics.clone();
+    params_generics.params.insert(0, syn::parse_quote! { '__params });
+    let (params_impl_generics, params_ty_generics, _) = params_generics.split_for_impl();
+    let doc = format!("Params of a [`{}`], serialized as the params of its current variant", ident);
+
+    Ok(quote! {
+        #[doc = #doc]
+        #vis struct #params_ident #params_generics (pub &'__params #ident #ty_generics) #where_clause;
+
+        impl #params_impl_generics ::serde::Serialize for #params_ident #params_ty_generics #serialize_where {
+            fn serialize<S: ::serde::Serializer>(&self, serializer: S) -> ::std::result::Result<S::Ok, S::Error> {
+                match self.0 {
+                    #( #ident::#variant_names(tx) => ::serde::Serialize::serialize(icon_utils::serializer::Transaction::params(tx), serializer), )*
+                }
+            }
+        }
+
+        impl #impl_generics ::serde::Serialize for #ident #ty_generics #serialize_where {
+            fn serialize<S: ::serde::Serializer>(&self, serializer: S) -> ::std::result::Result<S::Ok, S::Error> {
+                ::serde::Serialize::serialize(&#params_ident(self), serializer)
+            }
+        }
+
+        impl #impl_generics icon_utils::serializer::Transaction for #ident #ty_generics #transaction_where {
+            type Params = Self;
+            fn params(&self) -> &Self {
+                self
+            }
+            fn method(&self) -> &String {
+                match self {
+                    #( #ident::#variant_names(tx) => icon_utils::serializer::Transaction::method(tx), )*
+                }
+            }
+        }
+    })
 }
-/// Macro used by icon-utils for sorting the fields of a struct alphabetically
-/// 
+/// Returns the named fields of a struct, or an error spanning the unsupported item
+fn named_fields<'a>(data: &'a syn::Data, ident: &syn::Ident, name: &str) -> syn::Result<&'a syn::punctuated::Punctuated<syn::Field, syn::token::Comma>> {
+    match data {
+        syn::Data::Struct(syn::DataStruct { fields: syn::Fields::Named(fields), ..}) => Ok(&fields.named),
+        syn::Data::Struct(syn::DataStruct { fields, ..}) => Err(syn::Error::new_spanned(
+            fields,
+            format!("{} can only be used on structs with named fields, not tuple or unit structs", name),
+        )),
+        syn::Data::Enum(syn::DataEnum { enum_token, ..}) => Err(syn::Error::new_spanned(
+            enum_token,
+            format!("{} can only be used on structs with named fields, not enums", name),
+        )),
+        syn::Data::Union(_) => Err(syn::Error::new_spanned(
+            ident,
+            format!("{} can only be used on structs with named fields, not unions", name),
+        )),
+    }
+}
+/// The values of `#[transaction(...)]`
+#[derive(Default)]
+struct Attributes {
+    /// The method of a struct, `method = "..."`
+    method: Option<syn::LitStr>,
+    /// The name of the params wrapper of an enum, `params = "..."`
+    params: Option<syn::LitStr>,
+}
+/// Reads `#[transaction(method = "...", params = "...")]`, if present
+fn transaction_attributes(attrs: &[syn::Attribute]) -> syn::Result<Attributes> {
+    let mut attributes = Attributes::default();
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident("transaction")) {
+        let list = match attr.parse_meta()? {
+            syn::Meta::List(list) => list,
+            meta => return Err(syn::Error::new_spanned(meta, "expected #[transaction(method = \"...\")]")),
+        };
+        for nested in list.nested {
+            match nested {
+                syn::NestedMeta::Meta(syn::Meta::NameValue(syn::MetaNameValue { path, lit: syn::Lit::Str(lit), .. })) if path.is_ident("method") => attributes.method = Some(lit),
+                syn::NestedMeta::Meta(syn::Meta::NameValue(syn::MetaNameValue { path, lit: syn::Lit::Str(lit), .. })) if path.is_ident("params") => attributes.params = Some(lit),
+                other => return Err(syn::Error::new_spanned(other, "unknown transaction attribute, expected `method = \"...\"` or `params = \"...\"`")),
+            }
+        }
+    }
+    Ok(attributes)
+}
+/// Attribute kept for compatibility with older versions of icon-utils, it no longer does anything.
+///
+/// The serializer now sorts struct fields alphabetically at runtime, so fields can be declared in any order.
+/// This attribute used to reorder the fields of the struct itself, which broke positional construction.
+///
 /// # Example
-/// ```
+/// ```ignore
 /// #[derive(Transaction)]
 /// #[sort]
 /// struct ExampleTransaction {
@@ -49,28 +225,9 @@ pub fn transaction(input: TokenStream) -> TokenStream {
 /// ```
 #[proc_macro_attribute]
 pub fn sort(_args: TokenStream, input: TokenStream) -> TokenStream{
-    let mut ast = parse_macro_input!(input as syn::DeriveInput);
-    let ident = &ast.ident;
-    let data = &ast.data;
-    let fields = match &data {
-        syn::Data::Struct(syn::DataStruct { fields: syn::Fields::Named(fields), ..}) => &fields.named,
-        _ => panic!("Only structs with named fields are supported"),
-    };
-    let mut newFields: Vec<&syn::Field> = fields.iter().collect();
-    newFields.sort_by(|a, b| a.ident.as_ref().unwrap().cmp(&b.ident.as_ref().unwrap()));
-    let mut ast2 = ast.clone();
-    let mut data2 = &mut ast2.data;
-    match &mut data2 {
-        syn::Data::Struct(syn::DataStruct { fields: syn::Fields::Named(fields), ..}) => {
-            fields.named.clear();
-            for i in newFields {
-                fields.named.push(i.clone());
-            }
-        },
-        _ => panic!("Only structs with named fields are supported"),
-        
+    let ast = parse_macro_input!(input as syn::DeriveInput);
+    match named_fields(&ast.data, &ast.ident, "sort") {
+        Ok(_) => quote! { #ast }.into(),
+        Err(e) => e.to_compile_error().into(),
     }
-    return quote! {
-        #ast2
-    }.into()
 }
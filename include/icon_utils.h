@@ -0,0 +1,129 @@
+#ifndef ICON_UTILS_H
+#define ICON_UTILS_H
+
+/* Generated with cbindgen from src/ffi.rs, do not edit by hand */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * Status codes returned by every function
+ */
+typedef enum IconStatus {
+  ICON_STATUS_OK = 0,
+  ICON_STATUS_NULL_POINTER = 1,
+  ICON_STATUS_INVALID_UTF8 = 2,
+  ICON_STATUS_INVALID_KEY = 3,
+  ICON_STATUS_INVALID_TRANSACTION = 4,
+  ICON_STATUS_SERIALIZE_FAILED = 5,
+  ICON_STATUS_SIGN_FAILED = 6,
+} IconStatus;
+
+/**
+ * A wallet, created by the library and released with [`icon_wallet_free`]
+ */
+typedef struct IconWallet IconWallet;
+
+/**
+ * Bytes allocated by the library, followed by a NUL byte not counted in `len` so they can be read as a C string
+ */
+typedef struct IconBuffer {
+  uint8_t *data;
+  uintptr_t len;
+} IconBuffer;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Returns a static description of a status code
+ */
+const char *icon_status_message(enum IconStatus status);
+
+/**
+ * Creates a wallet with a new random private key
+ *
+ * # Safety
+ *
+ * `out` must be a valid pointer, the wallet written to it must be released with [`icon_wallet_free`].
+ */
+enum IconStatus icon_wallet_create(struct IconWallet **out);
+
+/**
+ * Creates a wallet from raw private key bytes
+ *
+ * # Safety
+ *
+ * `key` must point to `len` readable bytes and `out` must be a valid pointer,
+ * the wallet written to it must be released with [`icon_wallet_free`].
+ */
+enum IconStatus icon_wallet_from_private_key(const uint8_t *key,
+                                             uintptr_t len,
+                                             struct IconWallet **out);
+
+/**
+ * Writes the 32 byte private key of a wallet to `out`
+ *
+ * # Safety
+ *
+ * `wallet` must come from this library and `out` must point to 32 writable bytes.
+ */
+enum IconStatus icon_wallet_private_key(const struct IconWallet *wallet, uint8_t *out);
+
+/**
+ * Writes the hx address of a wallet to `out`
+ *
+ * # Safety
+ *
+ * `wallet` must come from this library and `out` must be a valid pointer.
+ */
+enum IconStatus icon_wallet_address(const struct IconWallet *wallet, struct IconBuffer *out);
+
+/**
+ * Releases a wallet, null is ignored
+ *
+ * # Safety
+ *
+ * `wallet` must be null or come from this library, and must not be used afterwards.
+ */
+void icon_wallet_free(struct IconWallet *wallet);
+
+/**
+ * Serializes a transaction given as the JSON of its `icx_sendTransaction` params, the string that gets hashed and signed
+ *
+ * # Safety
+ *
+ * `tx_json` must be a NUL terminated string and `out` must be a valid pointer.
+ */
+enum IconStatus icon_serialize_transaction(const char *tx_json,
+                                           struct IconBuffer *out);
+
+/**
+ * Signs a transaction given as the JSON of its `icx_sendTransaction` params,
+ * writing the JSON of the params with the signature added to `out`
+ *
+ * # Safety
+ *
+ * `wallet` must come from this library, `tx_json` must be a NUL terminated string and `out` must be a valid pointer.
+ */
+enum IconStatus icon_sign_transaction(const struct IconWallet *wallet,
+                                      const char *tx_json,
+                                      struct IconBuffer *out);
+
+/**
+ * Releases a buffer returned by the library, an empty buffer is ignored
+ *
+ * # Safety
+ *
+ * `buffer` must come from this library and must not be used afterwards.
+ */
+void icon_buffer_free(struct IconBuffer buffer);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* ICON_UTILS_H */
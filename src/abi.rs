@@ -0,0 +1,348 @@
+//! # Icon SCORE ABI
+//! `abi` is a module for reading SCORE APIs from `icx_getScoreApi` and generating typed Rust clients for them.
+//! The generated code is meant to be written from a build script and included with `include!`,
+//! it refers to `serde_json` for `dict` and `struct` values, so the including crate needs it as a dependency.
+//!
+//! # Example
+//!
+//! In `build.rs`, with the API saved from `IconClient::get_score_api`:
+//!  ```no_run
+//! # use icon_utils::abi::{self, ScoreApi};
+//! let api: ScoreApi = serde_json::from_str(&std::fs::read_to_string("token.json").unwrap()).unwrap();
+//! let out = std::path::Path::new(&std::env::var("OUT_DIR").unwrap()).join("token.rs");
+//! std::fs::write(out, abi::generate("Token", &api.entries)).unwrap();
+//! ```
+//! And in the crate:
+//!  ```ignore
+//! include!(concat!(env!("OUT_DIR"), "/token.rs"));
+//! ```
+use std::fmt::Write;
+
+use serde::{Deserialize, Serialize};
+
+/// The API of a SCORE, as returned by `icx_getScoreApi`
+///
+/// # Example
+///
+///  ```
+/// # use icon_utils::abi::ScoreApi;
+/// let api: ScoreApi = serde_json::from_value(serde_json::json!([
+///     {"type": "function", "name": "balanceOf", "inputs": [{"name": "_owner", "type": "Address"}],
+///      "outputs": [{"type": "int"}], "readonly": "0x1"},
+///     {"type": "function", "name": "transfer", "inputs": [{"name": "_to", "type": "Address"}, {"name": "_value", "type": "int"}]},
+///     {"type": "eventlog", "name": "Transfer", "inputs": [{"name": "_from", "type": "Address", "indexed": "0x1"}]}
+/// ])).unwrap();
+/// assert_eq!(api.functions().count(), 2);
+/// assert!(api.function("balanceOf").unwrap().is_readonly());
+/// assert!(api.eventlog("Transfer").unwrap().inputs[0].is_indexed());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ScoreApi {
+    pub entries: Vec<AbiEntry>,
+}
+
+impl ScoreApi {
+    /// Returns the functions of the SCORE
+    pub fn functions(&self) -> impl Iterator<Item = &AbiEntry> {
+        self.entries.iter().filter(|e| e.is_function())
+    }
+
+    /// Returns the read-only functions of the SCORE, called with `icx_call`
+    pub fn readonly_functions(&self) -> impl Iterator<Item = &AbiEntry> {
+        self.functions().filter(|e| e.is_readonly())
+    }
+
+    /// Returns the events the SCORE can emit
+    pub fn eventlogs(&self) -> impl Iterator<Item = &AbiEntry> {
+        self.entries.iter().filter(|e| e.is_eventlog())
+    }
+
+    /// Returns the function called `name`, if the SCORE has one
+    pub fn function(&self, name: &str) -> Option<&AbiEntry> {
+        self.functions().find(|e| e.name == name)
+    }
+
+    /// Returns the event called `name`, if the SCORE has one
+    pub fn eventlog(&self, name: &str) -> Option<&AbiEntry> {
+        self.eventlogs().find(|e| e.name == name)
+    }
+
+    /// Returns the fallback function, called when ICX is sent to the SCORE without a method
+    pub fn fallback(&self) -> Option<&AbiEntry> {
+        self.entries.iter().find(|e| e.entry_type == "fallback")
+    }
+}
+
+impl From<Vec<AbiEntry>> for ScoreApi {
+    fn from(entries: Vec<AbiEntry>) -> Self {
+        ScoreApi { entries }
+    }
+}
+
+/// An entry of a SCORE API, as returned by `icx_getScoreApi`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AbiEntry {
+    /// `function`, `eventlog` or `fallback`
+    #[serde(rename = "type")]
+    pub entry_type: String,
+    pub name: String,
+    #[serde(default)]
+    pub inputs: Vec<AbiParam>,
+    #[serde(default)]
+    pub outputs: Vec<AbiOutput>,
+    /// `0x1` for read-only functions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub readonly: Option<String>,
+    /// `0x1` for functions accepting ICX
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub payable: Option<String>,
+}
+
+impl AbiEntry {
+    /// Returns true for functions
+    pub fn is_function(&self) -> bool {
+        self.entry_type == "function"
+    }
+
+    /// Returns true for events
+    pub fn is_eventlog(&self) -> bool {
+        self.entry_type == "eventlog"
+    }
+
+    /// Returns true for read-only functions, called with `icx_call`
+    pub fn is_readonly(&self) -> bool {
+        self.readonly.as_deref() == Some("0x1")
+    }
+
+    /// Returns true for functions accepting ICX
+    pub fn is_payable(&self) -> bool {
+        self.payable.as_deref() == Some("0x1")
+    }
+}
+
+/// An input of a SCORE function or event
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AbiParam {
+    pub name: String,
+    /// `int`, `str`, `bytes`, `bool`, `Address`, `struct`, or one of those prefixed with `[]` for lists
+    #[serde(rename = "type")]
+    pub param_type: String,
+    /// Present when the param is optional, the default itself may be null
+    #[serde(default, deserialize_with = "present", skip_serializing_if = "Option::is_none")]
+    pub default: Option<serde_json::Value>,
+    /// `0x1` for indexed event params
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub indexed: Option<String>,
+}
+
+impl AbiParam {
+    /// Returns true for indexed event params
+    pub fn is_indexed(&self) -> bool {
+        self.indexed.as_deref() == Some("0x1")
+    }
+
+    /// Returns true for optional params, which have a default
+    pub fn is_optional(&self) -> bool {
+        self.default.is_some()
+    }
+}
+
+/// Deserializes a field that is Some whenever it is present, even when it is null
+fn present<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Option<serde_json::Value>, D::Error> {
+    serde_json::Value::deserialize(deserializer).map(Some)
+}
+
+/// An output of a SCORE function
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AbiOutput {
+    #[serde(rename = "type")]
+    pub output_type: String,
+}
+
+/// Generates the source of a typed client named `name` for a SCORE API
+///
+/// Read-only functions become methods returning the decoded result, other functions become methods returning a
+/// `CallTransactionBuilder`. ICON types map to `i128`, `&str`, `&[u8]`, `bool` and `&Address`, as `int` is signed,
+/// optional params become `Option`s and are left out when None.
+///
+/// # Example
+///
+///  ```
+/// # use icon_utils::abi::{self, AbiEntry};
+/// let api: Vec<AbiEntry> = serde_json::from_value(serde_json::json!([
+///     {"type": "function", "name": "balanceOf", "inputs": [{"name": "_owner", "type": "Address"}],
+///      "outputs": [{"type": "int"}], "readonly": "0x1"}
+/// ])).unwrap();
+/// let source = abi::generate("Token", &api);
+/// assert!(source.contains("pub fn balance_of(&self, owner: &::icon_utils::types::Address)"));
+/// ```
+pub fn generate(name: &str, api: &[AbiEntry]) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "/// Typed client for the `{}` SCORE, generated from its API", name);
+    let _ = writeln!(out, "pub struct {}<'a, T = ::icon_utils::rpc::HttpTransport> {{", name);
+    out.push_str("    client: &'a ::icon_utils::rpc::IconClient<T>,\n");
+    out.push_str("    address: ::icon_utils::types::Address,\n");
+    out.push_str("}\n\n");
+    let _ = writeln!(out, "impl<'a, T: ::icon_utils::rpc::Transport> {}<'a, T> {{", name);
+    out.push_str("    /// Creates a client for the SCORE at `address`\n");
+    out.push_str("    pub fn new(client: &'a ::icon_utils::rpc::IconClient<T>, address: ::icon_utils::types::Address) -> Self {\n");
+    let _ = writeln!(out, "        {} {{ client, address }}", name);
+    out.push_str("    }\n\n");
+    out.push_str("    /// Returns the address of the SCORE\n");
+    out.push_str("    pub fn address(&self) -> &::icon_utils::types::Address {\n");
+    out.push_str("        &self.address\n");
+    out.push_str("    }\n");
+    for entry in api.iter().filter(|e| e.is_function()) {
+        out.push('\n');
+        generate_function(&mut out, entry);
+    }
+    out.push_str("}\n");
+    out
+}
+
+fn generate_function(out: &mut String, entry: &AbiEntry) {
+    let args: Vec<(String, &AbiParam)> = entry
+        .inputs
+        .iter()
+        .enumerate()
+        .map(|(i, param)| (argument_name(&param.name, i), param))
+        .collect();
+    let signature: Vec<String> = args
+        .iter()
+        .map(|(arg, param)| match param.default {
+            Some(_) => format!("{}: Option<{}>", arg, input_type(&param.param_type)),
+            None => format!("{}: {}", arg, input_type(&param.param_type)),
+        })
+        .collect();
+    let has_optional = args.iter().any(|(_, param)| param.default.is_some());
+    let method = rust_ident(&snake_case(&entry.name));
+    let (doc, return_type, start) = if entry.is_readonly() {
+        (
+            format!("Calls the read-only `{}` method", entry.name),
+            format!("Result<{}, ::icon_utils::rpc::RpcError>", output_type(&entry.outputs)),
+            format!("::icon_utils::rpc::CallBuilder::new(&self.address.to_string(), {:?})", entry.name),
+        )
+    } else {
+        (
+            format!("Starts a `{}` call transaction{}", entry.name, if entry.is_payable() { ", which accepts ICX" } else { "" }),
+            "::icon_utils::transaction::CallTransactionBuilder".to_owned(),
+            format!("::icon_utils::transaction::CallTransactionBuilder::new().to(self.address).method({:?})", entry.name),
+        )
+    };
+    let _ = writeln!(out, "    /// {}", doc);
+    let _ = writeln!(out, "    pub fn {}(&self{}{}) -> {} {{", method, if signature.is_empty() { "" } else { ", " }, signature.join(", "), return_type);
+    // Transactions without optional params are returned directly, the others are built up in `call`
+    let direct = !entry.is_readonly() && !has_optional;
+    if direct {
+        let _ = write!(out, "        {}", start);
+    } else {
+        let _ = write!(out, "        let {}call = {}", if has_optional { "mut " } else { "" }, start);
+    }
+    for (arg, param) in args.iter().filter(|(_, param)| param.default.is_none()) {
+        let _ = write!(out, "\n            .param({:?}, {})", param.name, arg);
+    }
+    out.push_str(if direct { "\n" } else { ";\n" });
+    for (arg, param) in args.iter().filter(|(_, param)| param.default.is_some()) {
+        let _ = writeln!(out, "        if let Some({0}) = {0} {{", arg);
+        let _ = writeln!(out, "            call = call.param({:?}, {});", param.name, arg);
+        out.push_str("        }\n");
+    }
+    if entry.is_readonly() {
+        out.push_str("        self.client.call(&call)\n");
+    } else if !direct {
+        out.push_str("        call\n");
+    }
+    out.push_str("    }\n");
+}
+
+/// Maps an ICON input type to the Rust type of the generated argument
+fn input_type(param_type: &str) -> String {
+    match param_type.strip_prefix("[]") {
+        Some(inner) => format!("Vec<{}>", owned_type(inner)),
+        None => match param_type {
+            "int" => "i128".to_owned(),
+            "str" => "&str".to_owned(),
+            "bytes" => "&[u8]".to_owned(),
+            "bool" => "bool".to_owned(),
+            "Address" => "&::icon_utils::types::Address".to_owned(),
+            _ => "::serde_json::Value".to_owned(),
+        },
+    }
+}
+
+/// Maps an ICON type to an owned Rust type, used for list elements and outputs
+fn owned_type(icon_type: &str) -> String {
+    match icon_type {
+        "int" => "i128",
+        "str" => "String",
+        "bytes" => "::icon_utils::types::HexBytes",
+        "bool" => "bool",
+        "Address" => "::icon_utils::types::Address",
+        "list" => "Vec<::serde_json::Value>",
+        _ => "::serde_json::Value",
+    }
+    .to_owned()
+}
+
+fn output_type(outputs: &[AbiOutput]) -> String {
+    match outputs.first() {
+        Some(output) => match output.output_type.strip_prefix("[]") {
+            Some(inner) => format!("Vec<{}>", owned_type(inner)),
+            None => owned_type(&output.output_type),
+        },
+        None => "::serde_json::Value".to_owned(),
+    }
+}
+
+/// Names an argument after its param, without the leading underscores SCOREs commonly use
+fn argument_name(name: &str, index: usize) -> String {
+    let name = snake_case(name.trim_start_matches('_'));
+    if name.is_empty() {
+        format!("arg{}", index)
+    } else {
+        rust_ident(&name)
+    }
+}
+
+/// Converts a camelCase name to snake_case
+fn snake_case(name: &str) -> String {
+    let mut out = String::new();
+    let chars: Vec<char> = name.chars().collect();
+    for (i, c) in chars.iter().enumerate() {
+        if c.is_uppercase() {
+            let prev_lower = i > 0 && (chars[i - 1].is_lowercase() || chars[i - 1].is_ascii_digit());
+            let next_lower = chars.get(i + 1).is_some_and(|n| n.is_lowercase());
+            let prev_upper = i > 0 && chars[i - 1].is_uppercase();
+            if i > 0 && (prev_lower || (prev_upper && next_lower)) && !out.ends_with('_') {
+                out.push('_');
+            }
+            out.extend(c.to_lowercase());
+        } else if c.is_alphanumeric() || *c == '_' {
+            out.push(*c);
+        } else {
+            out.push('_');
+        }
+    }
+    out
+}
+
+/// Escapes Rust keywords as raw identifiers, and suffixes the ones that can't be raw identifiers with `_`
+fn rust_ident(name: &str) -> String {
+    // The strict keywords of every edition and the reserved ones
+    const KEYWORDS: [&str; 48] = [
+        "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do", "dyn", "else", "enum",
+        "extern", "false", "final", "fn", "for", "gen", "if", "impl", "in", "let", "loop", "macro", "match", "mod",
+        "move", "mut", "override", "priv", "pub", "ref", "return", "static", "struct", "trait", "true", "try", "type",
+        "typeof", "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+    ];
+    const NOT_RAW: [&str; 5] = ["self", "Self", "super", "crate", "_"];
+    let name = if name.starts_with(|c: char| c.is_ascii_digit()) { format!("_{}", name) } else { name.to_owned() };
+    if NOT_RAW.contains(&name.as_str()) {
+        format!("{}_", name)
+    } else if KEYWORDS.contains(&name.as_str()) {
+        format!("r#{}", name)
+    } else {
+        name
+    }
+}
@@ -0,0 +1,244 @@
+//! # Icon CLI
+//! `icon-cli` is a command line tool wrapping icon-utils, for creating wallets, signing and sending transactions and reading from the network.
+//! Build it with `cargo install icon_utils --features cli`.
+use std::fs::OpenOptions;
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::{Parser, Subcommand};
+use rand::RngCore;
+use serde_json::Value;
+
+use icon_utils::keystore::{KdfParams, Keystore};
+use icon_utils::network::Network;
+use icon_utils::rpc::{CallBuilder, IconClient};
+use icon_utils::transaction::IcxTransaction;
+use icon_utils::types::Address;
+use icon_utils::wallet::Wallet;
+
+type Error = Box<dyn std::error::Error>;
+
+#[derive(Parser)]
+#[command(name = "icon-cli", version, about = "Command line tool for the ICON network")]
+struct Cli {
+    /// Network to connect to: mainnet, lisbon, berlin or sejong
+    #[arg(long, global = true, default_value = "mainnet")]
+    network: String,
+    /// JSON-RPC endpoint, overrides the default endpoint of the network
+    #[arg(long, global = true)]
+    endpoint: Option<String>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Creates and inspects wallets
+    #[command(subcommand)]
+    Wallet(WalletCommand),
+    /// Signs a transaction read from a JSON file and prints the signed params
+    Sign {
+        /// The `icx_sendTransaction` params, as JSON
+        tx: PathBuf,
+        #[command(flatten)]
+        key: KeyArgs,
+    },
+    /// Signs a transaction read from a JSON file and sends it, printing its tx hash
+    Send {
+        /// The `icx_sendTransaction` params, as JSON
+        tx: PathBuf,
+        #[command(flatten)]
+        key: KeyArgs,
+    },
+    /// Prints a summary of a transaction read from a JSON file, to review it before signing
+    Describe {
+        /// The `icx_sendTransaction` params, as JSON
+        tx: PathBuf,
+    },
+    /// Prints the ICX balance of an address
+    Balance { address: Address },
+    /// Calls a read-only SCORE method and prints the result
+    Call {
+        score: Address,
+        method: String,
+        /// Params of the call, as `name=value`
+        #[arg(long = "param", value_parser = parse_param)]
+        params: Vec<(String, String)>,
+    },
+}
+
+#[derive(Subcommand)]
+enum WalletCommand {
+    /// Creates a new random wallet, printing its address
+    Create {
+        /// Writes the wallet to an encrypted keystore instead of printing its private key
+        #[arg(long)]
+        keystore: Option<PathBuf>,
+        #[arg(long, env = "ICON_PASSWORD", hide_env_values = true)]
+        password: Option<String>,
+        /// Overwrites the keystore if the file exists
+        #[arg(long)]
+        force: bool,
+    },
+    /// Encrypts a hex private key into a keystore
+    Keystore {
+        /// Hex private key, read from stdin if missing
+        #[arg(long, env = "ICON_PRIVATE_KEY", hide_env_values = true)]
+        key: Option<String>,
+        /// Path of the keystore to write
+        #[arg(long)]
+        out: PathBuf,
+        #[arg(long, env = "ICON_PASSWORD", hide_env_values = true)]
+        password: Option<String>,
+        /// Overwrites the keystore if the file exists
+        #[arg(long)]
+        force: bool,
+    },
+    /// Prints the address of a keystore
+    Address {
+        keystore: PathBuf,
+    },
+}
+
+/// The keystore a transaction is signed with
+#[derive(clap::Args)]
+struct KeyArgs {
+    /// Keystore of the signing wallet
+    #[arg(long)]
+    keystore: PathBuf,
+    #[arg(long, env = "ICON_PASSWORD", hide_env_values = true)]
+    password: Option<String>,
+}
+
+impl KeyArgs {
+    fn wallet(&self) -> Result<Wallet, Error> {
+        let keystore = Keystore::from_json(&std::fs::read_to_string(&self.keystore)?)?;
+        let password = password(self.password.clone())?;
+        Ok(keystore.decrypt(&password)?)
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match run(cli) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(cli: Cli) -> Result<(), Error> {
+    let client = || -> Result<IconClient, Error> {
+        Ok(match &cli.endpoint {
+            Some(endpoint) => IconClient::new(endpoint),
+            None => IconClient::for_network(&network(&cli.network)?),
+        })
+    };
+    match &cli.command {
+        Command::Wallet(WalletCommand::Create { keystore, password: pass, force }) => {
+            let mut key = [0u8; 32];
+            rand::rngs::OsRng.fill_bytes(&mut key);
+            let wallet = Wallet::from_bytes(&key)?;
+            match keystore {
+                Some(path) => write_keystore(&wallet, path, password(pass.clone())?, *force)?,
+                None => println!("private key: {}", hex::encode(key)),
+            }
+            println!("address: {}", wallet.address());
+        }
+        Command::Wallet(WalletCommand::Keystore { key, out, password: pass, force }) => {
+            let key = match key {
+                Some(key) => key.clone(),
+                None => prompt("private key: ")?,
+            };
+            let wallet = Wallet::new(key.trim().trim_start_matches("0x"))?;
+            write_keystore(&wallet, out, password(pass.clone())?, *force)?;
+            println!("address: {}", wallet.address());
+        }
+        Command::Wallet(WalletCommand::Address { keystore }) => {
+            let keystore = Keystore::from_json(&std::fs::read_to_string(keystore)?)?;
+            println!("{}", keystore.address);
+        }
+        Command::Sign { tx, key } => {
+            let signed = key.wallet()?.sign_transaction(&read_transaction(tx)?)?;
+            println!("{}", serde_json::to_string_pretty(&signed.to_rpc_params())?);
+        }
+        Command::Send { tx, key } => {
+            let signed = key.wallet()?.sign_transaction(&read_transaction(tx)?)?;
+            println!("{}", client()?.send_transaction(&signed)?);
+        }
+        Command::Describe { tx } => {
+            println!("{}", read_transaction(tx)?.describe());
+        }
+        Command::Balance { address } => {
+            println!("{}", client()?.get_balance(address)?.to_icx());
+        }
+        Command::Call { score, method, params } => {
+            let call = params
+                .iter()
+                .fold(CallBuilder::new(&score.to_string(), method), |call, (name, value)| call.param(name, value.as_str()));
+            let result: Value = client()?.call(&call)?;
+            println!("{}", serde_json::to_string_pretty(&result)?);
+        }
+    }
+    Ok(())
+}
+
+fn network(name: &str) -> Result<Network, Error> {
+    match name.to_ascii_lowercase().as_str() {
+        "mainnet" => Ok(Network::Mainnet),
+        "lisbon" => Ok(Network::Lisbon),
+        "berlin" => Ok(Network::Berlin),
+        "sejong" => Ok(Network::Sejong),
+        _ => Err(format!("unknown network {}, use --endpoint for other networks", name).into()),
+    }
+}
+
+fn parse_param(param: &str) -> Result<(String, String), String> {
+    param
+        .split_once('=')
+        .map(|(name, value)| (name.to_owned(), value.to_owned()))
+        .ok_or_else(|| format!("expected name=value, got {}", param))
+}
+
+fn read_transaction(path: &PathBuf) -> Result<IcxTransaction, Error> {
+    Ok(serde_json::from_str(&std::fs::read_to_string(path)?)?)
+}
+
+/// Writes a new keystore, failing if the file exists unless `force` is set, so an existing key isn't destroyed
+fn write_keystore(wallet: &Wallet, path: &PathBuf, password: String, force: bool) -> Result<(), Error> {
+    let keystore = Keystore::create(wallet, &password, &KdfParams::default())?;
+    let mut options = OpenOptions::new();
+    options.write(true);
+    if force {
+        options.create(true).truncate(true);
+    } else {
+        options.create_new(true);
+    }
+    let mut file = options.open(path).map_err(|e| -> Error {
+        match e.kind() {
+            io::ErrorKind::AlreadyExists => format!("{} already exists, pass --force to overwrite it", path.display()).into(),
+            _ => e.into(),
+        }
+    })?;
+    file.write_all(keystore.to_json()?.as_bytes())?;
+    Ok(())
+}
+
+/// Returns the password given as an argument, or reads it from stdin
+fn password(password: Option<String>) -> Result<String, Error> {
+    match password {
+        Some(password) => Ok(password),
+        None => prompt("password: "),
+    }
+}
+
+fn prompt(message: &str) -> Result<String, Error> {
+    eprint!("{}", message);
+    io::stderr().flush()?;
+    let mut line = String::new();
+    io::stdin().lock().read_line(&mut line)?;
+    Ok(line.trim_end_matches(['\r', '\n']).to_owned())
+}
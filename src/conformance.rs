@@ -0,0 +1,140 @@
+//! # Icon Conformance
+//! `conformance` is a module for checking the serializer against test vectors from other ICON SDKs.
+//! A vector holds the JSON params of a transaction with the serialized string and hash every SDK has to produce for them,
+//! so forks of this crate, or of its serializer, can verify they stay byte-compatible with the network.
+//!
+//! Reference vectors are embedded with the `conformance` feature. They were written to the serialization rules of the
+//! JSON-RPC v3 specification and their expected strings and hashes computed by a separate Python implementation of
+//! those rules, not by this crate. Each vector names its `source`, so vectors taken from another SDK's serializer
+//! tests can be told apart from them, and files exported from those test suites load as they are.
+//!
+//! # Example
+//!
+//!  ```
+//! # use icon_utils::conformance::{check_vector, load_vectors};
+//! let vectors = load_vectors(r#"[{
+//!     "name": "message",
+//!     "params": {"data": "0x68656c6c6f", "dataType": "message", "nid": "0x1", "version": "0x3"},
+//!     "serialized": "icx_sendTransaction.params.{data.0x68656c6c6f.dataType.message.nid.0x1.version.0x3}",
+//!     "hash": "0x2508df01dad71d08e070020716b18b8378ea5e8c672229e5702b8495f7f81a91"
+//! }]"#).unwrap();
+//! for vector in &vectors {
+//!     check_vector(vector).unwrap();
+//! }
+//! ```
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha3::{Digest, Sha3_256};
+use thiserror::Error;
+
+use crate::serializer::{serialize_to_string, SerializeError, Transaction};
+use crate::types::Hash256;
+
+#[derive(Error, Debug)]
+// Error struct for loading and checking test vectors
+pub enum ConformanceError {
+    #[error("Failed to read test vectors: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Invalid test vectors: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Vector {name} failed to serialize: {source}")]
+    Serialize { name: String, source: SerializeError },
+    #[error("Vector {name} serialized to {actual}, expected {expected}")]
+    Serialized { name: String, expected: String, actual: String },
+    #[error("Vector {name} hashed to {actual}, expected {expected}")]
+    Hash { name: String, expected: Hash256, actual: Hash256 },
+}
+
+/// A transaction and the serialized string and hash it has to produce
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TestVector {
+    pub name: String,
+    /// Where the vector comes from, such as the SDK test suite it was taken from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// The JSON-RPC method, `icx_sendTransaction` if the vector leaves it out
+    #[serde(default = "default_method")]
+    pub method: String,
+    /// The params as they are sent to the node, without a signature
+    pub params: Value,
+    pub serialized: String,
+    /// The SHA3-256 hash of `serialized`, which is the transaction hash
+    pub hash: Hash256,
+}
+
+fn default_method() -> String {
+    "icx_sendTransaction".to_owned()
+}
+
+impl Transaction for TestVector {
+    type Params = Value;
+    fn params(&self) -> &Value {
+        &self.params
+    }
+    fn method(&self) -> &String {
+        &self.method
+    }
+}
+
+/// The vectors either form a top level array or are under `vectors`, as in the files of the SDKs that nest them
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum VectorFile {
+    List(Vec<TestVector>),
+    Nested { vectors: Vec<TestVector> },
+}
+
+/// Parses test vectors from JSON
+pub fn load_vectors(json: &str) -> Result<Vec<TestVector>, ConformanceError> {
+    Ok(match serde_json::from_str(json)? {
+        VectorFile::List(vectors) | VectorFile::Nested { vectors } => vectors,
+    })
+}
+
+/// Reads test vectors from a JSON file
+pub fn load_vectors_from_file(path: impl AsRef<Path>) -> Result<Vec<TestVector>, ConformanceError> {
+    load_vectors(&fs::read_to_string(path)?)
+}
+
+/// Serializes the params of `vector`, failing unless both the serialized string and its hash match
+///
+/// The hash is checked separately so a vector with a wrong hash is reported, rather than passing on the string alone.
+pub fn check_vector(vector: &TestVector) -> Result<(), ConformanceError> {
+    let serialized = serialize_to_string(vector)
+        .map_err(|source| ConformanceError::Serialize { name: vector.name.clone(), source })?;
+    if serialized != vector.serialized {
+        return Err(ConformanceError::Serialized {
+            name: vector.name.clone(),
+            expected: vector.serialized.clone(),
+            actual: serialized,
+        });
+    }
+    let hash = Hash256(Sha3_256::digest(serialized.as_bytes()).into());
+    if hash != vector.hash {
+        return Err(ConformanceError::Hash { name: vector.name.clone(), expected: vector.hash, actual: hash });
+    }
+    Ok(())
+}
+
+/// Checks every vector, returning the ones that failed
+pub fn check_vectors(vectors: &[TestVector]) -> Vec<ConformanceError> {
+    vectors.iter().filter_map(|vector| check_vector(vector).err()).collect()
+}
+
+/// Returns the embedded reference vectors: transfers, SCORE calls with escaped strings and keys, hex integers, nested
+/// arrays and null params, messages, deploys and deposits
+///
+/// # Example
+///
+///  ```
+/// # use icon_utils::conformance::{check_vectors, reference_vectors};
+/// let failures = check_vectors(&reference_vectors());
+/// assert!(failures.is_empty(), "{:?}", failures);
+/// ```
+#[cfg(feature = "conformance")]
+pub fn reference_vectors() -> Vec<TestVector> {
+    load_vectors(include_str!("conformance/reference.json")).expect("embedded vectors are valid")
+}
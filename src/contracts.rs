@@ -0,0 +1,6 @@
+//! # Icon Contracts
+//! `contracts` holds typed helpers for SCOREs implementing the common ICON token standards, and for the standard multisig wallet SCORE.
+pub mod irc2;
+pub mod irc3;
+pub mod irc31;
+pub mod multisig;
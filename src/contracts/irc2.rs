@@ -0,0 +1,89 @@
+//! Helpers for IRC-2 fungible token SCOREs.
+use crate::params::IntoParam;
+use crate::rpc::{CallBuilder, HttpTransport, IconClient, RpcError, Transport};
+use crate::serializer::BigNum;
+use crate::transaction::CallTransactionBuilder;
+use crate::types::Address;
+
+/// An IRC-2 token SCORE
+///
+/// # Example
+///
+///  ```no_run
+/// # use icon_utils::{contracts::irc2::Irc2Token, rpc::IconClient, wallet::Wallet};
+/// let client = IconClient::new("https://api.icon.community/api/v3");
+/// let token = Irc2Token::new(&client, "cx88fd7df7ddff82f7cc735c871dc519838cb235bb".parse().unwrap());
+/// let wallet = Wallet::new("68ee9ca94b71c42ba79375b8677e29a717fb8072bcb17cd5ee288f9b77bc2894").unwrap();
+/// println!("{} balance: {}", token.symbol().unwrap(), token.balance_of(&wallet.address().parse().unwrap()).unwrap());
+///
+/// let tx = token
+///     .transfer(&"hx5bfdb090f43a808005ffc27c25b213145e80b7cd".parse().unwrap(), 1_000u64)
+///     .from(wallet.address())
+///     .step_limit(200_000)
+///     .nid(1)
+///     .build()
+///     .unwrap();
+/// let signed = wallet.sign_transaction(&tx).unwrap();
+/// client.send_transaction(&signed).unwrap();
+/// ```
+pub struct Irc2Token<'a, T = HttpTransport> {
+    client: &'a IconClient<T>,
+    address: Address,
+}
+
+impl<'a, T: Transport> Irc2Token<'a, T> {
+    /// Creates a helper for the token SCORE at `address`
+    pub fn new(client: &'a IconClient<T>, address: Address) -> Self {
+        Irc2Token { client, address }
+    }
+
+    /// Returns the address of the token SCORE
+    pub fn address(&self) -> &Address {
+        &self.address
+    }
+
+    /// Returns the name of the token
+    pub fn name(&self) -> Result<String, RpcError> {
+        self.client.call(&self.query("name"))
+    }
+
+    /// Returns the symbol of the token
+    pub fn symbol(&self) -> Result<String, RpcError> {
+        self.client.call(&self.query("symbol"))
+    }
+
+    /// Returns the number of decimals of the token
+    pub fn decimals(&self) -> Result<u8, RpcError> {
+        self.client.call(&self.query("decimals"))
+    }
+
+    /// Returns the total supply of the token, in its smallest unit
+    pub fn total_supply(&self) -> Result<BigNum, RpcError> {
+        self.client.call(&self.query("totalSupply"))
+    }
+
+    /// Returns the token balance of `owner`, in its smallest unit
+    pub fn balance_of(&self, owner: &Address) -> Result<BigNum, RpcError> {
+        self.client.call(&self.query("balanceOf").param("_owner", owner))
+    }
+
+    /// Starts a `transfer` call transaction sending `value` tokens to `to`
+    ///
+    /// The sender, step limit and network still need to be set before building and signing it.
+    pub fn transfer(&self, to: &Address, value: impl Into<BigNum>) -> CallTransactionBuilder {
+        CallTransactionBuilder::new()
+            .to(self.address)
+            .method("transfer")
+            .param("_to", to)
+            .param("_value", value.into())
+    }
+
+    /// Starts a `transfer` call transaction with `data` passed to the receiving SCORE's `tokenFallback`
+    pub fn transfer_with_data(&self, to: &Address, value: impl Into<BigNum>, data: &[u8]) -> CallTransactionBuilder {
+        self.transfer(to, value).param("_data", data.into_param())
+    }
+
+    fn query(&self, method: &str) -> CallBuilder {
+        CallBuilder::new(&self.address.to_string(), method)
+    }
+}
@@ -0,0 +1,99 @@
+//! Helpers for IRC-3 non-fungible token SCOREs.
+use serde::Deserialize;
+
+use crate::impl_event;
+use crate::rpc::{CallBuilder, HttpTransport, IconClient, RpcError, Transport};
+use crate::serializer::BigNum;
+use crate::transaction::CallTransactionBuilder;
+use crate::types::Address;
+
+/// An IRC-3 NFT SCORE
+///
+/// # Example
+///
+///  ```no_run
+/// # use icon_utils::{contracts::irc3::{Irc3Token, Irc3Transfer}, events, rpc::IconClient};
+/// let client = IconClient::new("https://api.icon.community/api/v3");
+/// let nft = Irc3Token::new(&client, "cx88fd7df7ddff82f7cc735c871dc519838cb235bb".parse().unwrap());
+/// let owner = nft.owner_of(7u64).unwrap();
+///
+/// let hash = "0x2e8e5c6f8a9e4c1b5d3f7a0b2c4d6e8f1a3b5c7d9e0f2a4b6c8d0e2f4a6b8c0d".parse().unwrap();
+/// let receipt = client.get_transaction_result(&hash).unwrap();
+/// for transfer in events::decode_all::<Irc3Transfer>(&receipt.event_logs).unwrap() {
+///     println!("token {} sent from {} to {}", transfer.token_id, transfer.from, transfer.to);
+/// }
+/// ```
+pub struct Irc3Token<'a, T = HttpTransport> {
+    client: &'a IconClient<T>,
+    address: Address,
+}
+
+impl<'a, T: Transport> Irc3Token<'a, T> {
+    /// Creates a helper for the NFT SCORE at `address`
+    pub fn new(client: &'a IconClient<T>, address: Address) -> Self {
+        Irc3Token { client, address }
+    }
+
+    /// Returns the address of the NFT SCORE
+    pub fn address(&self) -> &Address {
+        &self.address
+    }
+
+    /// Returns the name of the collection
+    pub fn name(&self) -> Result<String, RpcError> {
+        self.client.call(&self.query("name"))
+    }
+
+    /// Returns the symbol of the collection
+    pub fn symbol(&self) -> Result<String, RpcError> {
+        self.client.call(&self.query("symbol"))
+    }
+
+    /// Returns the number of tokens owned by `owner`
+    pub fn balance_of(&self, owner: &Address) -> Result<u64, RpcError> {
+        self.client.call(&self.query("balanceOf").param("_owner", owner))
+    }
+
+    /// Returns the owner of the token `token_id`
+    pub fn owner_of(&self, token_id: impl Into<BigNum>) -> Result<Address, RpcError> {
+        self.client.call(&self.query("ownerOf").param("_tokenId", token_id.into()))
+    }
+
+    /// Returns the id of the token at `index` among the tokens owned by `owner`
+    pub fn token_of_owner_by_index(&self, owner: &Address, index: u64) -> Result<BigNum, RpcError> {
+        self.client.call(&self.query("tokenOfOwnerByIndex").param("_owner", owner).param("_index", index))
+    }
+
+    /// Starts a `transfer` call transaction sending the caller's token `token_id` to `to`
+    pub fn transfer(&self, to: &Address, token_id: impl Into<BigNum>) -> CallTransactionBuilder {
+        CallTransactionBuilder::new()
+            .to(self.address)
+            .method("transfer")
+            .param("_to", to)
+            .param("_tokenId", token_id.into())
+    }
+
+    /// Starts a `transferFrom` call transaction sending token `token_id` from `from` to `to`, as an approved operator
+    pub fn transfer_from(&self, from: &Address, to: &Address, token_id: impl Into<BigNum>) -> CallTransactionBuilder {
+        CallTransactionBuilder::new()
+            .to(self.address)
+            .method("transferFrom")
+            .param("_from", from)
+            .param("_to", to)
+            .param("_tokenId", token_id.into())
+    }
+
+    fn query(&self, method: &str) -> CallBuilder {
+        CallBuilder::new(&self.address.to_string(), method)
+    }
+}
+
+/// The IRC-3 `Transfer(Address,Address,int)` event
+#[derive(Debug, Clone, Deserialize)]
+pub struct Irc3Transfer {
+    pub from: Address,
+    pub to: Address,
+    pub token_id: BigNum,
+}
+
+impl_event!(Irc3Transfer, "Transfer(Address,Address,int)");
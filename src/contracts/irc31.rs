@@ -0,0 +1,87 @@
+//! Helpers for IRC-31 multi-token SCOREs.
+use crate::rpc::{CallBuilder, HttpTransport, IconClient, RpcError, Transport};
+use crate::serializer::BigNum;
+use crate::transaction::CallTransactionBuilder;
+use crate::types::Address;
+
+/// An IRC-31 multi-token SCORE
+///
+/// # Example
+///
+///  ```no_run
+/// # use icon_utils::{contracts::irc31::Irc31Token, rpc::IconClient};
+/// let client = IconClient::new("https://api.icon.community/api/v3");
+/// let token = Irc31Token::new(&client, "cx88fd7df7ddff82f7cc735c871dc519838cb235bb".parse().unwrap());
+/// let owner = "hxbe258ceb872e08851f1f59694dac2558708ece11".parse().unwrap();
+/// let balances = token.balance_of_batch(&[owner, owner], &[1u64.into(), 2u64.into()]).unwrap();
+/// println!("{}", token.token_uri(1u64).unwrap());
+/// ```
+pub struct Irc31Token<'a, T = HttpTransport> {
+    client: &'a IconClient<T>,
+    address: Address,
+}
+
+impl<'a, T: Transport> Irc31Token<'a, T> {
+    /// Creates a helper for the multi-token SCORE at `address`
+    pub fn new(client: &'a IconClient<T>, address: Address) -> Self {
+        Irc31Token { client, address }
+    }
+
+    /// Returns the address of the multi-token SCORE
+    pub fn address(&self) -> &Address {
+        &self.address
+    }
+
+    /// Returns the balance of token `id` held by `owner`
+    pub fn balance_of(&self, owner: &Address, id: impl Into<BigNum>) -> Result<BigNum, RpcError> {
+        self.client.call(&self.query("balanceOf").param("_owner", owner).param("_id", id.into()))
+    }
+
+    /// Returns the balances of each pair of `owners` and `ids`
+    pub fn balance_of_batch(&self, owners: &[Address], ids: &[BigNum]) -> Result<Vec<BigNum>, RpcError> {
+        let call = self
+            .query("balanceOfBatch")
+            .param("_owners", owners.iter().collect::<Vec<_>>())
+            .param("_ids", ids.to_vec());
+        self.client.call(&call)
+    }
+
+    /// Returns the metadata URI of token `id`
+    pub fn token_uri(&self, id: impl Into<BigNum>) -> Result<String, RpcError> {
+        self.client.call(&self.query("tokenURI").param("_id", id.into()))
+    }
+
+    /// Starts a `transferFrom` call transaction sending `value` of token `id` from `from` to `to`
+    ///
+    /// Data passed to the receiving SCORE can be added with `.param("_data", ...)`.
+    pub fn transfer_from(
+        &self,
+        from: &Address,
+        to: &Address,
+        id: impl Into<BigNum>,
+        value: impl Into<BigNum>,
+    ) -> CallTransactionBuilder {
+        CallTransactionBuilder::new()
+            .to(self.address)
+            .method("transferFrom")
+            .param("_from", from)
+            .param("_to", to)
+            .param("_id", id.into())
+            .param("_value", value.into())
+    }
+
+    /// Starts a `transferFromBatch` call transaction sending each of `values` of the matching `ids` from `from` to `to`
+    pub fn transfer_from_batch(&self, from: &Address, to: &Address, ids: &[BigNum], values: &[BigNum]) -> CallTransactionBuilder {
+        CallTransactionBuilder::new()
+            .to(self.address)
+            .method("transferFromBatch")
+            .param("_from", from)
+            .param("_to", to)
+            .param("_ids", ids.to_vec())
+            .param("_values", values.to_vec())
+    }
+
+    fn query(&self, method: &str) -> CallBuilder {
+        CallBuilder::new(&self.address.to_string(), method)
+    }
+}
@@ -0,0 +1,312 @@
+//! Helpers for the ICON multisig wallet SCORE.
+use serde::Deserialize;
+use serde_json::{json, Value};
+use thiserror::Error;
+
+use crate::rpc::{CallBuilder, HttpTransport, IconClient, RpcError, Transport};
+use crate::serializer::BigNum;
+use crate::transaction::CallTransactionBuilder;
+use crate::types::Address;
+use crate::units::Loop;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+// Error struct for decoding multisig params
+pub enum MultisigError {
+    #[error("Invalid _params JSON: {0}")]
+    Json(String),
+    #[error("Unknown type {kind} for param {name}")]
+    UnknownType { name: String, kind: String },
+    #[error("Invalid {kind} value {value} for param {name}")]
+    InvalidValue { name: String, kind: String, value: String },
+}
+
+/// A value passed to the method a multisig transaction calls
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MultisigValue {
+    Int(BigNum),
+    Str(String),
+    Bool(bool),
+    Address(Address),
+    Bytes(Vec<u8>),
+}
+
+impl MultisigValue {
+    /// Returns the type name the multisig SCORE uses for the value
+    pub fn kind(&self) -> &'static str {
+        match self {
+            MultisigValue::Int(_) => "int",
+            MultisigValue::Str(_) => "str",
+            MultisigValue::Bool(_) => "bool",
+            MultisigValue::Address(_) => "Address",
+            MultisigValue::Bytes(_) => "bytes",
+        }
+    }
+
+    /// Returns the value as the string stored in `_params`, with ints, bools and bytes written as hex
+    pub fn encode(&self) -> String {
+        match self {
+            MultisigValue::Int(value) => value.to_string(),
+            MultisigValue::Str(value) => value.clone(),
+            MultisigValue::Bool(true) => "0x1".to_owned(),
+            MultisigValue::Bool(false) => "0x0".to_owned(),
+            MultisigValue::Address(address) => address.to_string(),
+            MultisigValue::Bytes(bytes) => crate::icon_hex::encode_bytes_0x(bytes),
+        }
+    }
+
+    /// Parses a value stored in `_params`, accepting decimal ints and `True`/`False` bools written by other tools
+    fn decode(name: &str, kind: &str, value: &str) -> Result<Self, MultisigError> {
+        let invalid = || MultisigError::InvalidValue { name: name.to_owned(), kind: kind.to_owned(), value: value.to_owned() };
+        match kind {
+            "int" => BigNum::from_hex(value)
+                .or_else(|| value.parse::<num_bigint::BigUint>().ok().map(BigNum))
+                .map(MultisigValue::Int)
+                .ok_or_else(invalid),
+            "str" => Ok(MultisigValue::Str(value.to_owned())),
+            "bool" => match value {
+                "0x1" | "1" | "true" | "True" => Ok(MultisigValue::Bool(true)),
+                "0x0" | "0" | "false" | "False" => Ok(MultisigValue::Bool(false)),
+                _ => Err(invalid()),
+            },
+            "Address" => value.parse().map(MultisigValue::Address).map_err(|_| invalid()),
+            "bytes" => crate::icon_hex::decode_bytes_0x(value).map(MultisigValue::Bytes).map_err(|_| invalid()),
+            _ => Err(MultisigError::UnknownType { name: name.to_owned(), kind: kind.to_owned() }),
+        }
+    }
+}
+
+impl From<BigNum> for MultisigValue {
+    fn from(value: BigNum) -> Self {
+        MultisigValue::Int(value)
+    }
+}
+
+impl From<u64> for MultisigValue {
+    fn from(value: u64) -> Self {
+        MultisigValue::Int(value.into())
+    }
+}
+
+impl From<&str> for MultisigValue {
+    fn from(value: &str) -> Self {
+        MultisigValue::Str(value.to_owned())
+    }
+}
+
+impl From<String> for MultisigValue {
+    fn from(value: String) -> Self {
+        MultisigValue::Str(value)
+    }
+}
+
+impl From<bool> for MultisigValue {
+    fn from(value: bool) -> Self {
+        MultisigValue::Bool(value)
+    }
+}
+
+impl From<Address> for MultisigValue {
+    fn from(value: Address) -> Self {
+        MultisigValue::Address(value)
+    }
+}
+
+impl From<Vec<u8>> for MultisigValue {
+    fn from(value: Vec<u8>) -> Self {
+        MultisigValue::Bytes(value)
+    }
+}
+
+/// A named param of the method a multisig transaction calls
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MultisigParam {
+    pub name: String,
+    pub value: MultisigValue,
+}
+
+impl MultisigParam {
+    /// Creates a param, the type is taken from the value
+    pub fn new(name: &str, value: impl Into<MultisigValue>) -> Self {
+        MultisigParam { name: name.to_owned(), value: value.into() }
+    }
+}
+
+/// Encodes params as the `_params` JSON string of `submitTransaction`
+///
+/// # Example
+///
+///  ```
+/// # use icon_utils::contracts::multisig::{decode_params, encode_params, MultisigParam};
+/// let params = vec![MultisigParam::new("_walletOwner", "hxbe258ceb872e08851f1f59694dac2558708ece11".parse::<icon_utils::types::Address>().unwrap())];
+/// let encoded = encode_params(&params);
+/// assert_eq!(encoded, r#"[{"name":"_walletOwner","type":"Address","value":"hxbe258ceb872e08851f1f59694dac2558708ece11"}]"#);
+/// assert_eq!(decode_params(&encoded).unwrap(), params);
+/// ```
+pub fn encode_params(params: &[MultisigParam]) -> String {
+    let params = params
+        .iter()
+        .map(|param| json!({ "name": param.name, "type": param.value.kind(), "value": param.value.encode() }))
+        .collect();
+    Value::Array(params).to_string()
+}
+
+/// Decodes the `_params` JSON string of a multisig transaction, an empty string meaning no params
+pub fn decode_params(params: &str) -> Result<Vec<MultisigParam>, MultisigError> {
+    #[derive(Deserialize)]
+    struct RawParam {
+        name: String,
+        #[serde(rename = "type")]
+        kind: String,
+        value: String,
+    }
+    if params.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    let raw: Vec<RawParam> = serde_json::from_str(params).map_err(|e| MultisigError::Json(e.to_string()))?;
+    raw.into_iter()
+        .map(|param| {
+            let value = MultisigValue::decode(&param.name, &param.kind, &param.value)?;
+            Ok(MultisigParam { name: param.name, value })
+        })
+        .collect()
+}
+
+/// A transaction submitted to a multisig wallet, returned by `getTransactionInfo` and `getTransactionList`
+#[derive(Debug, Clone, Deserialize)]
+pub struct MultisigTransaction {
+    #[serde(rename = "_transactionId")]
+    pub id: u64,
+    #[serde(rename = "_destination")]
+    pub destination: Address,
+    /// Method called on the destination, empty for plain ICX transfers
+    #[serde(rename = "_method", default)]
+    pub method: String,
+    /// The params of the call as a JSON string, see [`decode_params`]
+    #[serde(rename = "_params", default)]
+    pub params: String,
+    #[serde(rename = "_value", default)]
+    pub value: Loop,
+    #[serde(rename = "_description", default)]
+    pub description: String,
+    #[serde(rename = "_executed")]
+    pub executed: bool,
+}
+
+impl MultisigTransaction {
+    /// Decodes the params of the call
+    pub fn decode_params(&self) -> Result<Vec<MultisigParam>, MultisigError> {
+        decode_params(&self.params)
+    }
+}
+
+/// A multisig wallet SCORE, where owners submit transactions that run once enough owners confirm them
+///
+/// # Example
+///
+///  ```no_run
+/// # use icon_utils::{contracts::multisig::{MultisigParam, MultisigWallet}, rpc::IconClient};
+/// let client = IconClient::new("https://api.icon.community/api/v3");
+/// let multisig = MultisigWallet::new(&client, "cx8a5ef22d0d3a3f6a3bc9d3d1b5d1b3b49f1b28c1".parse().unwrap());
+/// for tx in multisig.pending_transactions(0, 10).unwrap() {
+///     println!("{} {} {:?}", tx.id, tx.method, tx.decode_params().unwrap());
+/// }
+///
+/// let tx = multisig
+///     .submit_transaction(
+///         &"cx88fd7df7ddff82f7cc735c871dc519838cb235bb".parse().unwrap(),
+///         "transfer",
+///         &[MultisigParam::new("_to", "hx5bfdb090f43a808005ffc27c25b213145e80b7cd".parse::<icon_utils::types::Address>().unwrap()), MultisigParam::new("_value", 1_000u64)],
+///         0u64,
+///     )
+///     .from("hxbe258ceb872e08851f1f59694dac2558708ece11")
+///     .step_limit(1_000_000)
+///     .nid(1)
+///     .build()
+///     .unwrap();
+/// ```
+pub struct MultisigWallet<'a, T = HttpTransport> {
+    client: &'a IconClient<T>,
+    address: Address,
+}
+
+impl<'a, T: Transport> MultisigWallet<'a, T> {
+    /// Creates a helper for the multisig wallet SCORE at `address`
+    pub fn new(client: &'a IconClient<T>, address: Address) -> Self {
+        MultisigWallet { client, address }
+    }
+
+    /// Returns the address of the multisig wallet SCORE
+    pub fn address(&self) -> &Address {
+        &self.address
+    }
+
+    /// Returns the owners of the wallet, from `offset`
+    pub fn wallet_owners(&self, offset: u64, count: u64) -> Result<Vec<Address>, RpcError> {
+        self.client.call(&self.query("getWalletOwners").param("_offset", offset).param("_count", count))
+    }
+
+    /// Returns the number of confirmations a transaction needs before it runs
+    pub fn requirement(&self) -> Result<u64, RpcError> {
+        self.client.call(&self.query("getRequirement"))
+    }
+
+    /// Returns the submitted transaction `id`
+    pub fn transaction(&self, id: u64) -> Result<MultisigTransaction, RpcError> {
+        self.client.call(&self.query("getTransactionInfo").param("_transactionId", id))
+    }
+
+    /// Returns the transactions still waiting for confirmations, from `offset`
+    pub fn pending_transactions(&self, offset: u64, count: u64) -> Result<Vec<MultisigTransaction>, RpcError> {
+        let call = self
+            .query("getTransactionList")
+            .param("_offset", offset)
+            .param("_count", count)
+            .param("_pending", true)
+            .param("_executed", false);
+        self.client.call(&call)
+    }
+
+    /// Returns the owners that confirmed transaction `id`
+    pub fn confirmations(&self, id: u64) -> Result<Vec<Address>, RpcError> {
+        self.client.call(&self.query("getConfirmations").param("_transactionId", id))
+    }
+
+    /// Starts a `submitTransaction` call transaction proposing a call of `method` on `destination`, sending `value`
+    ///
+    /// The sender's confirmation is added when it's submitted. An empty `method` submits a plain ICX transfer,
+    /// and a description can be added with `.param("_description", ...)`.
+    pub fn submit_transaction(
+        &self,
+        destination: &Address,
+        method: &str,
+        params: &[MultisigParam],
+        value: impl Into<Loop>,
+    ) -> CallTransactionBuilder {
+        let mut tx = self.call("submitTransaction").param("_destination", destination).param("_value", value.into());
+        if !method.is_empty() {
+            tx = tx.param("_method", method);
+        }
+        if !params.is_empty() {
+            tx = tx.param("_params", encode_params(params));
+        }
+        tx
+    }
+
+    /// Starts a `confirmTransaction` call transaction confirming transaction `id`, which runs it once enough owners have
+    pub fn confirm_transaction(&self, id: u64) -> CallTransactionBuilder {
+        self.call("confirmTransaction").param("_transactionId", id)
+    }
+
+    /// Starts a `revokeTransaction` call transaction withdrawing the sender's confirmation of transaction `id`
+    pub fn revoke_transaction(&self, id: u64) -> CallTransactionBuilder {
+        self.call("revokeTransaction").param("_transactionId", id)
+    }
+
+    fn query(&self, method: &str) -> CallBuilder {
+        CallBuilder::new(&self.address.to_string(), method)
+    }
+
+    fn call(&self, method: &str) -> CallTransactionBuilder {
+        CallTransactionBuilder::new().to(self.address).method(method)
+    }
+}
@@ -0,0 +1,355 @@
+//! # Icon Events
+//! `events` is a module for decoding the event logs of transaction receipts.
+//! Event values are ICON strings, decoded according to the parameter types in the event signature.
+use std::fmt;
+use std::str::FromStr;
+
+use num_bigint::BigInt;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::rpc::EventLog;
+use crate::types::Address;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+// Error struct for decoding events
+pub enum EventError {
+    #[error("Invalid event signature: {0}")]
+    InvalidSignature(String),
+    #[error("Event has {found} values but its signature has {expected} params")]
+    WrongValueCount { expected: usize, found: usize },
+    #[error("Invalid {param_type} value: {value}")]
+    InvalidValue { param_type: ParamType, value: String },
+    #[error("Failed to decode event: {0}")]
+    Decode(String),
+}
+
+/// The type of an event parameter
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ParamType {
+    Int,
+    Str,
+    Bytes,
+    Bool,
+    Address,
+}
+
+impl FromStr for ParamType {
+    type Err = EventError;
+    fn from_str(value: &str) -> Result<Self, EventError> {
+        match value {
+            "int" => Ok(ParamType::Int),
+            "str" => Ok(ParamType::Str),
+            "bytes" => Ok(ParamType::Bytes),
+            "bool" => Ok(ParamType::Bool),
+            "Address" => Ok(ParamType::Address),
+            other => Err(EventError::InvalidSignature(format!("unknown param type {}", other))),
+        }
+    }
+}
+
+impl fmt::Display for ParamType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ParamType::Int => "int",
+            ParamType::Str => "str",
+            ParamType::Bytes => "bytes",
+            ParamType::Bool => "bool",
+            ParamType::Address => "Address",
+        })
+    }
+}
+
+/// A parsed event signature, such as `Transfer(Address,Address,int,bytes)`
+///
+/// # Example
+///
+///  ```
+/// # use icon_utils::events::{EventSignature, ParamType};
+/// let signature: EventSignature = "Transfer(Address,Address,int,bytes)".parse().unwrap();
+/// assert_eq!(signature.name, "Transfer");
+/// assert_eq!(signature.params[2], ParamType::Int);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EventSignature {
+    pub name: String,
+    pub params: Vec<ParamType>,
+}
+
+impl FromStr for EventSignature {
+    type Err = EventError;
+    fn from_str(value: &str) -> Result<Self, EventError> {
+        let invalid = || EventError::InvalidSignature(value.to_owned());
+        let (name, rest) = value.split_once('(').ok_or_else(invalid)?;
+        let params = rest.strip_suffix(')').ok_or_else(invalid)?;
+        if name.is_empty() {
+            return Err(invalid());
+        }
+        let params = match params {
+            "" => Vec::new(),
+            params => params.split(',').map(|p| p.trim().parse()).collect::<Result<_, _>>()?,
+        };
+        Ok(EventSignature { name: name.to_owned(), params })
+    }
+}
+
+impl fmt::Display for EventSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let params: Vec<String> = self.params.iter().map(ParamType::to_string).collect();
+        write!(f, "{}({})", self.name, params.join(","))
+    }
+}
+
+/// A decoded event value
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EventValue {
+    Int(BigInt),
+    Str(String),
+    Bytes(Vec<u8>),
+    Bool(bool),
+    Address(Address),
+    /// A value the SCORE emitted as null
+    Null,
+}
+
+impl EventValue {
+    /// Decodes an ICON string as the given param type
+    pub fn decode(param_type: ParamType, value: Option<&str>) -> Result<Self, EventError> {
+        let value = match value {
+            Some(value) => value,
+            None => return Ok(EventValue::Null),
+        };
+        let invalid = || EventError::InvalidValue { param_type, value: value.to_owned() };
+        match param_type {
+            ParamType::Int => {
+                let (negative, digits) = match value.strip_prefix('-') {
+                    Some(rest) => (true, rest),
+                    None => (false, value),
+                };
+                let digits = crate::icon_hex::int_digits(digits).map_err(|_| invalid())?;
+                let parsed = BigInt::parse_bytes(digits.as_bytes(), 16).ok_or_else(invalid)?;
+                Ok(EventValue::Int(if negative { -parsed } else { parsed }))
+            }
+            ParamType::Str => Ok(EventValue::Str(value.to_owned())),
+            ParamType::Bytes => {
+                crate::icon_hex::decode_bytes_0x(value).map(EventValue::Bytes).map_err(|_| invalid())
+            }
+            ParamType::Bool => match value {
+                "0x1" => Ok(EventValue::Bool(true)),
+                "0x0" => Ok(EventValue::Bool(false)),
+                _ => Err(invalid()),
+            },
+            ParamType::Address => value.parse().map(EventValue::Address).map_err(|_| invalid()),
+        }
+    }
+}
+
+/// An event log decoded with its signature
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodedEvent {
+    pub score_address: Address,
+    pub signature: EventSignature,
+    /// The values of the event's params, indexed params first, in signature order
+    pub values: Vec<EventValue>,
+}
+
+impl DecodedEvent {
+    /// Decodes an event log, reading the signature from its first indexed value
+    ///
+    /// # Example
+    ///
+    ///  ```
+    /// # use icon_utils::{events::{DecodedEvent, EventValue}, rpc::EventLog};
+    /// let log: EventLog = serde_json::from_value(serde_json::json!({
+    ///     "scoreAddress": "cx88fd7df7ddff82f7cc735c871dc519838cb235bb",
+    ///     "indexed": [
+    ///         "Transfer(Address,Address,int,bytes)",
+    ///         "hxbe258ceb872e08851f1f59694dac2558708ece11",
+    ///         "hx5bfdb090f43a808005ffc27c25b213145e80b7cd",
+    ///         "0xde0b6b3a7640000"
+    ///     ],
+    ///     "data": ["0x"]
+    /// })).unwrap();
+    /// let event = DecodedEvent::decode(&log).unwrap();
+    /// assert_eq!(event.signature.name, "Transfer");
+    /// assert_eq!(event.values[3], EventValue::Bytes(vec![]));
+    /// ```
+    pub fn decode(log: &EventLog) -> Result<Self, EventError> {
+        let signature: EventSignature = log
+            .indexed
+            .first()
+            .and_then(Option::as_deref)
+            .ok_or_else(|| EventError::InvalidSignature("missing event signature".to_owned()))?
+            .parse()?;
+        let raw = raw_values(log);
+        if raw.len() != signature.params.len() {
+            return Err(EventError::WrongValueCount { expected: signature.params.len(), found: raw.len() });
+        }
+        let values = signature
+            .params
+            .iter()
+            .zip(raw)
+            .map(|(param_type, value)| EventValue::decode(*param_type, value))
+            .collect::<Result<_, _>>()?;
+        Ok(DecodedEvent { score_address: log.score_address, signature, values })
+    }
+}
+
+/// The params of an event log in signature order, without the signature itself
+fn raw_values(log: &EventLog) -> Vec<Option<&str>> {
+    log.indexed.iter().skip(1).chain(log.data.iter()).map(Option::as_deref).collect()
+}
+
+/// Selects events by SCORE address, signature and param values
+///
+/// Filters are passed to the node's event websocket by `subscribe_events`, or matched against event logs
+/// with [`EventFilter::matches`].
+///
+/// # Example
+///
+///  ```
+/// # use icon_utils::{events::EventFilter, rpc::EventLog};
+/// let filter = EventFilter::new("Transfer(Address,Address,int,bytes)")
+///     .address("cx88fd7df7ddff82f7cc735c871dc519838cb235bb".parse().unwrap())
+///     .indexed(vec![Some("hxbe258ceb872e08851f1f59694dac2558708ece11".to_owned())]);
+/// let log: EventLog = serde_json::from_value(serde_json::json!({
+///     "scoreAddress": "cx88fd7df7ddff82f7cc735c871dc519838cb235bb",
+///     "indexed": [
+///         "Transfer(Address,Address,int,bytes)",
+///         "hxbe258ceb872e08851f1f59694dac2558708ece11",
+///         "hx5bfdb090f43a808005ffc27c25b213145e80b7cd",
+///         "0xde0b6b3a7640000"
+///     ],
+///     "data": ["0x"]
+/// })).unwrap();
+/// assert!(filter.matches(&log));
+/// ```
+#[derive(Debug, Clone, Serialize)]
+pub struct EventFilter {
+    event: String,
+    #[serde(rename = "addr", skip_serializing_if = "Option::is_none")]
+    address: Option<Address>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    indexed: Vec<Option<String>>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    data: Vec<Option<String>>,
+}
+
+impl EventFilter {
+    /// Matches events with the given signature
+    pub fn new(signature: &str) -> Self {
+        EventFilter { event: signature.to_owned(), address: None, indexed: Vec::new(), data: Vec::new() }
+    }
+
+    /// Only matches events emitted by the SCORE at `address`
+    pub fn address(mut self, address: Address) -> Self {
+        self.address = Some(address);
+        self
+    }
+
+    /// Only matches events whose indexed params equal `indexed`, None matches any value
+    pub fn indexed(mut self, indexed: Vec<Option<String>>) -> Self {
+        self.indexed = indexed;
+        self
+    }
+
+    /// Only matches events whose data params equal `data`, None matches any value
+    pub fn data(mut self, data: Vec<Option<String>>) -> Self {
+        self.data = data;
+        self
+    }
+
+    /// Returns the event signature the filter matches
+    pub fn signature(&self) -> &str {
+        &self.event
+    }
+
+    /// Returns true if `log` is the filtered event, emitted by the filtered SCORE with matching param values
+    pub fn matches(&self, log: &EventLog) -> bool {
+        if log.indexed.first().and_then(Option::as_deref) != Some(self.event.as_str()) {
+            return false;
+        }
+        if self.address.is_some_and(|address| address != log.score_address) {
+            return false;
+        }
+        values_match(&self.indexed, log.indexed.get(1..).unwrap_or_default()) && values_match(&self.data, &log.data)
+    }
+}
+
+/// Returns true if every expected value that is set equals the value at its position
+fn values_match(expected: &[Option<String>], values: &[Option<String>]) -> bool {
+    expected.iter().enumerate().all(|(i, expected)| match expected {
+        Some(expected) => values.get(i).and_then(Option::as_deref) == Some(expected.as_str()),
+        None => true,
+    })
+}
+
+/// Trait for user structs that an event log can be decoded into
+///
+/// The struct is deserialized from the event's params in signature order, with the same hex decoding as
+/// [`params::from_value`](crate::params::from_value), so fields can be integers, booleans, strings,
+/// [`Address`] or [`HexBytes`](crate::types::HexBytes). Use [`impl_event!`](crate::impl_event) to implement it.
+pub trait Event: DeserializeOwned {
+    /// The event signature, such as `Transfer(Address,Address,int,bytes)`
+    const SIGNATURE: &'static str;
+
+    /// Decodes `log` into the struct, returning None if the log is a different event
+    fn from_log(log: &EventLog) -> Result<Option<Self>, EventError> {
+        if log.indexed.first().and_then(Option::as_deref) != Some(Self::SIGNATURE) {
+            return Ok(None);
+        }
+        let expected = Self::SIGNATURE.parse::<EventSignature>()?.params.len();
+        let raw = raw_values(log);
+        if raw.len() != expected {
+            return Err(EventError::WrongValueCount { expected, found: raw.len() });
+        }
+        let values = raw.into_iter().map(|v| v.map_or(Value::Null, |v| Value::String(v.to_owned()))).collect();
+        crate::params::from_value(Value::Array(values))
+            .map(Some)
+            .map_err(|e| EventError::Decode(e.to_string()))
+    }
+}
+
+/// Decodes every log of `logs` that matches the event `T`
+pub fn decode_all<T: Event>(logs: &[EventLog]) -> Result<Vec<T>, EventError> {
+    logs.iter().filter_map(|log| T::from_log(log).transpose()).collect()
+}
+
+/// Implements [`Event`] for a struct with the given signature
+///
+/// # Example
+///
+///  ```
+/// # use icon_utils::{events::{self, Event}, impl_event, rpc::EventLog, types::{Address, HexBytes}};
+/// #[derive(serde::Deserialize)]
+/// struct Transfer {
+///     from: Address,
+///     to: Address,
+///     value: u128,
+///     data: HexBytes,
+/// }
+/// impl_event!(Transfer, "Transfer(Address,Address,int,bytes)");
+///
+/// let log: EventLog = serde_json::from_value(serde_json::json!({
+///     "scoreAddress": "cx88fd7df7ddff82f7cc735c871dc519838cb235bb",
+///     "indexed": [
+///         "Transfer(Address,Address,int,bytes)",
+///         "hxbe258ceb872e08851f1f59694dac2558708ece11",
+///         "hx5bfdb090f43a808005ffc27c25b213145e80b7cd",
+///         "0xde0b6b3a7640000"
+///     ],
+///     "data": ["0x"]
+/// })).unwrap();
+/// let transfers: Vec<Transfer> = events::decode_all(&[log]).unwrap();
+/// assert_eq!(transfers[0].value, 1_000_000_000_000_000_000);
+/// ```
+#[macro_export]
+macro_rules! impl_event {
+    ($ty:ty, $signature:expr) => {
+        impl $crate::events::Event for $ty {
+            const SIGNATURE: &'static str = $signature;
+        }
+    };
+}
@@ -0,0 +1,159 @@
+//! # Icon Fees
+//! `fees` is a module for computing transaction fees, the step limit of a transaction multiplied by the step price.
+//! The step price and step costs are set by governance, the constants below are the mainnet values.
+use serde::Deserialize;
+
+use crate::rpc::{CallBuilder, HttpTransport, IconClient, RpcError, Transport};
+use crate::transaction::{IcxTransaction, DEFAULT_STEPS};
+use crate::types::Address;
+use crate::units::{Icx, Loop};
+
+pub use crate::transaction::DEFAULT_STEP_PRICE;
+
+/// The base step cost of an ICX transfer without data
+pub const TRANSFER_STEPS: u64 = DEFAULT_STEPS;
+
+/// The base step cost of a `message` transaction, before the cost of its data
+pub const MESSAGE_STEPS: u64 = DEFAULT_STEPS;
+
+/// The base step cost of a `call` transaction, before the cost of its data and of the SCORE execution
+pub const CALL_STEPS: u64 = DEFAULT_STEPS + CONTRACT_CALL_STEPS;
+
+/// The base step cost of a `deploy` transaction installing a new SCORE, before the cost of its content
+pub const DEPLOY_STEPS: u64 = DEFAULT_STEPS + CONTRACT_CREATE_STEPS;
+
+/// The base step cost of a `deposit` transaction, before the cost of its data
+pub const DEPOSIT_STEPS: u64 = DEFAULT_STEPS + CONTRACT_CALL_STEPS;
+
+/// The step cost of calling a SCORE on mainnet
+pub const CONTRACT_CALL_STEPS: u64 = 25_000;
+
+/// The step cost of installing a SCORE on mainnet
+pub const CONTRACT_CREATE_STEPS: u64 = 1_000_000_000;
+
+/// The step cost of updating a SCORE on mainnet
+pub const CONTRACT_UPDATE_STEPS: u64 = 1_600_000_000;
+
+/// Returns the mainnet base step cost of a transaction with the given `dataType`, None for unknown data types
+///
+/// # Example
+///
+///  ```
+/// # use icon_utils::fees::{self, CALL_STEPS};
+/// assert_eq!(fees::base_steps(Some("call")), Some(CALL_STEPS));
+/// assert_eq!(fees::base_steps(None), Some(100_000));
+/// ```
+pub fn base_steps(data_type: Option<&str>) -> Option<u64> {
+    match data_type {
+        None => Some(TRANSFER_STEPS),
+        Some("message") => Some(MESSAGE_STEPS),
+        Some("call") => Some(CALL_STEPS),
+        Some("deploy") => Some(DEPLOY_STEPS),
+        Some("deposit") => Some(DEPOSIT_STEPS),
+        Some(_) => None,
+    }
+}
+
+/// Returns the most a transaction can cost, its step limit multiplied by `step_price`
+///
+/// # Example
+///
+///  ```
+/// # use icon_utils::{fees::{self, DEFAULT_STEP_PRICE}, units::Loop};
+/// assert_eq!(fees::max_fee(100_000, &Loop::from(DEFAULT_STEP_PRICE)).format_icx(), "0.00125");
+/// ```
+pub fn max_fee(step_limit: u64, step_price: &Loop) -> Loop {
+    step_price.clone() * step_limit
+}
+
+/// Returns the most a transaction can cost in ICX, its step limit multiplied by `step_price`
+pub fn max_fee_icx(step_limit: u64, step_price: &Loop) -> Icx {
+    max_fee(step_limit, step_price).to_icx()
+}
+
+/// The step costs set by governance, returned by `getStepCosts`
+///
+/// Costs missing from the response are left at 0, some costs such as `delete` are negative refunds.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct StepCosts {
+    /// The base cost of every transaction
+    pub default: i64,
+    pub contract_call: i64,
+    pub contract_create: i64,
+    pub contract_update: i64,
+    pub contract_set: i64,
+    pub get: i64,
+    pub set: i64,
+    pub replace: i64,
+    pub delete: i64,
+    /// The cost of each byte of transaction data
+    pub input: i64,
+    pub event_log: i64,
+    pub api_call: i64,
+}
+
+impl StepCosts {
+    /// Returns the base step cost of a transaction with the given `dataType`, None for unknown data types
+    pub fn base_steps(&self, data_type: Option<&str>) -> Option<i64> {
+        match data_type {
+            None | Some("message") => Some(self.default),
+            Some("call") | Some("deposit") => Some(self.default + self.contract_call),
+            Some("deploy") => Some(self.default + self.contract_create),
+            Some(_) => None,
+        }
+    }
+}
+
+/// Reads the fee settings from the governance SCORE
+///
+/// # Example
+///
+///  ```no_run
+/// # use icon_utils::{fees::Fees, network::Network, rpc::IconClient, transaction::TransactionBuilder, units::Icx};
+/// let client = IconClient::new("https://api.icon.community/api/v3");
+/// let fees = Fees::new(&client);
+/// let tx = TransactionBuilder::new()
+///     .from("hxbe258ceb872e08851f1f59694dac2558708ece11")
+///     .to("hx5bfdb090f43a808005ffc27c25b213145e80b7cd")
+///     .value(Icx::new(1))
+///     .step_limit(100_000)
+///     .network(&Network::Mainnet)
+///     .build()
+///     .unwrap();
+/// println!("fee up to {}", fees.max_fee(&tx).unwrap().to_icx());
+/// ```
+pub struct Fees<'a, T = HttpTransport> {
+    client: &'a IconClient<T>,
+}
+
+impl<'a, T: Transport> Fees<'a, T> {
+    /// Creates a helper sending reads through `client`
+    pub fn new(client: &'a IconClient<T>) -> Self {
+        Fees { client }
+    }
+
+    /// Returns the current step price, in loop
+    pub fn get_step_price(&self) -> Result<Loop, RpcError> {
+        self.client.call(&query("getStepPrice"))
+    }
+
+    /// Returns the current step costs
+    pub fn get_step_costs(&self) -> Result<StepCosts, RpcError> {
+        self.client.call(&query("getStepCosts"))
+    }
+
+    /// Returns the highest step limit allowed for transactions, `context_type` is `invoke` or `query`
+    pub fn get_max_step_limit(&self, context_type: &str) -> Result<u64, RpcError> {
+        self.client.call(&query("getMaxStepLimit").param("contextType", context_type))
+    }
+
+    /// Returns the most `tx` can cost at the current step price, in loop
+    pub fn max_fee(&self, tx: &IcxTransaction) -> Result<Loop, RpcError> {
+        Ok(max_fee(tx.step_limit, &self.get_step_price()?))
+    }
+}
+
+fn query(method: &str) -> CallBuilder {
+    CallBuilder::new(&Address::GOVERNANCE_SCORE.to_string(), method)
+}
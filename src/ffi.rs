@@ -0,0 +1,204 @@
+//! # Icon FFI
+//! `ffi` is a module of `extern "C"` functions, so apps written in other languages can link the crate.
+//! The header is at `include/icon_utils.h`, regenerate it with `cbindgen --config cbindgen.toml --output include/icon_utils.h`.
+//!
+//! Functions return an [`IconStatus`], outputs are written through pointers. Strings returned by the library are
+//! [`IconBuffer`]s owned by the caller, which must be released with [`icon_buffer_free`].
+//! Build a static library with `cargo rustc --release --features capi --crate-type staticlib`.
+use std::ffi::{c_char, CStr};
+use std::ptr;
+
+use rand::{rngs::OsRng, RngCore};
+
+use crate::serializer;
+use crate::transaction::IcxTransaction;
+use crate::wallet::Wallet;
+
+/// Status codes returned by every function
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IconStatus {
+    Ok = 0,
+    NullPointer = 1,
+    InvalidUtf8 = 2,
+    InvalidKey = 3,
+    InvalidTransaction = 4,
+    SerializeFailed = 5,
+    SignFailed = 6,
+}
+
+/// Bytes allocated by the library, followed by a NUL byte not counted in `len` so they can be read as a C string
+#[repr(C)]
+#[derive(Debug)]
+pub struct IconBuffer {
+    pub data: *mut u8,
+    pub len: usize,
+}
+
+impl IconBuffer {
+    fn new(value: String) -> Self {
+        let mut bytes = value.into_bytes();
+        let len = bytes.len();
+        bytes.push(0);
+        let data = Box::into_raw(bytes.into_boxed_slice()) as *mut u8;
+        IconBuffer { data, len }
+    }
+}
+
+/// A wallet, created by the library and released with [`icon_wallet_free`]
+pub struct IconWallet(Wallet);
+
+/// Returns a static description of a status code
+#[no_mangle]
+pub extern "C" fn icon_status_message(status: IconStatus) -> *const c_char {
+    let message: &'static CStr = match status {
+        IconStatus::Ok => c"ok",
+        IconStatus::NullPointer => c"null pointer",
+        IconStatus::InvalidUtf8 => c"invalid UTF-8",
+        IconStatus::InvalidKey => c"invalid private key",
+        IconStatus::InvalidTransaction => c"invalid transaction JSON",
+        IconStatus::SerializeFailed => c"failed to serialize transaction",
+        IconStatus::SignFailed => c"failed to sign transaction",
+    };
+    message.as_ptr()
+}
+
+/// Creates a wallet with a new random private key
+///
+/// # Safety
+///
+/// `out` must be a valid pointer, the wallet written to it must be released with [`icon_wallet_free`].
+#[no_mangle]
+pub unsafe extern "C" fn icon_wallet_create(out: *mut *mut IconWallet) -> IconStatus {
+    let mut key = [0u8; 32];
+    OsRng.fill_bytes(&mut key);
+    icon_wallet_from_private_key(key.as_ptr(), key.len(), out)
+}
+
+/// Creates a wallet from raw private key bytes
+///
+/// # Safety
+///
+/// `key` must point to `len` readable bytes and `out` must be a valid pointer,
+/// the wallet written to it must be released with [`icon_wallet_free`].
+#[no_mangle]
+pub unsafe extern "C" fn icon_wallet_from_private_key(key: *const u8, len: usize, out: *mut *mut IconWallet) -> IconStatus {
+    if key.is_null() || out.is_null() {
+        return IconStatus::NullPointer;
+    }
+    match Wallet::from_bytes(std::slice::from_raw_parts(key, len)) {
+        Ok(wallet) => {
+            *out = Box::into_raw(Box::new(IconWallet(wallet)));
+            IconStatus::Ok
+        }
+        Err(_) => IconStatus::InvalidKey,
+    }
+}
+
+/// Writes the 32 byte private key of a wallet to `out`
+///
+/// # Safety
+///
+/// `wallet` must come from this library and `out` must point to 32 writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn icon_wallet_private_key(wallet: *const IconWallet, out: *mut u8) -> IconStatus {
+    if wallet.is_null() || out.is_null() {
+        return IconStatus::NullPointer;
+    }
+    let key = (*wallet).0.export_private_key();
+    ptr::copy_nonoverlapping(key.as_ptr(), out, key.len());
+    IconStatus::Ok
+}
+
+/// Writes the hx address of a wallet to `out`
+///
+/// # Safety
+///
+/// `wallet` must come from this library and `out` must be a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn icon_wallet_address(wallet: *const IconWallet, out: *mut IconBuffer) -> IconStatus {
+    if wallet.is_null() || out.is_null() {
+        return IconStatus::NullPointer;
+    }
+    *out = IconBuffer::new((*wallet).0.address());
+    IconStatus::Ok
+}
+
+/// Releases a wallet, null is ignored
+///
+/// # Safety
+///
+/// `wallet` must be null or come from this library, and must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn icon_wallet_free(wallet: *mut IconWallet) {
+    if !wallet.is_null() {
+        drop(Box::from_raw(wallet));
+    }
+}
+
+/// Serializes a transaction given as the JSON of its `icx_sendTransaction` params, the string that gets hashed and signed
+///
+/// # Safety
+///
+/// `tx_json` must be a NUL terminated string and `out` must be a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn icon_serialize_transaction(tx_json: *const c_char, out: *mut IconBuffer) -> IconStatus {
+    if out.is_null() {
+        return IconStatus::NullPointer;
+    }
+    let tx = match parse_transaction(tx_json) {
+        Ok(tx) => tx,
+        Err(status) => return status,
+    };
+    match serializer::serialize_to_string(&tx) {
+        Ok(serialized) => {
+            *out = IconBuffer::new(serialized);
+            IconStatus::Ok
+        }
+        Err(_) => IconStatus::SerializeFailed,
+    }
+}
+
+/// Signs a transaction given as the JSON of its `icx_sendTransaction` params,
+/// writing the JSON of the params with the signature added to `out`
+///
+/// # Safety
+///
+/// `wallet` must come from this library, `tx_json` must be a NUL terminated string and `out` must be a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn icon_sign_transaction(wallet: *const IconWallet, tx_json: *const c_char, out: *mut IconBuffer) -> IconStatus {
+    if wallet.is_null() || out.is_null() {
+        return IconStatus::NullPointer;
+    }
+    let tx = match parse_transaction(tx_json) {
+        Ok(tx) => tx,
+        Err(status) => return status,
+    };
+    match (*wallet).0.sign_transaction(&tx) {
+        Ok(signed) => {
+            *out = IconBuffer::new(signed.to_rpc_params().to_string());
+            IconStatus::Ok
+        }
+        Err(_) => IconStatus::SignFailed,
+    }
+}
+
+/// Releases a buffer returned by the library, an empty buffer is ignored
+///
+/// # Safety
+///
+/// `buffer` must come from this library and must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn icon_buffer_free(buffer: IconBuffer) {
+    if !buffer.data.is_null() {
+        drop(Box::from_raw(ptr::slice_from_raw_parts_mut(buffer.data, buffer.len + 1)));
+    }
+}
+
+unsafe fn parse_transaction(tx_json: *const c_char) -> Result<IcxTransaction, IconStatus> {
+    if tx_json.is_null() {
+        return Err(IconStatus::NullPointer);
+    }
+    let json = CStr::from_ptr(tx_json).to_str().map_err(|_| IconStatus::InvalidUtf8)?;
+    serde_json::from_str(json).map_err(|_| IconStatus::InvalidTransaction)
+}
@@ -0,0 +1,447 @@
+//! # Icon Governance
+//! `governance` is a module for IISS staking, delegation, rewards and P-Reps, handled by the system SCORE, and for
+//! network proposals, handled by the governance SCORE.
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::params::IntoParam;
+use crate::rpc::{CallBuilder, HttpTransport, IconClient, RpcError, Transport};
+use crate::serializer::BigNum;
+use crate::transaction::CallTransactionBuilder;
+use crate::types::{Address, Hash256};
+use crate::units::{Icx, Loop};
+
+/// The stake of an account, returned by `getStake`
+#[derive(Debug, Clone, Deserialize)]
+pub struct Stake {
+    pub stake: Loop,
+    /// Amounts being unstaked, which become available after their lock period
+    #[serde(default)]
+    pub unstakes: Vec<Unstake>,
+}
+
+/// An amount being unstaked
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Unstake {
+    pub unstake: Loop,
+    pub unstake_block_height: u64,
+    pub remaining_blocks: u64,
+}
+
+/// A delegation of voting power to a P-Rep
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Delegation {
+    pub address: Address,
+    pub value: Loop,
+}
+
+impl IntoParam for Delegation {
+    fn into_param(self) -> Value {
+        json!({ "address": self.address.into_param(), "value": self.value.into_param() })
+    }
+}
+
+/// The delegations of an account, returned by `getDelegation`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DelegationInfo {
+    pub delegations: Vec<Delegation>,
+    pub total_delegated: Loop,
+    pub voting_power: Loop,
+}
+
+/// The unclaimed rewards of an account, returned by `queryIScore`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IScore {
+    pub block_height: u64,
+    /// Unclaimed I-Score, 1000 I-Score is worth 1 loop
+    pub iscore: BigNum,
+    #[serde(rename = "estimatedICX")]
+    pub estimated_icx: Loop,
+}
+
+/// A P-Rep, returned by `getPRep` and `getPReps`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PRep {
+    pub address: Address,
+    pub name: String,
+    #[serde(default)]
+    pub country: String,
+    #[serde(default)]
+    pub city: String,
+    #[serde(default)]
+    pub email: String,
+    #[serde(default)]
+    pub website: String,
+    /// URL of the P-Rep's details JSON
+    #[serde(default)]
+    pub details: String,
+    #[serde(default)]
+    pub p2p_endpoint: String,
+    /// Address of the node's key, if it differs from the P-Rep address
+    pub node_address: Option<Address>,
+    /// 0 for main P-Reps, 1 for sub P-Reps and 2 for candidates
+    pub grade: u64,
+    /// 0 when active, 1 when unregistered and 2 when disqualified
+    pub status: u64,
+    pub delegated: Loop,
+    pub bonded: Option<Loop>,
+    pub power: Option<Loop>,
+    pub total_blocks: Option<u64>,
+    pub validated_blocks: Option<u64>,
+}
+
+/// The P-Reps list returned by `getPReps`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PRepList {
+    pub block_height: u64,
+    pub start_ranking: Option<u64>,
+    pub total_delegated: Loop,
+    pub total_stake: Loop,
+    pub preps: Vec<PRep>,
+}
+
+/// The public details of a P-Rep, used when registering or updating one
+///
+/// Fields left as None are omitted, so `setPRep` only changes the fields that are set.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PRepInfo {
+    pub name: Option<String>,
+    pub country: Option<String>,
+    pub city: Option<String>,
+    pub email: Option<String>,
+    pub website: Option<String>,
+    pub details: Option<String>,
+    pub p2p_endpoint: Option<String>,
+    pub node_address: Option<Address>,
+}
+
+impl PRepInfo {
+    /// Adds the fields that are set as params of `tx`
+    fn apply(self, mut tx: CallTransactionBuilder) -> CallTransactionBuilder {
+        let fields = [
+            ("name", self.name),
+            ("country", self.country),
+            ("city", self.city),
+            ("email", self.email),
+            ("website", self.website),
+            ("details", self.details),
+            ("p2pEndpoint", self.p2p_endpoint),
+            ("nodeAddress", self.node_address.map(|a| a.to_string())),
+        ];
+        for (name, value) in fields {
+            if let Some(value) = value {
+                tx = tx.param(name, value);
+            }
+        }
+        tx
+    }
+}
+
+/// The fee burned when registering a P-Rep, in ICX
+pub const PREP_REGISTRATION_FEE: u64 = 2000;
+
+/// The fee burned when registering a network proposal, in ICX
+pub const PROPOSAL_REGISTRATION_FEE: u64 = 100;
+
+/// A network proposal, returned by `getProposal` and `getProposals`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Proposal {
+    /// Hash of the `registerProposal` transaction
+    pub id: Hash256,
+    pub proposer: Address,
+    #[serde(default)]
+    pub proposer_name: String,
+    /// 0 while voting, 1 when applied, 2 when disapproved, 3 when canceled, 4 when approved and 5 when expired
+    pub status: u64,
+    pub start_block_height: u64,
+    pub end_block_height: u64,
+    pub vote: ProposalVotes,
+    pub contents: ProposalContents,
+}
+
+/// What a network proposal changes
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProposalContents {
+    pub title: String,
+    pub description: String,
+    /// The changes, as registered with [`ProposalValue`]
+    pub value: Value,
+}
+
+/// The votes on a network proposal
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProposalVotes {
+    pub agree: VoteTally,
+    pub disagree: VoteTally,
+    pub no_vote: NoVotes,
+}
+
+/// The P-Reps that voted one way on a proposal
+///
+/// `getProposal` lists each vote, `getProposals` only counts them.
+#[derive(Debug, Clone, Deserialize)]
+pub struct VoteTally {
+    /// Delegated voting power behind the votes
+    pub amount: Loop,
+    pub count: Option<u64>,
+    #[serde(default)]
+    pub list: Vec<Vote>,
+}
+
+/// A P-Rep's vote on a proposal
+#[derive(Debug, Clone, Deserialize)]
+pub struct Vote {
+    /// Hash of the `voteProposal` transaction
+    pub id: Hash256,
+    pub timestamp: u64,
+    pub address: Address,
+    #[serde(default)]
+    pub name: String,
+    pub amount: Loop,
+}
+
+/// The P-Reps that haven't voted on a proposal yet
+#[derive(Debug, Clone, Deserialize)]
+pub struct NoVotes {
+    pub amount: Loop,
+    #[serde(alias = "size")]
+    pub count: Option<u64>,
+    #[serde(default)]
+    pub list: Vec<Address>,
+}
+
+#[derive(Deserialize)]
+struct ProposalList {
+    proposals: Vec<Proposal>,
+}
+
+/// Filters for `getProposals`, fields left as None aren't filtered on
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ProposalFilter {
+    /// Only proposals with this status, see [`Proposal::status`]
+    pub status: Option<u64>,
+    /// Skips this many proposals, newest first
+    pub start: Option<u64>,
+    /// Returns at most this many proposals
+    pub size: Option<u64>,
+}
+
+impl ProposalFilter {
+    /// Adds the filters that are set as params of `call`
+    fn apply(self, mut call: CallBuilder) -> CallBuilder {
+        for (name, value) in [("status", self.status), ("start", self.start), ("size", self.size)] {
+            if let Some(value) = value {
+                call = call.param(name, value);
+            }
+        }
+        call
+    }
+}
+
+/// A change made by a network proposal once it's approved
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProposalValue {
+    /// A text proposal, which changes nothing on chain
+    Text(String),
+    /// Sets the network revision
+    Revision(u64),
+    /// Freezes a SCORE, or unfreezes it when `freeze` is false
+    MaliciousScore { address: Address, freeze: bool },
+    /// Disqualifies a P-Rep
+    PRepDisqualification(Address),
+    /// Sets the step price, in loop
+    StepPrice(Loop),
+    /// Sets the yearly reward fund
+    RewardFund(Loop),
+    /// Any other proposal type, given as its name and value object
+    Other { name: String, value: Value },
+}
+
+impl ProposalValue {
+    /// Returns the `{"name": ..., "value": ...}` object registered for the change
+    pub fn to_json(&self) -> Value {
+        let (name, value) = match self {
+            ProposalValue::Text(text) => ("text", json!({ "text": text })),
+            ProposalValue::Revision(revision) => ("revision", json!({ "revision": revision.into_param() })),
+            ProposalValue::MaliciousScore { address, freeze } => (
+                "maliciousScore",
+                json!({ "address": address.into_param(), "type": u64::from(!freeze).into_param() }),
+            ),
+            ProposalValue::PRepDisqualification(address) => {
+                ("prepDisqualification", json!({ "address": address.into_param() }))
+            }
+            ProposalValue::StepPrice(price) => ("stepPrice", json!({ "stepPrice": price.clone().into_param() })),
+            ProposalValue::RewardFund(fund) => ("rewardFund", json!({ "iglobal": fund.clone().into_param() })),
+            ProposalValue::Other { name, value } => (name.as_str(), value.clone()),
+        };
+        json!({ "name": name, "value": value })
+    }
+}
+
+/// Reads and transactions for IISS, sent to the system SCORE, and for network proposals, sent to the governance SCORE
+///
+/// # Example
+///
+///  ```no_run
+/// # use icon_utils::{governance::{Delegation, Governance}, rpc::IconClient, units::Icx};
+/// let client = IconClient::new("https://api.icon.community/api/v3");
+/// let governance = Governance::new(&client);
+/// let address = "hxbe258ceb872e08851f1f59694dac2558708ece11".parse().unwrap();
+/// println!("staked {}", governance.get_stake(&address).unwrap().stake.format_icx());
+///
+/// let tx = governance
+///     .set_delegation(vec![Delegation { address: "hx5bfdb090f43a808005ffc27c25b213145e80b7cd".parse().unwrap(), value: Icx::new(100).into() }])
+///     .from(address)
+///     .step_limit(1_000_000)
+///     .nid(1)
+///     .build()
+///     .unwrap();
+/// ```
+pub struct Governance<'a, T = HttpTransport> {
+    client: &'a IconClient<T>,
+}
+
+impl<'a, T: Transport> Governance<'a, T> {
+    /// Creates a helper sending reads through `client`
+    pub fn new(client: &'a IconClient<T>) -> Self {
+        Governance { client }
+    }
+
+    /// Returns the stake of `address`
+    pub fn get_stake(&self, address: &Address) -> Result<Stake, RpcError> {
+        self.client.call(&query("getStake").param("address", address))
+    }
+
+    /// Returns the delegations of `address`
+    pub fn get_delegation(&self, address: &Address) -> Result<DelegationInfo, RpcError> {
+        self.client.call(&query("getDelegation").param("address", address))
+    }
+
+    /// Returns the unclaimed rewards of `address`
+    pub fn query_iscore(&self, address: &Address) -> Result<IScore, RpcError> {
+        self.client.call(&query("queryIScore").param("address", address))
+    }
+
+    /// Returns every P-Rep, ordered by ranking
+    pub fn get_preps(&self) -> Result<PRepList, RpcError> {
+        self.client.call(&query("getPReps"))
+    }
+
+    /// Returns the P-Rep registered by `address`
+    pub fn get_prep(&self, address: &Address) -> Result<PRep, RpcError> {
+        self.client.call(&query("getPRep").param("address", address))
+    }
+
+    /// Starts a `setStake` transaction, setting the sender's total stake to `value`
+    pub fn set_stake(&self, value: impl Into<Loop>) -> CallTransactionBuilder {
+        call("setStake").param("value", value.into())
+    }
+
+    /// Starts a `setDelegation` transaction, replacing all of the sender's delegations
+    pub fn set_delegation(&self, delegations: Vec<Delegation>) -> CallTransactionBuilder {
+        call("setDelegation").param("delegations", delegations)
+    }
+
+    /// Starts a `setBonderList` transaction, setting the accounts allowed to bond to the sending P-Rep
+    pub fn set_bonder_list(&self, bonders: &[Address]) -> CallTransactionBuilder {
+        call("setBonderList").param("bonderList", bonders.iter().collect::<Vec<_>>())
+    }
+
+    /// Starts a `claimIScore` transaction, claiming the sender's rewards
+    pub fn claim_iscore(&self) -> CallTransactionBuilder {
+        call("claimIScore")
+    }
+
+    /// Starts a `registerPRep` transaction registering the sender as a P-Rep, paying the registration fee
+    pub fn register_prep(&self, info: PRepInfo) -> CallTransactionBuilder {
+        info.apply(call("registerPRep")).value(Icx::new(PREP_REGISTRATION_FEE))
+    }
+
+    /// Starts a `setPRep` transaction updating the sender's P-Rep details
+    pub fn set_prep(&self, info: PRepInfo) -> CallTransactionBuilder {
+        info.apply(call("setPRep"))
+    }
+
+    /// Starts an `unregisterPRep` transaction unregistering the sender as a P-Rep
+    pub fn unregister_prep(&self) -> CallTransactionBuilder {
+        call("unregisterPRep")
+    }
+
+    /// Returns the network proposal registered by the transaction `id`
+    pub fn get_proposal(&self, id: &Hash256) -> Result<Proposal, RpcError> {
+        self.client.call(&gov_query("getProposal").param("id", id))
+    }
+
+    /// Returns the network proposals matching `filter`, newest first
+    pub fn get_proposals(&self, filter: ProposalFilter) -> Result<Vec<Proposal>, RpcError> {
+        let list: ProposalList = self.client.call(&filter.apply(gov_query("getProposals")))?;
+        Ok(list.proposals)
+    }
+
+    /// Starts a `registerProposal` transaction proposing `values` as one network proposal, paying the registration fee
+    ///
+    /// Only main P-Reps can register proposals.
+    ///
+    /// # Example
+    ///
+    ///  ```
+    /// # use icon_utils::{governance::{Governance, ProposalValue}, rpc::IconClient};
+    /// let client = IconClient::new("https://api.icon.community/api/v3");
+    /// let tx = Governance::new(&client)
+    ///     .register_proposal("Revision 25", "Enables revision 25", &[ProposalValue::Revision(25)])
+    ///     .from("hxbe258ceb872e08851f1f59694dac2558708ece11")
+    ///     .step_limit(1_000_000)
+    ///     .nid(1)
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(tx.to.to_string(), "cx0000000000000000000000000000000000000001");
+    /// ```
+    pub fn register_proposal(
+        &self,
+        title: &str,
+        description: &str,
+        values: &[ProposalValue],
+    ) -> CallTransactionBuilder {
+        let value = Value::Array(values.iter().map(ProposalValue::to_json).collect()).to_string();
+        gov_call("registerProposal")
+            .param("title", title)
+            .param("description", description)
+            .param("value", value.as_bytes())
+            .value(Icx::new(PROPOSAL_REGISTRATION_FEE))
+    }
+
+    /// Starts a `voteProposal` transaction voting for or against the proposal `id`
+    pub fn vote_proposal(&self, id: &Hash256, agree: bool) -> CallTransactionBuilder {
+        gov_call("voteProposal").param("id", id).param("vote", u64::from(agree))
+    }
+
+    /// Starts a `cancelProposal` transaction withdrawing a proposal the sender registered, while it's being voted on
+    pub fn cancel_proposal(&self, id: &Hash256) -> CallTransactionBuilder {
+        gov_call("cancelProposal").param("id", id)
+    }
+}
+
+fn query(method: &str) -> CallBuilder {
+    CallBuilder::new(&Address::SYSTEM_SCORE.to_string(), method)
+}
+
+fn call(method: &str) -> CallTransactionBuilder {
+    CallTransactionBuilder::new().to(Address::SYSTEM_SCORE).method(method)
+}
+
+fn gov_query(method: &str) -> CallBuilder {
+    CallBuilder::new(&Address::GOVERNANCE_SCORE.to_string(), method)
+}
+
+fn gov_call(method: &str) -> CallTransactionBuilder {
+    CallTransactionBuilder::new()
+        .to(Address::GOVERNANCE_SCORE)
+        .method(method)
+}
@@ -0,0 +1,67 @@
+//! # Icon HD Wallet
+//! `hd` is a module for deriving many ICON accounts from one BIP39 seed, along the BIP44 path `m/44'/4801368'/0'/0/{index}`.
+use bip32::{ChildNumber, DerivationPath, XPrv};
+
+use crate::wallet::{Wallet, WalletError, COIN_TYPE};
+
+/// A hierarchical deterministic wallet, deriving ICON accounts from a seed
+///
+/// # Example
+///
+///  ```
+/// # use icon_utils::{hd::HdWallet, wallet::Wallet};
+/// let phrase = Wallet::generate_mnemonic(24).unwrap();
+/// let hd = HdWallet::from_mnemonic(&phrase, "").unwrap();
+/// let first = hd.derive_account(0).unwrap();
+/// let second = hd.derive_account(1).unwrap();
+/// assert_ne!(first.address(), second.address());
+/// ```
+pub struct HdWallet {
+    master: XPrv,
+    /// The `m/44'/4801368'/0'/0` node, which accounts are derived from
+    external: XPrv,
+}
+
+impl HdWallet {
+    /// Creates an HD wallet from a BIP39 mnemonic and optional passphrase
+    pub fn from_mnemonic(phrase: &str, passphrase: &str) -> Result<Self, WalletError> {
+        let mnemonic = bip39::Mnemonic::parse(phrase).map_err(|e| WalletError::InvalidMnemonic(e.to_string()))?;
+        HdWallet::from_seed(&mnemonic.to_seed(passphrase))
+    }
+
+    /// Creates an HD wallet from a BIP39 seed
+    pub fn from_seed(seed: &[u8]) -> Result<Self, WalletError> {
+        let master = XPrv::new(seed).map_err(derive_error)?;
+        let mut external = master.clone();
+        for child in [
+            ChildNumber::new(44, true),
+            ChildNumber::new(COIN_TYPE, true),
+            ChildNumber::new(0, true),
+            ChildNumber::new(0, false),
+        ] {
+            external = external.derive_child(child.map_err(derive_error)?).map_err(derive_error)?;
+        }
+        Ok(HdWallet { master, external })
+    }
+
+    /// Derives the wallet of the account at `index`, at path `m/44'/4801368'/0'/0/{index}`
+    pub fn derive_account(&self, index: u32) -> Result<Wallet, WalletError> {
+        let child = ChildNumber::new(index, false).map_err(derive_error)?;
+        let key = self.external.derive_child(child).map_err(derive_error)?;
+        Wallet::from_bytes(&key.private_key().to_bytes())
+    }
+
+    /// Derives the wallet at an arbitrary BIP32 path, such as `m/44'/4801368'/1'/0/0`
+    pub fn derive_path(&self, path: &str) -> Result<Wallet, WalletError> {
+        let path: DerivationPath = path.parse().map_err(derive_error)?;
+        let mut key = self.master.clone();
+        for child in path.iter() {
+            key = key.derive_child(child).map_err(derive_error)?;
+        }
+        Wallet::from_bytes(&key.private_key().to_bytes())
+    }
+}
+
+fn derive_error(e: bip32::Error) -> WalletError {
+    WalletError::FailedToDerive(e.to_string())
+}
@@ -0,0 +1,109 @@
+//! # Icon Hex
+//! `icon_hex` holds the helpers for the `0x` prefixed hex ICON uses for integers and bytes.
+//!
+//! Integers are written in lowercase without leading zeros, so `0x0` for zero and `0x10` for sixteen, and bytes are
+//! written as two lowercase digits per byte. The decoders only accept that canonical form, since a value written any
+//! other way serializes differently and breaks the signature of the transaction it's part of.
+use crate::types::HexError;
+
+/// Writes an integer as `0x` prefixed lowercase hex without leading zeros
+///
+/// # Example
+///
+///  ```
+/// # use icon_utils::icon_hex::to_hex_no_pad;
+/// assert_eq!(to_hex_no_pad(0), "0x0");
+/// assert_eq!(to_hex_no_pad(1_000_000_000_000_000_000), "0xde0b6b3a7640000");
+/// ```
+pub fn to_hex_no_pad(value: u128) -> String {
+    format!("{:#x}", value)
+}
+
+/// Parses a `0x` prefixed integer in the form [`to_hex_no_pad`] writes
+///
+/// # Example
+///
+///  ```
+/// # use icon_utils::{icon_hex::from_hex_int, types::HexError};
+/// assert_eq!(from_hex_int("0xde0b6b3a7640000"), Ok(1_000_000_000_000_000_000));
+/// assert_eq!(from_hex_int("0x0"), Ok(0));
+/// assert_eq!(from_hex_int("0x01"), Err(HexError::LeadingZeros("0x01".to_owned())));
+/// assert_eq!(from_hex_int("0xDE"), Err(HexError::Uppercase("0xDE".to_owned())));
+/// ```
+pub fn from_hex_int(value: &str) -> Result<u128, HexError> {
+    u128::from_str_radix(int_digits(value)?, 16).map_err(|_| HexError::InvalidHex(value.to_owned()))
+}
+
+/// Parses a `0x` prefixed integer with an optional `-` sign, the way negative integers are written in params
+///
+/// # Example
+///
+///  ```
+/// # use icon_utils::{icon_hex::from_hex_signed, types::HexError};
+/// assert_eq!(from_hex_signed("-0x10"), Ok(-16));
+/// assert_eq!(from_hex_signed("0x10"), Ok(16));
+/// assert_eq!(from_hex_signed("-0x01"), Err(HexError::LeadingZeros("-0x01".to_owned())));
+/// ```
+pub fn from_hex_signed(value: &str) -> Result<i128, HexError> {
+    let invalid = || HexError::InvalidHex(value.to_owned());
+    let Some(magnitude) = value.strip_prefix('-') else {
+        return i128::try_from(from_hex_int(value)?).map_err(|_| invalid());
+    };
+    // Errors name the whole value, sign included
+    let digits = int_digits(magnitude).map_err(|e| match e {
+        HexError::MissingPrefix(_) => HexError::MissingPrefix(value.to_owned()),
+        HexError::LeadingZeros(_) => HexError::LeadingZeros(value.to_owned()),
+        HexError::Uppercase(_) => HexError::Uppercase(value.to_owned()),
+        _ => invalid(),
+    })?;
+    let magnitude = u128::from_str_radix(digits, 16).map_err(|_| invalid())?;
+    0i128.checked_sub_unsigned(magnitude).ok_or_else(invalid)
+}
+
+/// Returns the digits of a `0x` prefixed integer of any size, rejecting the forms [`to_hex_no_pad`] doesn't write
+pub(crate) fn int_digits(value: &str) -> Result<&str, HexError> {
+    let digits = digits(value)?;
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(HexError::InvalidHex(value.to_owned()));
+    }
+    if digits.len() > 1 && digits.starts_with('0') {
+        return Err(HexError::LeadingZeros(value.to_owned()));
+    }
+    Ok(digits)
+}
+
+/// Writes bytes as `0x` prefixed lowercase hex
+///
+/// # Example
+///
+///  ```
+/// # use icon_utils::icon_hex::encode_bytes_0x;
+/// assert_eq!(encode_bytes_0x(b"hello"), "0x68656c6c6f");
+/// assert_eq!(encode_bytes_0x(&[]), "0x");
+/// ```
+pub fn encode_bytes_0x(bytes: &[u8]) -> String {
+    format!("0x{}", hex::encode(bytes))
+}
+
+/// Parses `0x` prefixed lowercase hex bytes, the inverse of [`encode_bytes_0x`]
+///
+/// # Example
+///
+///  ```
+/// # use icon_utils::icon_hex::decode_bytes_0x;
+/// assert_eq!(decode_bytes_0x("0x68656c6c6f").unwrap(), b"hello");
+/// assert!(decode_bytes_0x("68656c6c6f").is_err());
+/// assert!(decode_bytes_0x("0x0").is_err());
+/// ```
+pub fn decode_bytes_0x(value: &str) -> Result<Vec<u8>, HexError> {
+    hex::decode(digits(value)?).map_err(|_| HexError::InvalidHex(value.to_owned()))
+}
+
+/// Returns the digits after the `0x` prefix, rejecting uppercase digits
+fn digits(value: &str) -> Result<&str, HexError> {
+    let digits = value.strip_prefix("0x").ok_or_else(|| HexError::MissingPrefix(value.to_owned()))?;
+    if digits.bytes().any(|b| b.is_ascii_uppercase()) {
+        return Err(HexError::Uppercase(value.to_owned()));
+    }
+    Ok(digits)
+}
@@ -0,0 +1,691 @@
+//! # Icon Keystore
+//! `keystore` is a module for ICON keystore files, as used by ICONex and the official SDKs. It creates and reads
+//! keystores, manages directories of them, reads ICONex wallet backups and keeps wallets encrypted in memory.
+use aes::{cipher::{KeyIvInit, StreamCipher}, Aes128};
+use eth_keystore::{CipherparamsJson, CryptoJson, KdfType, KdfparamsType};
+use hmac::Hmac;
+use pbkdf2::pbkdf2;
+use rand::{rngs::OsRng, RngCore};
+use scrypt::{scrypt, Params as ScryptParams};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use sha3::{Digest, Keccak256};
+use subtle::ConstantTimeEq;
+#[cfg(not(target_arch = "wasm32"))]
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
+#[cfg(not(target_arch = "wasm32"))]
+use std::thread;
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::{Path, PathBuf};
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::{Duration, Instant, SystemTime};
+use thiserror::Error;
+use uuid::Uuid;
+use zeroize::Zeroizing;
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::signer::{DeterministicSigner, Signature, Signer};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::types::Address;
+use crate::wallet::{Wallet, WalletError};
+
+type Aes128Ctr = ctr::Ctr128BE<Aes128>;
+
+const CIPHER: &str = "aes-128-ctr";
+const PRF: &str = "hmac-sha256";
+const DKLEN: u8 = 32;
+const SALT_SIZE: usize = 32;
+const IV_SIZE: usize = 16;
+/// Limits on the scrypt parameters of keystores being decrypted, so a keystore file can't make decrypting it use
+/// gigabytes of memory or hours of CPU. 2^20 with r = 8 already takes 1 GiB, 64 times the ICONex default.
+const MAX_SCRYPT_N: u32 = 1 << 20;
+const MAX_SCRYPT_R: u32 = 8;
+const MAX_SCRYPT_P: u32 = 16;
+/// Limit on the PBKDF2 iterations of keystores being decrypted, four times the 262144 of geth
+const MAX_PBKDF2_C: u32 = 1 << 20;
+/// Limit on the derived key length, only the first 32 bytes of which are used
+const MAX_DKLEN: u8 = 64;
+
+#[derive(Error, Debug)]
+// Error struct for keystore creation and decryption
+pub enum KeystoreError {
+    #[error("Invalid key derivation parameters: {0}")]
+    InvalidKdfParams(String),
+    #[error("Invalid cipher parameters: {0}")]
+    InvalidCipherParams(String),
+    #[error("Unsupported keystore: {0}")]
+    Unsupported(String),
+    #[error("Wrong keystore password")]
+    WrongPassword,
+    #[error("No keystore for {0}")]
+    NotFound(String),
+    #[error("Keystore is for {expected} but holds the key of {actual}")]
+    AddressMismatch { expected: String, actual: String },
+    #[error("Failed to write keystore: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Failed to encode keystore: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// Key derivation parameters used when encrypting a keystore.
+/// The default matches the parameters used by ICONex.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KdfParams {
+    /// scrypt, `n` must be a power of two
+    Scrypt { n: u32, r: u32, p: u32 },
+    /// PBKDF2 with HMAC-SHA256 and `c` iterations
+    Pbkdf2 { c: u32 },
+}
+
+impl Default for KdfParams {
+    fn default() -> Self {
+        KdfParams::Scrypt { n: 16384, r: 8, p: 1 }
+    }
+}
+
+/// An ICON keystore document.
+/// This is a V3 keystore with the `address` and `coinType` fields ICONex expects.
+///
+/// # Example
+///
+///  ```
+/// # use icon_utils::{keystore::{KdfParams, Keystore}, wallet::Wallet};
+/// let wallet = Wallet::new("68ee9ca94b71c42ba79375b8677e29a717fb8072bcb17cd5ee288f9b77bc2894").unwrap();
+/// let keystore = Keystore::create(&wallet, "password", &KdfParams::Pbkdf2 { c: 1024 }).unwrap();
+/// let json = keystore.to_json().unwrap();
+/// let restored = Keystore::from_json(&json).unwrap().decrypt("password").unwrap();
+/// assert_eq!(restored.address(), wallet.address());
+/// ```
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Keystore {
+    pub address: String,
+    #[serde(alias = "Crypto")]
+    pub crypto: CryptoJson,
+    pub id: Uuid,
+    pub version: u8,
+    #[serde(rename = "coinType")]
+    pub coin_type: String,
+}
+
+/// Former name of [`Keystore`]
+pub type IconKeystore = Keystore;
+
+impl Keystore {
+    /// Encrypts a wallet into a keystore document with the given key derivation parameters
+    pub fn create(wallet: &Wallet, password: &str, params: &KdfParams) -> Result<Self, KeystoreError> {
+        encrypt(&*wallet.export_private_key(), &wallet.address(), password, params)
+    }
+
+    /// Reads a keystore document from JSON
+    pub fn from_json(json: &str) -> Result<Self, KeystoreError> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// Encodes the keystore document as JSON
+    pub fn to_json(&self) -> Result<String, KeystoreError> {
+        Ok(serde_json::to_string(self)?)
+    }
+
+    /// Decrypts the keystore with its password, the inverse of [`Keystore::create`]
+    pub fn decrypt(&self, password: &str) -> Result<Wallet, WalletError> {
+        let mut privkey = self.decrypt_key(password)?;
+        let wallet = Wallet::from_bytes(&privkey);
+        privkey.fill(0);
+        wallet
+    }
+
+    /// Re-encrypts a keystore under a new password and key derivation parameters, for rotating keystore passwords
+    ///
+    /// `path_or_json` is either the keystore JSON or the path of a keystore file. The private key is only held
+    /// while it's re-encrypted, and the returned keystore keeps the address and id of the original, with a new salt
+    /// and IV. Nothing is written, so the original file stays valid until the caller replaces it.
+    ///
+    /// # Example
+    ///
+    ///  ```
+    /// # use icon_utils::{keystore::{KdfParams, Keystore}, wallet::Wallet};
+    /// # let wallet = Wallet::new("68ee9ca94b71c42ba79375b8677e29a717fb8072bcb17cd5ee288f9b77bc2894").unwrap();
+    /// # let json = Keystore::create(&wallet, "old password", &KdfParams::Pbkdf2 { c: 1024 }).unwrap().to_json().unwrap();
+    /// let rotated = Keystore::change_password(&json, "old password", "new password", &KdfParams::Pbkdf2 { c: 2048 }).unwrap();
+    /// assert_eq!(rotated.decrypt("new password").unwrap().address(), wallet.address());
+    /// assert!(rotated.decrypt("old password").is_err());
+    /// ```
+    pub fn change_password(path_or_json: &str, old: &str, new: &str, params: &KdfParams) -> Result<Self, KeystoreError> {
+        let keystore = if path_or_json.trim_start().starts_with('{') {
+            Keystore::from_json(path_or_json)?
+        } else {
+            Keystore::from_json(&std::fs::read_to_string(path_or_json)?)?
+        };
+        let mut privkey = keystore.decrypt_key(old)?;
+        let rotated = encrypt(&privkey, &keystore.address, new, params);
+        privkey.fill(0);
+        let mut rotated = rotated?;
+        rotated.id = keystore.id;
+        Ok(rotated)
+    }
+
+    /// Checks the password against the MAC and decrypts the private key
+    fn decrypt_key(&self, password: &str) -> Result<Vec<u8>, KeystoreError> {
+        let crypto = &self.crypto;
+        if crypto.cipher != CIPHER {
+            return Err(KeystoreError::Unsupported(format!("cipher {}", crypto.cipher)));
+        }
+        if crypto.cipherparams.iv.len() != IV_SIZE {
+            return Err(KeystoreError::InvalidCipherParams(format!("iv must be {} bytes, got {}", IV_SIZE, crypto.cipherparams.iv.len())));
+        }
+        let dklen = match &crypto.kdfparams {
+            KdfparamsType::Scrypt { dklen, .. } | KdfparamsType::Pbkdf2 { dklen, .. } => *dklen,
+        };
+        if !(DKLEN..=MAX_DKLEN).contains(&dklen) {
+            return Err(KeystoreError::InvalidKdfParams(format!("dklen must be {} to {}, got {}", DKLEN, MAX_DKLEN, dklen)));
+        }
+        // Wiped when dropped, on every return below
+        let key = Zeroizing::new(match &crypto.kdfparams {
+            KdfparamsType::Scrypt { dklen, n, p, r, salt } => {
+                if !n.is_power_of_two() || *n < 2 {
+                    return Err(KeystoreError::InvalidKdfParams(format!("scrypt n must be a power of two, got {}", n)));
+                }
+                if *n > MAX_SCRYPT_N || *r > MAX_SCRYPT_R || *p > MAX_SCRYPT_P {
+                    return Err(KeystoreError::InvalidKdfParams(format!(
+                        "scrypt n = {}, r = {}, p = {} exceed the limits of n = {}, r = {}, p = {}",
+                        n, r, p, MAX_SCRYPT_N, MAX_SCRYPT_R, MAX_SCRYPT_P
+                    )));
+                }
+                let params = ScryptParams::new(n.trailing_zeros() as u8, *r, *p)
+                    .map_err(|e| KeystoreError::InvalidKdfParams(e.to_string()))?;
+                let mut key = vec![0u8; *dklen as usize];
+                scrypt(password.as_bytes(), salt, &params, &mut key).map_err(|e| KeystoreError::InvalidKdfParams(e.to_string()))?;
+                key
+            }
+            KdfparamsType::Pbkdf2 { c, dklen, prf, salt } => {
+                if prf != PRF {
+                    return Err(KeystoreError::Unsupported(format!("prf {}", prf)));
+                }
+                if *c == 0 || *c > MAX_PBKDF2_C {
+                    return Err(KeystoreError::InvalidKdfParams(format!("pbkdf2 c must be 1 to {}, got {}", MAX_PBKDF2_C, c)));
+                }
+                let mut key = vec![0u8; *dklen as usize];
+                pbkdf2::<Hmac<Sha256>>(password.as_bytes(), salt, *c, &mut key);
+                key
+            }
+        });
+        let mac = Keccak256::new()
+            .chain_update(&key[16..32])
+            .chain_update(&crypto.ciphertext)
+            .finalize();
+        // Compared in constant time, so the time taken doesn't tell how much of a forged MAC was right
+        if !bool::from(mac.as_slice().ct_eq(crypto.mac.as_slice())) {
+            return Err(KeystoreError::WrongPassword);
+        }
+        let mut privkey = crypto.ciphertext.clone();
+        Aes128Ctr::new_from_slices(&key[..16], &crypto.cipherparams.iv)
+            .map_err(|e| KeystoreError::InvalidCipherParams(e.to_string()))?
+            .apply_keystream(&mut privkey);
+        Ok(privkey)
+    }
+}
+
+/// An account of an ICONex wallet backup, with the name it had in ICONex
+#[derive(Debug)]
+pub struct BackupAccount {
+    pub name: Option<String>,
+    pub keystore: Keystore,
+}
+
+impl BackupAccount {
+    /// Decrypts the account's keystore with its password
+    pub fn decrypt(&self, password: &str) -> Result<Wallet, WalletError> {
+        self.keystore.decrypt(password)
+    }
+}
+
+/// The ICON accounts of an ICONex wallet backup, for migrating from the official wallet
+///
+/// ICONex backs up all wallets as one JSON object keyed by address, where each wallet has a `name`, a `type`
+/// and its keystore under `priv`, as an object or a JSON string. The keystore of a single wallet and arrays of
+/// either shape are read too. Wallets of other coins, such as the ETH wallets ICONex also manages, are skipped.
+///
+/// # Example
+///
+///  ```
+/// # use icon_utils::{keystore::{IconexBackup, KdfParams, Keystore}, wallet::Wallet};
+/// # use serde_json::json;
+/// # let wallet = Wallet::new("68ee9ca94b71c42ba79375b8677e29a717fb8072bcb17cd5ee288f9b77bc2894").unwrap();
+/// # let keystore = Keystore::create(&wallet, "password", &KdfParams::Pbkdf2 { c: 1024 }).unwrap().to_json().unwrap();
+/// let json = json!({
+///     wallet.address(): { "name": "savings", "type": "icx", "priv": keystore, "tokens": {}, "createdAt": "1534921603822" },
+///     "0x6d6cf3c5e3fa3a2dea4bb8fd9c03bd5c0f5e7e4f": { "name": "eth", "type": "eth", "priv": {}, "tokens": {} }
+/// });
+/// let backup = IconexBackup::from_json(&json.to_string()).unwrap();
+/// assert_eq!(backup.accounts.len(), 1);
+/// assert_eq!(backup.accounts[0].name.as_deref(), Some("savings"));
+/// assert_eq!(backup.decrypt_all("password").unwrap()[0].address(), wallet.address());
+/// ```
+#[derive(Debug)]
+pub struct IconexBackup {
+    pub accounts: Vec<BackupAccount>,
+}
+
+impl IconexBackup {
+    /// Reads the accounts of an ICONex backup from JSON
+    pub fn from_json(json: &str) -> Result<Self, KeystoreError> {
+        let mut accounts = Vec::new();
+        collect_accounts(serde_json::from_str(json)?, &mut accounts)?;
+        if accounts.is_empty() {
+            return Err(KeystoreError::Unsupported("no ICON accounts in backup".to_owned()));
+        }
+        Ok(IconexBackup { accounts })
+    }
+
+    /// Decrypts every account with one password, as ICONex uses for all wallets of a backup
+    pub fn decrypt_all(&self, password: &str) -> Result<Vec<Wallet>, WalletError> {
+        self.accounts.iter().map(|account| account.decrypt(password)).collect()
+    }
+}
+
+/// Finds the keystores in the JSON of an ICONex backup
+fn collect_accounts(value: serde_json::Value, accounts: &mut Vec<BackupAccount>) -> Result<(), KeystoreError> {
+    use serde_json::Value;
+    match value {
+        Value::Array(values) => values.into_iter().try_for_each(|value| collect_accounts(value, accounts)),
+        Value::Object(ref object) if object.contains_key("crypto") || object.contains_key("Crypto") => {
+            accounts.push(BackupAccount { name: None, keystore: serde_json::from_value(value)? });
+            Ok(())
+        }
+        Value::Object(mut object) if object.contains_key("priv") => {
+            let coin = object.get("type").or_else(|| object.get("coinType")).and_then(Value::as_str);
+            if coin.is_some_and(|coin| coin != "icx") {
+                return Ok(());
+            }
+            let name = object.get("name").and_then(Value::as_str).map(str::to_owned);
+            let keystore = match object.remove("priv").unwrap_or_default() {
+                Value::String(json) => Keystore::from_json(&json)?,
+                value => serde_json::from_value(value)?,
+            };
+            accounts.push(BackupAccount { name, keystore });
+            Ok(())
+        }
+        Value::Object(object) => object.into_iter().try_for_each(|(_, value)| collect_accounts(value, accounts)),
+        _ => Err(KeystoreError::Unsupported("ICONex backup must be a JSON object or array".to_owned())),
+    }
+}
+
+/// How long a [`LockedWallet`] stays unlocked without signing anything
+#[cfg(not(target_arch = "wasm32"))]
+pub const DEFAULT_IDLE_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+/// A wallet that only holds its encrypted keystore until it's unlocked, for long running daemons
+///
+/// [`LockedWallet::unlock`] decrypts the key and the wallet signs as a [`Signer`] until it has been idle for the idle
+/// timeout, or until [`LockedWallet::lock`] is called. Each signature resets the timeout. Once it runs out a background
+/// thread, started by the unlock, drops the key whether or not the wallet is used again, which zeroizes it, and signing
+/// fails with [`WalletError::Locked`] until the wallet is unlocked again. Dropping the wallet locks it too.
+///
+/// # Example
+///
+///  ```
+/// # use icon_utils::{keystore::{KdfParams, Keystore, LockedWallet}, signer::Signer, wallet::{Wallet, WalletError}};
+/// # use std::time::Duration;
+/// # let wallet = Wallet::new("68ee9ca94b71c42ba79375b8677e29a717fb8072bcb17cd5ee288f9b77bc2894").unwrap();
+/// # let keystore = Keystore::create(&wallet, "password", &KdfParams::Pbkdf2 { c: 1024 }).unwrap();
+/// let locked = LockedWallet::new(keystore).unwrap().idle_timeout(Duration::from_millis(50));
+/// assert!(matches!(locked.sign_hash(&[1; 32]), Err(WalletError::Locked)));
+///
+/// locked.unlock("password").unwrap();
+/// assert_eq!(locked.sign_hash(&[1; 32]).unwrap(), wallet.sign_hash(&[1; 32]).unwrap());
+///
+/// std::thread::sleep(Duration::from_millis(200));
+/// assert!(!locked.is_unlocked());
+/// assert!(matches!(locked.sign_hash(&[1; 32]), Err(WalletError::Locked)));
+/// ```
+#[cfg(not(target_arch = "wasm32"))]
+pub struct LockedWallet {
+    keystore: Keystore,
+    address: Address,
+    idle_timeout: Duration,
+    shared: Arc<Shared>,
+}
+
+/// The state of a [`LockedWallet`] shared with the thread that locks it once it's idle
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Default)]
+struct Shared {
+    state: Mutex<State>,
+    /// Wakes the thread when the session is replaced or dropped
+    changed: Condvar,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Default)]
+struct State {
+    session: Option<Session>,
+    /// Counts the unlocks, so the thread of an earlier unlock stops instead of watching the new session
+    unlocks: u64,
+}
+
+/// The decrypted key of an unlocked wallet, and when it was last used
+#[cfg(not(target_arch = "wasm32"))]
+struct Session {
+    wallet: Wallet,
+    last_used: Instant,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl LockedWallet {
+    /// Creates a locked wallet from a keystore, which stays unlocked for [`DEFAULT_IDLE_TIMEOUT`] once unlocked
+    pub fn new(keystore: Keystore) -> Result<Self, KeystoreError> {
+        let address = keystore
+            .address
+            .parse()
+            .map_err(|_| KeystoreError::Unsupported(format!("address {}", keystore.address)))?;
+        Ok(LockedWallet { keystore, address, idle_timeout: DEFAULT_IDLE_TIMEOUT, shared: Arc::default() })
+    }
+
+    /// Encrypts a wallet and keeps only the keystore, dropping the wallet
+    pub fn from_wallet(wallet: Wallet, password: &str, params: &KdfParams) -> Result<Self, KeystoreError> {
+        LockedWallet::new(Keystore::create(&wallet, password, params)?)
+    }
+
+    /// Sets how long the wallet stays unlocked without signing anything
+    pub fn idle_timeout(mut self, idle_timeout: Duration) -> Self {
+        self.idle_timeout = idle_timeout;
+        self
+    }
+
+    /// Decrypts the key, so the wallet can sign until it's idle for the idle timeout
+    ///
+    /// Starts a thread that drops the key once the wallet has been idle for the idle timeout, replacing the thread of
+    /// any earlier unlock. Fails without unlocking if the key isn't the key of the keystore's address, since that
+    /// address is the one the wallet reports as its [`Signer::address`].
+    pub fn unlock(&self, password: &str) -> Result<(), WalletError> {
+        let wallet = self.keystore.decrypt(password)?;
+        if Signer::address(&wallet) != self.address {
+            return Err(KeystoreError::AddressMismatch { expected: self.address.to_string(), actual: wallet.address() }.into());
+        }
+        let unlocks = {
+            let mut state = self.shared.state();
+            state.session = Some(Session { wallet, last_used: Instant::now() });
+            state.unlocks += 1;
+            state.unlocks
+        };
+        self.shared.changed.notify_all();
+        let (shared, idle_timeout) = (Arc::clone(&self.shared), self.idle_timeout);
+        if let Err(err) = thread::Builder::new()
+            .name("icon-locked-wallet".to_owned())
+            .spawn(move || shared.lock_when_idle(unlocks, idle_timeout))
+        {
+            self.lock();
+            return Err(WalletError::Signer(format!("Failed to start the idle timer: {}", err)));
+        }
+        Ok(())
+    }
+
+    /// Drops the decrypted key
+    pub fn lock(&self) {
+        self.shared.state().session.take();
+        self.shared.changed.notify_all();
+    }
+
+    /// Returns true if the wallet holds its decrypted key
+    pub fn is_unlocked(&self) -> bool {
+        self.shared.state().session.is_some()
+    }
+
+    /// Returns the encrypted keystore of the wallet
+    pub fn keystore(&self) -> &Keystore {
+        &self.keystore
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Shared {
+    /// Waits until the session of unlock number `unlocks` has been idle for `idle_timeout` and drops it, returning
+    /// early if it's locked or replaced first
+    fn lock_when_idle(&self, unlocks: u64, idle_timeout: Duration) {
+        let mut state = self.state();
+        while state.unlocks == unlocks {
+            let Some(session) = &state.session else { return };
+            let Some(remaining) = idle_timeout.checked_sub(session.last_used.elapsed()).filter(|d| !d.is_zero()) else {
+                state.session = None;
+                return;
+            };
+            state = self.changed.wait_timeout(state, remaining).unwrap_or_else(PoisonError::into_inner).0;
+        }
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        // The session is only replaced while locked, so a panic elsewhere can't leave it half written
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Drop for LockedWallet {
+    fn drop(&mut self) {
+        self.lock();
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Signer for LockedWallet {
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    fn sign_hash(&self, hash: &[u8; 32]) -> Result<Signature, WalletError> {
+        let mut state = self.shared.state();
+        let session = state.session.as_mut().ok_or(WalletError::Locked)?;
+        // The thread may not have woken up yet, so a session past its timeout is refused all the same
+        if session.last_used.elapsed() >= self.idle_timeout {
+            state.session = None;
+            return Err(WalletError::Locked);
+        }
+        session.last_used = Instant::now();
+        session.wallet.sign_hash(hash)
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl DeterministicSigner for LockedWallet {}
+
+/// A directory of keystore files, one per account, as kept by ICONex and geth
+///
+/// New keystores are named `UTC--<time>--<address>` after the time they're created, which sorts them by age. Files
+/// that aren't keystores are ignored, so the directory can hold other files.
+///
+/// # Example
+///
+///  ```
+/// # use icon_utils::keystore::{KdfParams, KeystoreDir};
+/// # let path = std::env::temp_dir().join(format!("icon-keystores-{}", std::process::id()));
+/// let dir = KeystoreDir::open(&path).unwrap();
+/// let params = KdfParams::Pbkdf2 { c: 1024 };
+/// let (wallet, file) = dir.create("password", &params).unwrap();
+/// assert!(file.file_name().unwrap().to_str().unwrap().starts_with("UTC--"));
+///
+/// let address = wallet.address().parse().unwrap();
+/// assert_eq!(dir.addresses().unwrap(), vec![address]);
+/// assert_eq!(dir.unlock(&address, "password").unwrap().address(), wallet.address());
+/// # std::fs::remove_dir_all(&path).unwrap();
+/// ```
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, Clone)]
+pub struct KeystoreDir {
+    path: PathBuf,
+}
+
+/// A keystore file found in a [`KeystoreDir`]
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeystoreFile {
+    pub address: Address,
+    pub path: PathBuf,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl KeystoreDir {
+    /// Opens a keystore directory, creating it if it doesn't exist
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self, KeystoreError> {
+        let path = path.into();
+        std::fs::create_dir_all(&path)?;
+        Ok(KeystoreDir { path })
+    }
+
+    /// Returns the path of the directory
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Lists the keystore files in the directory, sorted by file name
+    pub fn list(&self) -> Result<Vec<KeystoreFile>, KeystoreError> {
+        let mut files = Vec::new();
+        for entry in std::fs::read_dir(&self.path)? {
+            let path = entry?.path();
+            if !path.is_file() {
+                continue;
+            }
+            let keystore = match std::fs::read_to_string(&path).ok().and_then(|json| Keystore::from_json(&json).ok()) {
+                Some(keystore) => keystore,
+                None => continue,
+            };
+            if let Ok(address) = keystore.address.parse() {
+                files.push(KeystoreFile { address, path });
+            }
+        }
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(files)
+    }
+
+    /// Lists the addresses of the accounts in the directory
+    pub fn addresses(&self) -> Result<Vec<Address>, KeystoreError> {
+        Ok(self.list()?.into_iter().map(|file| file.address).collect())
+    }
+
+    /// Loads the keystore of `address`, the oldest one if there are several
+    pub fn load(&self, address: &Address) -> Result<Keystore, KeystoreError> {
+        let file = self.list()?.into_iter().find(|file| file.address == *address);
+        let file = file.ok_or_else(|| KeystoreError::NotFound(address.to_string()))?;
+        Keystore::from_json(&std::fs::read_to_string(file.path)?)
+    }
+
+    /// Loads and decrypts the keystore of `address`
+    pub fn unlock(&self, address: &Address, password: &str) -> Result<Wallet, WalletError> {
+        self.load(address)?.decrypt(password)
+    }
+
+    /// Creates a new random account, returning its wallet and the path of its keystore
+    pub fn create(&self, password: &str, params: &KdfParams) -> Result<(Wallet, PathBuf), WalletError> {
+        let mut key = [0u8; 32];
+        OsRng.fill_bytes(&mut key);
+        let wallet = Wallet::from_bytes(&key);
+        key.fill(0);
+        let wallet = wallet?;
+        let path = self.import(&wallet, password, params)?;
+        Ok((wallet, path))
+    }
+
+    /// Encrypts a wallet into a new keystore file, returning its path
+    ///
+    /// Existing files are never overwritten, a name that's taken is retried with a later time.
+    pub fn import(&self, wallet: &Wallet, password: &str, params: &KdfParams) -> Result<PathBuf, KeystoreError> {
+        use std::io::Write;
+        let json = Keystore::create(wallet, password, params)?.to_json()?;
+        loop {
+            let path = self.path.join(keystore_file_name(&wallet.address(), SystemTime::now()));
+            match std::fs::OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(mut file) => {
+                    file.write_all(json.as_bytes())?;
+                    return Ok(path);
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+}
+
+/// Names a keystore file like geth, `UTC--2024-01-02T03-04-05.123456789Z--hx...`
+#[cfg(not(target_arch = "wasm32"))]
+fn keystore_file_name(address: &str, time: SystemTime) -> String {
+    let since_epoch = time.duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default();
+    let secs = since_epoch.as_secs();
+    let (year, month, day) = civil_from_days((secs / 86_400) as i64);
+    let secs_of_day = secs % 86_400;
+    format!(
+        "UTC--{:04}-{:02}-{:02}T{:02}-{:02}-{:02}.{:09}Z--{}",
+        year,
+        month,
+        day,
+        secs_of_day / 3600,
+        secs_of_day / 60 % 60,
+        secs_of_day % 60,
+        since_epoch.subsec_nanos(),
+        address
+    )
+}
+
+/// Converts days since the unix epoch to a proleptic Gregorian date
+#[cfg(not(target_arch = "wasm32"))]
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * shifted_month + 2) / 5 + 1) as u32;
+    let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 } as u32;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// Encrypts a private key into an ICON keystore document
+pub(crate) fn encrypt(privkey: &[u8], address: &str, password: &str, params: &KdfParams) -> Result<Keystore, KeystoreError> {
+    let mut salt = vec![0u8; SALT_SIZE];
+    OsRng.fill_bytes(&mut salt);
+    let mut iv = vec![0u8; IV_SIZE];
+    OsRng.fill_bytes(&mut iv);
+
+    let mut key = vec![0u8; DKLEN as usize];
+    let (kdf, kdfparams) = match *params {
+        KdfParams::Scrypt { n, r, p } => {
+            if !n.is_power_of_two() || n < 2 {
+                return Err(KeystoreError::InvalidKdfParams(format!("scrypt n must be a power of two, got {}", n)));
+            }
+            let scrypt_params = ScryptParams::new(n.trailing_zeros() as u8, r, p)
+                .map_err(|e| KeystoreError::InvalidKdfParams(e.to_string()))?;
+            scrypt(password.as_bytes(), &salt, &scrypt_params, &mut key)
+                .map_err(|e| KeystoreError::InvalidKdfParams(e.to_string()))?;
+            (KdfType::Scrypt, KdfparamsType::Scrypt { dklen: DKLEN, n, p, r, salt })
+        }
+        KdfParams::Pbkdf2 { c } => {
+            if c == 0 {
+                return Err(KeystoreError::InvalidKdfParams("pbkdf2 c must be at least 1".to_owned()));
+            }
+            pbkdf2::<Hmac<Sha256>>(password.as_bytes(), &salt, c, &mut key);
+            (KdfType::Pbkdf2, KdfparamsType::Pbkdf2 { c, dklen: DKLEN, prf: PRF.to_owned(), salt })
+        }
+    };
+
+    let mut ciphertext = privkey.to_vec();
+    Aes128Ctr::new(key[..16].into(), iv[..].into()).apply_keystream(&mut ciphertext);
+    let mac = Keccak256::new()
+        .chain_update(&key[16..32])
+        .chain_update(&ciphertext)
+        .finalize();
+
+    Ok(Keystore {
+        address: address.to_owned(),
+        crypto: CryptoJson {
+            cipher: CIPHER.to_owned(),
+            cipherparams: CipherparamsJson { iv },
+            ciphertext,
+            kdf,
+            kdfparams,
+            mac: mac.to_vec(),
+        },
+        id: Uuid::new_v4(),
+        version: 3,
+        coin_type: "icx".to_owned(),
+    })
+}
@@ -1,18 +1,114 @@
 
 //! #   Icon Utils
 //! `icon_utils` is a library for serializing and signing transactions for the icon network.
+//!
+//! The serializer, transaction builders and ICON types are always available, the rest can be turned off with
+//! `default-features = false`: `derive` for the Transaction derive, `wallet` for signing, `keystore` for encrypted
+//! keystores and `rpc` for the JSON-RPC client and the SCORE helpers built on it.
+pub mod abi;
+pub mod conformance;
+#[cfg(feature = "rpc")]
+pub mod contracts;
+#[cfg(feature = "rpc")]
+pub mod events;
+#[cfg(feature = "capi")]
+pub mod ffi;
+#[cfg(feature = "rpc")]
+pub mod fees;
+#[cfg(feature = "rpc")]
+pub mod governance;
+#[cfg(feature = "wallet")]
+pub mod hd;
+pub mod icon_hex;
+#[cfg(feature = "keystore")]
+pub mod keystore;
+pub mod network;
+pub mod params;
+#[cfg(feature = "rpc")]
+pub mod rpc;
+#[cfg(feature = "wallet")]
+pub mod signer;
+pub mod transaction;
+pub mod types;
+pub mod units;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 pub mod serializer {
     
     //! # Icon Transaction Serializer
     //! `serializer` is a module for serializing structs to the transaction format for the icon network.
-    use icon_derive::Transaction;
-    use serde::{ser, Serialize};
+    #[cfg(feature = "derive")]
+    pub use icon_derive::Transaction;
+    use std::io;
+    use num_bigint::BigUint;
+    use serde::{ser, Deserialize, Serialize};
+    use sha3::{Digest, Sha3_256};
     use thiserror::Error;
 #[derive(Error, Debug)]
 // Error struct for Serialization
 pub enum SerializeError {
-    #[error("Failed to serialize")]
-    FailedToSerialize(String)
+    #[error("Failed to serialize: {0}")]
+    FailedToSerialize(String),
+    #[error("Cannot serialize {type_name}, ICON transactions have no such type")]
+    UnsupportedType { type_name: &'static str },
+    #[error("Cannot serialize float {0}, only finite floats can be written as decimals")]
+    Float(f64),
+    #[error("Map keys must be strings, numbers or booleans")]
+    NonStringKey,
+    #[error("Serialized output is not valid UTF-8")]
+    InvalidUtf8,
+    #[error("Cannot serialize null, the serializer is set to NullMode::Error")]
+    Null,
+    #[error("Params are nested more than {max_depth} levels deep")]
+    DepthExceeded { max_depth: usize },
+    #[error("Serialized transaction is longer than {max_len} bytes")]
+    OutputTooLong { max_len: usize },
+    #[error("Failed to write serialized output: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("{source} at {path}")]
+    AtPath { path: String, source: Box<SerializeError> }
+}
+impl SerializeError {
+    /// Returns the path to the value that failed, such as `params.data.params.amounts[2]`
+    ///
+    /// # Example
+    ///
+    ///  ```
+    /// # use icon_utils::serializer::{serialize_value, SerializeError};
+    /// # use serde_json::json;
+    /// let params = json!({ "data": { "params": { "amounts": ["0x1", "0x2", 0.5] } } });
+    /// let error = serialize_value("icx_sendTransaction", &params).unwrap_err();
+    /// assert_eq!(error.path(), Some("params.data.params.amounts[2]"));
+    /// assert!(matches!(error.kind(), SerializeError::UnsupportedType { type_name: "f64" }));
+    /// ```
+    pub fn path(&self) -> Option<&str> {
+        match self {
+            SerializeError::AtPath { path, .. } => Some(path),
+            _ => None,
+        }
+    }
+    /// Returns the underlying error, without the path
+    pub fn kind(&self) -> &SerializeError {
+        match self {
+            SerializeError::AtPath { source, .. } => source,
+            error => error,
+        }
+    }
+    fn at(self, segment: String) -> Self {
+        match self {
+            SerializeError::AtPath { path, source } if path.starts_with('[') => SerializeError::AtPath { path: segment + &path, source },
+            SerializeError::AtPath { path, source } => SerializeError::AtPath { path: segment + "." + &path, source },
+            // Write errors come from the writer, not from a value
+            SerializeError::Io(e) => SerializeError::Io(e),
+            error => SerializeError::AtPath { path: segment, source: Box::new(error) },
+        }
+    }
+    fn at_field(self, name: &str) -> Self {
+        self.at(name.to_owned())
+    }
+    fn at_index(self, index: usize) -> Self {
+        self.at(format!("[{}]", index))
+    }
 }
 impl serde::ser::Error for SerializeError {
     fn custom<T: std::fmt::Display>(msg: T) -> Self {
@@ -21,25 +117,413 @@ impl serde::ser::Error for SerializeError {
 }
 
 /// Trait required for transaction serializing, can be implemented or derived with the icon_derive crate.
-/// If your struct params aren't in alphabetical order, use the sort attribute on the struct. Sub-structs should do this as well.
+/// Struct fields don't need to be declared in alphabetical order, the serializer sorts them, along with the keys of maps
+/// such as `HashMap`, so signatures don't depend on iteration order.
 /// # Example
 /// ```
+/// # use icon_utils::serializer::Transaction;
+/// # struct ExampleParams {}
 /// #[derive(Transaction)]
-/// #[sort]
 /// struct ExampleTransaction {
 ///     method: String,
 ///     params: ExampleParams
 /// }
 /// ```
+/// The method can also be set with the transaction attribute, in which case the struct itself is the params.
+/// ```
+/// # use serde::Serialize;
+/// # use icon_utils::serializer::{serialize_to_string, Transaction};
+/// #[derive(Serialize, Transaction)]
+/// #[transaction(method = "icx_sendTransaction")]
+/// struct TransferParams {
+///     to: String,
+///     from: String
+/// }
+/// let tx = TransferParams { to: "hx2".to_string(), from: "hx1".to_string() };
+/// assert_eq!(serialize_to_string(&tx).unwrap(), "icx_sendTransaction.params.{from.hx1.to.hx2}");
+/// ```
+/// Params keys come from serde, so `#[serde(rename = "...")]` and `#[serde(rename_all = "camelCase")]` rename them.
+/// Fields are sorted by their renamed keys, not by the Rust field names.
+/// ```
+/// # use serde::Serialize;
+/// # use icon_utils::serializer::{serialize_to_string, Transaction};
+/// #[derive(Serialize, Transaction)]
+/// #[transaction(method = "icx_sendTransaction")]
+/// #[serde(rename_all = "camelCase")]
+/// struct TransferParams {
+///     step_limit: String,
+///     data_type: String,
+///     #[serde(rename = "to")]
+///     recipient: String,
+/// }
+/// let tx = TransferParams { step_limit: "0x1".to_string(), data_type: "call".to_string(), recipient: "cx1".to_string() };
+/// assert_eq!(serialize_to_string(&tx).unwrap(), "icx_sendTransaction.params.{dataType.call.stepLimit.0x1.to.cx1}");
+/// ```
+/// Optional fields marked `#[serde(skip_serializing_if = "Option::is_none")]` are left out when `None`, both from the
+/// signed payload and from the `icx_sendTransaction` params, so the two always match. Without it `None` is written as null,
+/// see [`NullMode`] to change that.
+/// ```
+/// # use serde::Serialize;
+/// # use icon_utils::{serializer::{serialize_to_string, Transaction}, wallet::Wallet};
+/// #[derive(Serialize, Transaction)]
+/// #[transaction(method = "icx_sendTransaction")]
+/// struct TransferParams {
+///     to: String,
+///     #[serde(skip_serializing_if = "Option::is_none")]
+///     nonce: Option<String>,
+/// }
+/// let tx = TransferParams { to: "hx1".to_string(), nonce: None };
+/// assert_eq!(serialize_to_string(&tx).unwrap(), "icx_sendTransaction.params.{to.hx1}");
+/// # let wallet = Wallet::new("68ee9ca94b71c42ba79375b8677e29a717fb8072bcb17cd5ee288f9b77bc2894").unwrap();
+/// let signed = wallet.sign_transaction(&tx).unwrap();
+/// assert!(signed.to_rpc_params().get("nonce").is_none());
+/// ```
+/// Fields of `#[serde(flatten)]` structs and maps are merged into the parent and sorted along with its own fields, so
+/// builders can compose common fields with `dataType` specific ones. If flattened fields share a key, the last one
+/// wins, as it does in the JSON sent to the node.
+/// ```
+/// # use serde::Serialize;
+/// # use icon_utils::{serializer::{serialize_to_string, serialize_value, Transaction}, transaction::TransactionData};
+/// #[derive(Serialize)]
+/// struct Common { version: String, from: String, nid: String }
+/// #[derive(Serialize, Transaction)]
+/// #[transaction(method = "icx_sendTransaction")]
+/// struct CallParams {
+///     to: String,
+///     #[serde(flatten)]
+///     common: Common,
+///     #[serde(flatten)]
+///     data: TransactionData,
+/// }
+/// let tx = CallParams {
+///     to: "cx1".to_string(),
+///     common: Common { version: "0x3".to_string(), from: "hx1".to_string(), nid: "0x1".to_string() },
+///     data: TransactionData::Call { method: "transfer".to_string(), params: Default::default() },
+/// };
+/// let serialized = serialize_to_string(&tx).unwrap();
+/// assert_eq!(serialized, "icx_sendTransaction.params.{data.{method.transfer}.dataType.call.from.hx1.nid.0x1.to.cx1.version.0x3}");
+/// assert_eq!(serialize_value("icx_sendTransaction", &serde_json::to_value(&tx).unwrap()).unwrap(), serialized);
+/// ```
 pub trait Transaction {
     type Params;
     fn params(&self) -> &Self::Params;
     fn method(&self) -> &String;
 }
+/// References to transactions are transactions too, so both `serialize_to_string(tx)` and `serialize_to_string(&tx)` work.
+impl<T: Transaction + ?Sized> Transaction for &T {
+    type Params = T::Params;
+    fn params(&self) -> &Self::Params {
+        (**self).params()
+    }
+    fn method(&self) -> &String {
+        (**self).method()
+    }
+}
+/// An arbitrary size unsigned integer that serializes as `0x` prefixed hex, the way ICON expects values such as amounts in loop.
+///
+/// # Example
+/// ```
+/// # use icon_utils::serializer::BigNum;
+/// let value = BigNum::from(1_000_000_000_000_000_000u128);
+/// assert_eq!(value.to_string(), "0xde0b6b3a7640000");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct BigNum(pub BigUint);
+impl BigNum {
+    /// Parses a `0x` prefixed hex string, in lowercase without leading zeros like [`BigNum`] is written
+    ///
+    /// # Example
+    ///
+    ///  ```
+    /// # use icon_utils::serializer::BigNum;
+    /// assert_eq!(BigNum::from_hex("0xde0b6b3a7640000"), Some(BigNum::from(1_000_000_000_000_000_000u128)));
+    /// assert_eq!(BigNum::from_hex("0x01"), None);
+    /// assert_eq!(BigNum::from_hex("0xAB"), None);
+    /// ```
+    pub fn from_hex(value: &str) -> Option<Self> {
+        let digits = crate::icon_hex::int_digits(value).ok()?;
+        BigUint::parse_bytes(digits.as_bytes(), 16).map(BigNum)
+    }
+}
+impl std::fmt::Display for BigNum {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "0x{:x}", self.0)
+    }
+}
+impl From<BigUint> for BigNum {
+    fn from(value: BigUint) -> Self {
+        BigNum(value)
+    }
+}
+impl From<u64> for BigNum {
+    fn from(value: u64) -> Self {
+        BigNum(BigUint::from(value))
+    }
+}
+impl From<u128> for BigNum {
+    fn from(value: u128) -> Self {
+        BigNum(BigUint::from(value))
+    }
+}
+impl Serialize for BigNum {
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+impl<'de> Deserialize<'de> for BigNum {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        BigNum::from_hex(&value).ok_or_else(|| serde::de::Error::custom(format!("invalid hex integer {}", value)))
+    }
+}
+/// How the serializer handles floats, which ICON has no type for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FloatMode {
+    /// Fail with [`SerializeError::UnsupportedType`]
+    #[default]
+    Reject,
+    /// Write the shortest decimal string that parses back to the same float, such as `0.1`, with the dot escaped like in
+    /// any other string.
+    /// NaN and infinities fail with [`SerializeError::Float`].
+    Decimal,
+}
+/// How the serializer handles `None`, unit and JSON null values
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NullMode {
+    /// Write `\0`, the two characters ICON's serialization spec uses for null
+    #[default]
+    EscapedNull,
+    /// Leave out struct fields and map entries whose value is null. Nulls inside arrays keep their position and are
+    /// written as `\0`.
+    Omit,
+    /// Fail with [`SerializeError::Null`]
+    Error,
+}
+/// Options for the serializer
+///
+/// # Example
+///
+///  ```
+/// # use serde::Serialize;
+/// # use icon_utils::serializer::{serialize_to_string_with_options, NullMode, SerializerOptions, Transaction};
+/// #[derive(Serialize, Transaction)]
+/// #[transaction(method = "icx_call")]
+/// struct CallParams { to: String, data: Option<String> }
+/// let options = SerializerOptions { null_mode: NullMode::Omit, ..SerializerOptions::default() };
+/// let tx = CallParams { to: "cx1".to_string(), data: None };
+/// assert_eq!(serialize_to_string_with_options(&tx, options).unwrap(), "icx_call.params.{to.cx1}");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SerializerOptions {
+    /// Write struct fields and map entries in key order regardless of the order they're declared or iterated in, as ICON requires.
+    /// Map keys are compared by their serialized bytes.
+    pub sort_fields: bool,
+    pub float_mode: FloatMode,
+    pub null_mode: NullMode,
+    /// How many levels of arrays, maps and structs can be nested, for params built from untrusted data
+    pub max_depth: Option<usize>,
+    /// How many bytes the serialized transaction can take, including the method
+    pub max_output_len: Option<usize>,
+}
+impl Default for SerializerOptions {
+    fn default() -> Self {
+        SerializerOptions {
+            sort_fields: true,
+            float_mode: FloatMode::default(),
+            null_mode: NullMode::default(),
+            max_depth: None,
+            max_output_len: None,
+        }
+    }
+}
 /// The serializer itself
 /// Should not be used directly except in special cases, use the serialize_to_string function instead.
-pub struct Serializer {
-    output: String
+/// Output is written to `W` as it is produced, by default it is collected in memory.
+pub struct Serializer<W = Vec<u8>> {
+    output: W,
+    /// Whether the last value written was a null, so struct fields and map entries can be left out
+    null: bool,
+    /// Whether an array, map or struct was started, used to reject composite map keys
+    nested: bool,
+    /// Index of the next element of each open array
+    indices: Vec<usize>,
+    depth: usize,
+    written: usize,
+    /// Whether strings are escaped as they are written, map keys are buffered unescaped so they sort by their text
+    escape: bool,
+    options: SerializerOptions
+}
+impl Serializer {
+    /// Creates a serializer. If `sort_fields` is set, struct fields and map entries are written in alphabetical order
+    /// regardless of the order they're declared or iterated in, as ICON requires. Otherwise maps keep their iteration order.
+    pub fn new(sort_fields: bool) -> Self {
+        Serializer::with_writer(Vec::new(), sort_fields)
+    }
+    /// Returns the serialized output
+    pub fn into_output(self) -> String {
+        String::from_utf8(self.output).expect("the serializer only writes UTF-8")
+    }
+    /// Creates a serializer whose output buffer starts with room for `capacity` bytes
+    pub fn with_capacity(capacity: usize, sort_fields: bool) -> Self {
+        Serializer::with_writer(Vec::with_capacity(capacity), sort_fields)
+    }
+}
+impl<W: io::Write> Serializer<W> {
+    /// Creates a serializer that writes its output to `writer`
+    pub fn with_writer(writer: W, sort_fields: bool) -> Self {
+        Serializer::with_options(writer, SerializerOptions { sort_fields, ..SerializerOptions::default() })
+    }
+    /// Creates a serializer that writes its output to `writer`, with the given options
+    pub fn with_options(writer: W, options: SerializerOptions) -> Self {
+        Serializer { output: writer, null: false, nested: false, indices: Vec::new(), depth: 0, written: 0, escape: true, options }
+    }
+    /// Sets how floats are serialized, they are rejected by default
+    ///
+    /// # Example
+    ///
+    ///  ```
+    /// # use serde::Serialize;
+    /// # use icon_utils::serializer::{FloatMode, Serializer};
+    /// let mut serializer = Serializer::new(true).float_mode(FloatMode::Decimal);
+    /// 0.1f64.serialize(&mut serializer).unwrap();
+    /// assert_eq!(serializer.into_output(), r"0\.1");
+    /// assert!(1.5f64.serialize(&mut Serializer::new(true)).is_err());
+    /// ```
+    pub fn float_mode(mut self, mode: FloatMode) -> Self {
+        self.options.float_mode = mode;
+        self
+    }
+    /// Sets how null values are serialized, they are written as `\0` by default
+    pub fn null_mode(mut self, mode: NullMode) -> Self {
+        self.options.null_mode = mode;
+        self
+    }
+    /// Limits how deeply params can be nested, deeper params fail with [`SerializeError::DepthExceeded`]
+    ///
+    /// # Example
+    ///
+    ///  ```
+    /// # use serde::Serialize;
+    /// # use icon_utils::serializer::{SerializeError, Serializer};
+    /// let nested = vec![vec![vec![1u8]]];
+    /// assert!(nested.serialize(&mut Serializer::new(true).max_depth(3)).is_ok());
+    /// let error = nested.serialize(&mut Serializer::new(true).max_depth(2)).unwrap_err();
+    /// assert!(matches!(error.kind(), SerializeError::DepthExceeded { max_depth: 2 }));
+    /// ```
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.options.max_depth = Some(max_depth);
+        self
+    }
+    /// Limits how many bytes can be written, longer output fails with [`SerializeError::OutputTooLong`]
+    pub fn max_output_len(mut self, max_len: usize) -> Self {
+        self.options.max_output_len = Some(max_len);
+        self
+    }
+    /// Returns the writer the output was written to
+    pub fn into_inner(self) -> W {
+        self.output
+    }
+    /// Creates an empty in-memory serializer with the same options, for buffering struct fields and map entries
+    /// The child starts at the depth and output length of its parent, so limits apply to the whole transaction.
+    fn child(&self) -> Serializer {
+        let mut child = Serializer::with_options(Vec::new(), self.options);
+        child.depth = self.depth;
+        child.written = self.written;
+        child
+    }
+    /// Enters a nested array, map or struct, checking the depth limit
+    fn enter(&mut self) -> Result<(), SerializeError> {
+        self.nested = true;
+        self.depth += 1;
+        match self.options.max_depth {
+            Some(max_depth) if self.depth > max_depth => Err(SerializeError::DepthExceeded { max_depth }),
+            _ => Ok(()),
+        }
+    }
+    fn leave(&mut self) {
+        self.depth -= 1;
+    }
+    /// Starts an array
+    fn open_array(&mut self) -> Result<(), SerializeError> {
+        self.enter()?;
+        self.indices.push(0);
+        Ok(())
+    }
+    /// Ends an array, writing `close`
+    fn close_array(&mut self, close: &str) -> Result<(), SerializeError> {
+        self.write(close)?;
+        self.indices.pop();
+        self.leave();
+        Ok(())
+    }
+    /// Writes an array element, naming its index in any error
+    fn element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), SerializeError> {
+        let index = match self.indices.last_mut() {
+            Some(next) => {
+                *next += 1;
+                *next - 1
+            }
+            None => 0,
+        };
+        if index > 0 {
+            self.write(".")?;
+        }
+        value.serialize(&mut *self).map_err(|e| e.at_index(index))
+    }
+    fn write(&mut self, value: &str) -> Result<(), SerializeError> {
+        self.write_bytes(value.as_bytes())
+    }
+    /// Writes a string with `\`, `.`, `{`, `}`, `[` and `]` escaped by a backslash, so it can't be read as structure
+    fn write_escaped(&mut self, value: &str) -> Result<(), SerializeError> {
+        let mut start = 0;
+        for (i, byte) in value.bytes().enumerate() {
+            if matches!(byte, b'\\' | b'.' | b'{' | b'}' | b'[' | b']') {
+                self.write(&value[start..i])?;
+                self.write("\\")?;
+                start = i;
+            }
+        }
+        self.write(&value[start..])
+    }
+    /// Writes a string, escaped unless it is a map key being buffered
+    fn write_str(&mut self, value: &str) -> Result<(), SerializeError> {
+        if self.escape {
+            self.write_escaped(value)
+        } else {
+            self.write(value)
+        }
+    }
+    fn write_bytes(&mut self, value: &[u8]) -> Result<(), SerializeError> {
+        self.written += value.len();
+        if let Some(max_len) = self.options.max_output_len.filter(|&max_len| self.written > max_len) {
+            return Err(SerializeError::OutputTooLong { max_len });
+        }
+        self.output.write_all(value)?;
+        self.null = false;
+        Ok(())
+    }
+}
+impl Default for Serializer {
+    fn default() -> Self {
+        Serializer::new(true)
+    }
+}
+/// Buffers the fields of a struct so they can be written in sorted order
+/// All fields share one buffer, each field is a range of it.
+pub struct StructSerializer<'a, W = Vec<u8>> {
+    ser: &'a mut Serializer<W>,
+    buffer: Serializer,
+    fields: Vec<(&'static str, std::ops::Range<usize>)>,
+    /// Whether the fields belong to a struct variant, whose `{variant.` is closed after them
+    variant: bool
+}
+/// Buffers the entries of a map so they can be written sorted by key, like struct fields
+pub struct MapSerializer<'a, W = Vec<u8>> {
+    ser: &'a mut Serializer<W>,
+    entries: Vec<(Vec<u8>, Vec<u8>)>,
+    key: Option<Vec<u8>>,
+    /// Bytes buffered in entries so far, counted towards the output limit
+    buffered: usize
 }
 /// Converts any struct to transaction format for the icon network.
 /// Params must also implement the Serialize trait from serde.
@@ -47,40 +531,227 @@ pub struct Serializer {
 /// # Example
 /// 
 ///  ```
-/// #[derive(Transaction)]
-/// #[sort]
+/// # use serde::Serialize;
+/// # use icon_utils::serializer::{serialize_to_string, Transaction};
+/// # #[derive(Serialize)]
+/// # struct ExampleParams {}
+/// #[derive(Serialize, Transaction)]
 /// struct ExampleTransaction {
 ///     method: String,
 ///     params: ExampleParams
 /// }
-/// println!("{}", serialize_to_string(&ExampleTransaction {method: "yo".to_string(), params: ExampleParams{}}).unwrap();
+/// println!("{}", serialize_to_string(&ExampleTransaction {method: "yo".to_string(), params: ExampleParams{}}).unwrap());
 /// ```
 pub fn serialize_to_string<T>(value: T) -> Result<String, SerializeError>
 where
-    T: Serialize,
-    T: Transaction + for<'a> Transaction,
+    T: Transaction,
     T::Params: Serialize,
 {
-    let params: &<T as Transaction>::Params = value.params();
-    
-    let mut serializer = Serializer { output: String::new() };
-    params.serialize(&mut serializer)?;
-    Ok(value.method().to_owned() + ".params." + &serializer.output.to_owned())
+    serialize_transaction(value.method(), value.params())
+}
+/// Converts any struct to transaction format like [`serialize_to_string`], preallocating `capacity` bytes for the output.
+/// Avoids most reallocations when the size of the serialized transaction is roughly known, such as for large param arrays.
+///
+/// # Example
+///
+///  ```
+/// # use serde::Serialize;
+/// # use icon_utils::serializer::{serialize_to_string_with_capacity, Transaction};
+/// #[derive(Serialize, Transaction)]
+/// #[transaction(method = "icx_sendTransaction")]
+/// struct BatchParams { values: Vec<u64> }
+/// let tx = BatchParams { values: (0..10_000).collect() };
+/// let serialized = serialize_to_string_with_capacity(&tx, 64 * 1024).unwrap();
+/// assert!(serialized.ends_with(".0x270f]}"));
+/// ```
+pub fn serialize_to_string_with_capacity<T>(value: T, capacity: usize) -> Result<String, SerializeError>
+where
+    T: Transaction,
+    T::Params: Serialize,
+{
+    let mut output = Vec::with_capacity(capacity);
+    write_transaction(value.method(), value.params(), &mut output)?;
+    String::from_utf8(output).map_err(|_| SerializeError::InvalidUtf8)
+}
+/// Serializes a batch of transactions in parallel on the rayon thread pool, keeping each result in order
+///
+/// Every transaction is written to its own buffer, so the threads share nothing while serializing and one
+/// transaction failing doesn't stop the rest.
+///
+/// # Example
+///
+///  ```
+/// # use serde::Serialize;
+/// # use icon_utils::serializer::{serialize_batch, Transaction};
+/// #[derive(Serialize, Transaction)]
+/// #[transaction(method = "icx_sendTransaction")]
+/// struct TransferParams { to: String, nonce: u64 }
+/// let txs: Vec<_> = (0..10_000).map(|nonce| TransferParams { to: "hx1".to_string(), nonce }).collect();
+/// let serialized = serialize_batch(&txs);
+/// assert_eq!(serialized[42].as_deref().unwrap(), "icx_sendTransaction.params.{nonce.0x2a.to.hx1}");
+/// ```
+#[cfg(feature = "rayon")]
+pub fn serialize_batch<T>(txs: &[T]) -> Vec<Result<String, SerializeError>>
+where
+    T: Transaction + Sync,
+    T::Params: Serialize,
+{
+    use rayon::prelude::*;
+    txs.par_iter().map(|tx| serialize_transaction(tx.method(), tx.params())).collect()
 }
-impl<'a> ser::Serializer for &'a mut Serializer {
+/// Converts any struct to transaction format like [`serialize_to_string`], with the given serializer options
+pub fn serialize_to_string_with_options<T>(value: T, options: SerializerOptions) -> Result<String, SerializeError>
+where
+    T: Transaction,
+    T::Params: Serialize,
+{
+    let mut output = Vec::new();
+    write_transaction_with_options(value.method(), value.params(), &mut output, options)?;
+    String::from_utf8(output).map_err(|_| SerializeError::InvalidUtf8)
+}
+/// Serializes dynamically built params, such as params read from config or user input, to transaction format.
+/// Object keys are sorted at every level whatever order the map iterates in, so the result is the same with
+/// serde_json's `preserve_order` feature enabled.
+///
+/// # Example
+///
+///  ```
+/// # use icon_utils::serializer::serialize_value;
+/// # use serde_json::json;
+/// let params = json!({ "to": "cx1", "data": { "params": { "b": "0x2", "a": ["0x1", "0x3"] }, "method": "transfer" } });
+/// assert_eq!(
+///     serialize_value("icx_sendTransaction", &params).unwrap(),
+///     "icx_sendTransaction.params.{data.{method.transfer.params.{a.[0x1.0x3].b.0x2}}.to.cx1}"
+/// );
+/// ```
+///
+/// Backslashes, dots, braces and brackets in strings and keys are escaped with a backslash, so they can't be
+/// mistaken for the structure around them. Keys are sorted before they are escaped.
+///
+///  ```
+/// # use icon_utils::serializer::serialize_value;
+/// # use serde_json::json;
+/// let params = json!({ "memo": "a.b{c}[d]\\e", "a.b": "0x1" });
+/// assert_eq!(
+///     serialize_value("icx_sendTransaction", &params).unwrap(),
+///     r"icx_sendTransaction.params.{a\.b.0x1.memo.a\.b\{c\}\[d\]\\e}"
+/// );
+/// ```
+///
+/// Other maps are sorted the same way, such as a `HashMap` in a struct.
+///
+///  ```
+/// # use serde::Serialize;
+/// # use std::collections::HashMap;
+/// # use icon_utils::serializer::{serialize_to_string, Transaction};
+/// #[derive(Serialize, Transaction)]
+/// #[transaction(method = "icx_call")]
+/// struct CallParams { values: HashMap<String, String> }
+/// let values = (0..20).map(|i| (format!("k{:02}", i), i.to_string())).collect();
+/// let serialized = serialize_to_string(&CallParams { values }).unwrap();
+/// let expected: Vec<String> = (0..20).map(|i| format!("k{:02}.{}", i, i)).collect();
+/// assert_eq!(serialized, format!("icx_call.params.{{values.{{{}}}}}", expected.join(".")));
+/// ```
+pub fn serialize_value(method: &str, params: &serde_json::Value) -> Result<String, SerializeError> {
+    serialize_transaction(method, params)
+}
+/// Serializes a transaction into a writer instead of building a String, for large transactions such as deploys.
+///
+/// # Example
+///
+///  ```
+/// # use serde::Serialize;
+/// # use icon_utils::serializer::{serialize_to_writer, Transaction};
+/// #[derive(Serialize, Transaction)]
+/// #[transaction(method = "icx_sendTransaction")]
+/// struct TransferParams { to: String }
+/// let mut output = Vec::new();
+/// serialize_to_writer(&TransferParams { to: "hx1".to_string() }, &mut output).unwrap();
+/// assert_eq!(output, b"icx_sendTransaction.params.{to.hx1}");
+/// ```
+pub fn serialize_to_writer<T, W>(value: T, writer: W) -> Result<(), SerializeError>
+where
+    T: Transaction,
+    T::Params: Serialize,
+    W: io::Write,
+{
+    write_transaction(value.method(), value.params(), writer)
+}
+/// Computes the SHA3-256 hash of a serialized transaction, which is the value its signature covers.
+/// Useful for tracking a transaction before it's submitted.
+///
+/// # Example
+///
+///  ```
+/// # use serde::Serialize;
+/// # use icon_utils::serializer::{tx_hash, Transaction};
+/// # #[derive(Serialize)]
+/// # struct ExampleParams {}
+/// # struct ExampleTransaction { method: String, params: ExampleParams }
+/// # impl Transaction for ExampleTransaction {
+/// #     type Params = ExampleParams;
+/// #     fn params(&self) -> &ExampleParams { &self.params }
+/// #     fn method(&self) -> &String { &self.method }
+/// # }
+/// let tx = ExampleTransaction { method: "icx_sendTransaction".to_string(), params: ExampleParams {} };
+/// println!("0x{}", hex::encode(tx_hash(&tx).unwrap()));
+/// ```
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", name = "hash", skip_all, fields(method = %tx.method())))]
+pub fn tx_hash<T>(tx: &T) -> Result<[u8; 32], SerializeError>
+where
+    T: Transaction,
+    T::Params: Serialize,
+{
+    let mut hasher = Sha3_256::new();
+    write_transaction(tx.method(), tx.params(), &mut hasher)?;
+    let hash = hasher.finalize().into();
+    #[cfg(feature = "tracing")]
+    tracing::trace!(tx_hash = %hex::encode(hash), "hashed transaction");
+    Ok(hash)
+}
+/// Serializes a method and its params to transaction format
+pub(crate) fn serialize_transaction<P>(method: &str, params: &P) -> Result<String, SerializeError>
+where
+    P: Serialize + ?Sized,
+{
+    let mut output = Vec::new();
+    write_transaction(method, params, &mut output)?;
+    String::from_utf8(output).map_err(|_| SerializeError::InvalidUtf8)
+}
+/// Writes a method and its params to `writer` in transaction format
+pub(crate) fn write_transaction<P, W>(method: &str, params: &P, writer: W) -> Result<(), SerializeError>
+where
+    P: Serialize + ?Sized,
+    W: io::Write,
+{
+    write_transaction_with_options(method, params, writer, SerializerOptions::default())
+}
+/// Writes a method and its params to `writer` in transaction format, with the given serializer options
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", name = "serialize", skip_all, fields(method = %method)))]
+pub(crate) fn write_transaction_with_options<P, W>(method: &str, params: &P, writer: W, options: SerializerOptions) -> Result<(), SerializeError>
+where
+    P: Serialize + ?Sized,
+    W: io::Write,
+{
+    let mut serializer = Serializer::with_options(writer, options);
+    serializer.write(method)?;
+    serializer.write(".params.")?;
+    params.serialize(&mut serializer).map_err(|e| e.at_field("params"))
+}
+impl<'a, W: io::Write> ser::Serializer for &'a mut Serializer<W> {
     type Ok = ();
 
     type Error = SerializeError;
 
     type SerializeSeq = Self;
-    type SerializeMap = Self;
-    type SerializeStruct = Self;
-    type SerializeStructVariant = Self;
+    type SerializeMap = MapSerializer<'a, W>;
+    type SerializeStruct = StructSerializer<'a, W>;
+    type SerializeStructVariant = StructSerializer<'a, W>;
     type SerializeTuple = Self;
     type SerializeTupleStruct = Self;
     type SerializeTupleVariant = Self;
     fn serialize_bool(self, v: bool) -> Result<Self::Ok, Self::Error> {
-        self.output += if v { "true"} else { "false" };
+        self.write(if v { "0x1" } else { "0x0" })?;
         Ok(())
     }
 
@@ -95,10 +766,16 @@ impl<'a> ser::Serializer for &'a mut Serializer {
     fn serialize_i32(self, v: i32) -> Result<Self::Ok, Self::Error> {
         self.serialize_i64(i64::from(v))
     }
-    //TODO: Use itoa
     fn serialize_i64(self, v: i64) -> Result<Self::Ok, Self::Error> {
-        self.output += &v.to_string();
-        Ok(())
+        self.serialize_i128(i128::from(v))
+    }
+
+    // Negative integers are written with a sign in front of the hex, like `-0x1`
+    fn serialize_i128(self, v: i128) -> Result<Self::Ok, Self::Error> {
+        if v < 0 {
+            self.write("-")?;
+        }
+        self.write(&crate::icon_hex::to_hex_no_pad(v.unsigned_abs()))
     }
 
     fn serialize_u8(self, v: u8) -> Result<Self::Ok, Self::Error> {
@@ -114,95 +791,111 @@ impl<'a> ser::Serializer for &'a mut Serializer {
     }
 
     fn serialize_u64(self, v: u64) -> Result<Self::Ok, Self::Error> {
-        self.output += &v.to_string();
-        Ok(())
+        self.serialize_u128(u128::from(v))
     }
 
+    fn serialize_u128(self, v: u128) -> Result<Self::Ok, Self::Error> {
+        self.write(&crate::icon_hex::to_hex_no_pad(v))
+    }
+
+    // f32 is formatted as itself, widening it first would write 0.1 as 0.10000000149011612
     fn serialize_f32(self, v: f32) -> Result<Self::Ok, Self::Error> {
-        self.serialize_f64(f64::from(v))
+        if self.options.float_mode == FloatMode::Reject {
+            return Err(SerializeError::UnsupportedType { type_name: "f32" });
+        }
+        if !v.is_finite() {
+            return Err(SerializeError::Float(f64::from(v)));
+        }
+        self.write_str(&v.to_string())
     }
 
+    // Display is the shortest round-trip decimal and never uses an exponent, unlike ryu which writes 1e21
     fn serialize_f64(self, v: f64) -> Result<Self::Ok, Self::Error> {
-        self.output += &v.to_string();
-        Ok(())
-
+        if self.options.float_mode == FloatMode::Reject {
+            return Err(SerializeError::UnsupportedType { type_name: "f64" });
+        }
+        if !v.is_finite() {
+            return Err(SerializeError::Float(v));
+        }
+        self.write_str(&v.to_string())
     }
 
     fn serialize_char(self, v: char) -> Result<Self::Ok, Self::Error> {
-        self.serialize_str(&v.to_string())
+        self.serialize_str(v.encode_utf8(&mut [0u8; 4]))
     }
 
     fn serialize_str(self, v: &str) -> Result<Self::Ok, Self::Error> {
-        self.output += v;
-        Ok(())
+        self.write_str(v)
     }
 
     fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok, Self::Error> {
-        use serde::ser::SerializeSeq;
-        let mut seq = self.serialize_seq(Some(v.len()))?;
-        for byte in v {
-            seq.serialize_element(byte)?;
-        }
-        seq.end()
+        self.write(&crate::icon_hex::encode_bytes_0x(v))
     }
 
     fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
         self.serialize_unit()
     }
 
-    fn serialize_some<T: ?Sized>(self, value: &T) -> Result<Self::Ok, Self::Error>
+    fn serialize_some<T>(self, value: &T) -> Result<Self::Ok, Self::Error>
     where
-        T: Serialize {
+        T: ?Sized + Serialize {
         value.serialize(self)
     }
 
     fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
-        self.output += "\0";
+        if self.options.null_mode == NullMode::Error {
+            return Err(SerializeError::Null);
+        }
+        self.write("\\0")?;
+        self.null = true;
         Ok(())
     }
 
-    fn serialize_unit_struct(self, name: &'static str) -> Result<Self::Ok, Self::Error> {
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok, Self::Error> {
         self.serialize_unit()
     }
 
     fn serialize_unit_variant(
         self,
-        name: &'static str,
-        variant_index: u32,
+        _name: &'static str,
+        _variant_index: u32,
         variant: &'static str,
     ) -> Result<Self::Ok, Self::Error> {
         self.serialize_str(variant)
     }
 
-    fn serialize_newtype_struct<T: ?Sized>(
+    fn serialize_newtype_struct<T>(
         self,
-        name: &'static str,
+        _name: &'static str,
         value: &T,
     ) -> Result<Self::Ok, Self::Error>
     where
-        T: Serialize {
+        T: ?Sized + Serialize {
         value.serialize(self)
     }
 
-    fn serialize_newtype_variant<T: ?Sized>(
+    fn serialize_newtype_variant<T>(
         self,
-        name: &'static str,
-        variant_index: u32,
+        _name: &'static str,
+        _variant_index: u32,
         variant: &'static str,
         value: &T,
     ) -> Result<Self::Ok, Self::Error>
     where
-        T: Serialize {
-            self.output += "{";
+        T: ?Sized + Serialize {
+            self.enter()?;
+            self.write("{")?;
             variant.serialize(&mut *self)?;
-            self.output += ".";
-            value.serialize(&mut *self)?;
-            self.output += "}";
+            self.write(".")?;
+            value.serialize(&mut *self).map_err(|e| e.at_field(variant))?;
+            self.write("}")?;
+            self.leave();
             Ok(())
     }
 
-    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
-        self.output += "[";
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        self.open_array()?;
+        self.write("[")?;
         Ok(self)
     }
 
@@ -212,7 +905,7 @@ impl<'a> ser::Serializer for &'a mut Serializer {
 
     fn serialize_tuple_struct(
         self,
-        name: &'static str,
+        _name: &'static str,
         len: usize,
     ) -> Result<Self::SerializeTupleStruct, Self::Error> {
         self.serialize_seq(Some(len))
@@ -220,44 +913,51 @@ impl<'a> ser::Serializer for &'a mut Serializer {
 
     fn serialize_tuple_variant(
         self,
-        name: &'static str,
-        variant_index: u32,
+        _name: &'static str,
+        _variant_index: u32,
         variant: &'static str,
-        len: usize,
+        _len: usize,
     ) -> Result<Self::SerializeTupleVariant, Self::Error> {
-        self.output += "{";
+        self.open_array()?;
+        self.write("{")?;
         variant.serialize(&mut *self)?;
-        self.output += ".[";
+        self.write(".[")?;
         Ok(self)
     }
 
     fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
-        self.output += "{";
-        Ok(self)
+        self.enter()?;
+        Ok(MapSerializer { ser: self, entries: Vec::with_capacity(len.unwrap_or(0)), key: None, buffered: 0 })
     }
 
     fn serialize_struct(
         self,
-        name: &'static str,
+        _name: &'static str,
         len: usize,
     ) -> Result<Self::SerializeStruct, Self::Error> {
-        self.serialize_map(Some(len))
+        self.enter()?;
+        let buffer = self.child();
+        Ok(StructSerializer { ser: self, buffer, fields: Vec::with_capacity(len), variant: false })
     }
 
+    // Written like a newtype variant holding the struct, so the fields are sorted like any other struct
     fn serialize_struct_variant(
         self,
         name: &'static str,
-        variant_index: u32,
+        _variant_index: u32,
         variant: &'static str,
         len: usize,
     ) -> Result<Self::SerializeStructVariant, Self::Error> {
-        self.output += "{";
+        self.enter()?;
+        self.write("{")?;
         variant.serialize(&mut *self)?;
-        self.output += ".{";
-        Ok(self)
+        self.write(".")?;
+        let mut fields = self.serialize_struct(name, len)?;
+        fields.variant = true;
+        Ok(fields)
     }
 }
-impl<'a> ser::SerializeSeq for &'a mut Serializer {
+impl<W: io::Write> ser::SerializeSeq for &mut Serializer<W> {
     // Must match the `Ok` type of the serializer.
     type Ok = ();
     // Must match the `Error` type of the serializer.
@@ -268,19 +968,15 @@ impl<'a> ser::SerializeSeq for &'a mut Serializer {
     where
         T: ?Sized + Serialize,
     {
-        if !self.output.ends_with('[') {
-            self.output += ".";
-        }
-        value.serialize(&mut **self)
+        self.element(value)
     }
 
     // Close the sequence.
     fn end(self) -> Result<Self::Ok,Self::Error> {
-        self.output += "]";
-        Ok(())
+        self.close_array("]")
     }
 }
-impl<'a> ser::SerializeTuple for &'a mut Serializer {
+impl<W: io::Write> ser::SerializeTuple for &mut Serializer<W> {
     type Ok = ();
     type Error = SerializeError;
 
@@ -288,18 +984,14 @@ impl<'a> ser::SerializeTuple for &'a mut Serializer {
     where
         T: ?Sized + Serialize,
     {
-        if !self.output.ends_with('[') {
-            self.output += ".";
-        }
-        value.serialize(&mut **self)
+        self.element(value)
     }
 
     fn end(self) -> Result<Self::Ok,Self::Error> {
-        self.output += "]";
-        Ok(())
+        self.close_array("]")
     }
 }
-impl<'a> ser::SerializeTupleStruct for &'a mut Serializer {
+impl<W: io::Write> ser::SerializeTupleStruct for &mut Serializer<W> {
     type Ok = ();
     type Error = SerializeError;
 
@@ -307,18 +999,14 @@ impl<'a> ser::SerializeTupleStruct for &'a mut Serializer {
     where
         T: ?Sized + Serialize,
     {
-        if !self.output.ends_with('[') {
-            self.output += ".";
-        }
-        value.serialize(&mut **self)
+        self.element(value)
     }
 
     fn end(self) -> Result<Self::Ok,Self::Error> {
-        self.output += "]";
-        Ok(())
+        self.close_array("]")
     }
 }
-impl<'a> ser::SerializeTupleVariant for &'a mut Serializer {
+impl<W: io::Write> ser::SerializeTupleVariant for &mut Serializer<W> {
     type Ok = ();
     type Error = SerializeError;
 
@@ -326,18 +1014,14 @@ impl<'a> ser::SerializeTupleVariant for &'a mut Serializer {
     where
         T: ?Sized + Serialize,
     {
-        if !self.output.ends_with('[') {
-            self.output += ".";
-        }
-        value.serialize(&mut **self)
+        self.element(value)
     }
 
     fn end(self) -> Result<Self::Ok,Self::Error> {
-        self.output += "]}";
-        Ok(())
+        self.close_array("]}")
     }
 }
-impl<'a> ser::SerializeMap for &'a mut Serializer {
+impl<W: io::Write> ser::SerializeMap for MapSerializer<'_, W> {
     type Ok = ();
     type Error = SerializeError;
 
@@ -346,10 +1030,16 @@ impl<'a> ser::SerializeMap for &'a mut Serializer {
     where
         T: ?Sized + Serialize,
     {
-        if !self.output.ends_with('{') {
-            self.output += ".";
+        let mut buffer = self.ser.child();
+        buffer.written += self.buffered;
+        buffer.escape = false;
+        key.serialize(&mut buffer)?;
+        if buffer.nested || buffer.null {
+            return Err(SerializeError::NonStringKey);
         }
-        key.serialize(&mut **self)
+        self.buffered += buffer.output.len();
+        self.key = Some(buffer.output);
+        Ok(())
     }
 
 
@@ -357,16 +1047,46 @@ impl<'a> ser::SerializeMap for &'a mut Serializer {
     where
         T: ?Sized + Serialize,
     {
-        self.output += ".";
-        value.serialize(&mut **self)
+        let key = self.key.take().ok_or_else(|| SerializeError::FailedToSerialize("map value serialized before its key".to_owned()))?;
+        let mut buffer = self.ser.child();
+        buffer.written += self.buffered;
+        value.serialize(&mut buffer).map_err(|e| e.at_field(&String::from_utf8_lossy(&key)))?;
+        if buffer.null && buffer.options.null_mode == NullMode::Omit {
+            self.buffered -= key.len();
+        } else {
+            self.buffered += buffer.output.len();
+            self.entries.push((key, buffer.output));
+        }
+        Ok(())
     }
 
-    fn end(self) -> Result<Self::Ok,Self::Error> {
-        self.output += "}";
+    fn end(mut self) -> Result<Self::Ok,Self::Error> {
+        if self.ser.options.sort_fields {
+            // Flattened structs can repeat a key, the sort is stable so the last entry of each key is kept like serde_json does
+            self.entries.sort_by(|a, b| a.0.cmp(&b.0));
+            self.entries.dedup_by(|later, earlier| {
+                let duplicate = later.0 == earlier.0;
+                if duplicate {
+                    std::mem::swap(later, earlier);
+                }
+                duplicate
+            });
+        }
+        self.ser.write("{")?;
+        for (i, (key, value)) in self.entries.iter().enumerate() {
+            if i > 0 {
+                self.ser.write(".")?;
+            }
+            self.ser.write_escaped(std::str::from_utf8(key).map_err(|_| SerializeError::InvalidUtf8)?)?;
+            self.ser.write(".")?;
+            self.ser.write_bytes(value)?;
+        }
+        self.ser.write("}")?;
+        self.ser.leave();
         Ok(())
     }
 }
-impl<'a> ser::SerializeStruct for &'a mut Serializer {
+impl<W: io::Write> ser::SerializeStruct for StructSerializer<'_, W> {
     type Ok = ();
     type Error = SerializeError;
 
@@ -374,20 +1094,40 @@ impl<'a> ser::SerializeStruct for &'a mut Serializer {
     where
         T: ?Sized + Serialize,
     {
-        if !self.output.ends_with('{') {
-            self.output += ".";
+        let start = self.buffer.output.len();
+        value.serialize(&mut self.buffer).map_err(|e| e.at_field(key))?;
+        if self.buffer.null && self.buffer.options.null_mode == NullMode::Omit {
+            self.buffer.written -= self.buffer.output.len() - start;
+            self.buffer.output.truncate(start);
+            return Ok(());
         }
-        key.serialize(&mut **self)?;
-        self.output += ".";
-        value.serialize(&mut **self)
+        self.fields.push((key, start..self.buffer.output.len()));
+        Ok(())
     }
 
-    fn end(self) -> Result<Self::Ok,Self::Error> {
-        self.output += "}";
+    fn end(mut self) -> Result<Self::Ok,Self::Error> {
+        if self.ser.options.sort_fields {
+            self.fields.sort_by(|a, b| a.0.cmp(b.0));
+        }
+        self.ser.write("{")?;
+        for (i, (key, value)) in self.fields.iter().enumerate() {
+            if i > 0 {
+                self.ser.write(".")?;
+            }
+            self.ser.write_escaped(key)?;
+            self.ser.write(".")?;
+            self.ser.write_bytes(&self.buffer.output[value.clone()])?;
+        }
+        self.ser.write("}")?;
+        self.ser.leave();
+        if self.variant {
+            self.ser.write("}")?;
+            self.ser.leave();
+        }
         Ok(())
     }
 }
-impl<'a> ser::SerializeStructVariant for &'a mut Serializer {
+impl<W: io::Write> ser::SerializeStructVariant for StructSerializer<'_, W> {
     type Ok = ();
     type Error = SerializeError;
 
@@ -395,31 +1135,188 @@ impl<'a> ser::SerializeStructVariant for &'a mut Serializer {
     where
         T: ?Sized + Serialize,
     {
-        if !self.output.ends_with('{') {
-            self.output += ".";
-        }
-        key.serialize(&mut **self)?;
-        self.output += ":";
-        value.serialize(&mut **self)
+        ser::SerializeStruct::serialize_field(self, key, value)
     }
 
     fn end(self) -> Result<Self::Ok,Self::Error> {
-        self.output += "}}";
-        Ok(())
+        ser::SerializeStruct::end(self)
     }
 }
 }
+#[cfg(feature = "wallet")]
 pub mod wallet {
     //! # Icon Wallet
     //! `wallet` is a module for ICON wallets, and transaction signing.
+    #[cfg(all(feature = "keystore", not(target_arch = "wasm32")))]
     use std::path::PathBuf;
 
-use k256::{ecdsa::{SigningKey, recoverable, VerifyingKey, signature::Signer}, EncodedPoint, schnorr::signature::{hazmat::PrehashSigner, Signature}};
+use k256::{ecdsa::{SigningKey, recoverable, VerifyingKey}, elliptic_curve::{sec1::ToEncodedPoint, zeroize::{Zeroize, Zeroizing}}, schnorr::signature::hazmat::{PrehashSigner, PrehashVerifier}};
 use sha3::Sha3_256;
 use sha3::Digest;
-use eth_keystore::{decrypt_key,encrypt_key,new};
+#[cfg(all(feature = "keystore", not(target_arch = "wasm32")))]
+use eth_keystore::decrypt_key;
+use rand::{rngs::OsRng, RngCore};
+use thiserror::Error;
+use crate::hd::HdWallet;
+#[cfg(feature = "keystore")]
+use crate::keystore::{self, KeystoreError};
+#[cfg(all(feature = "keystore", not(target_arch = "wasm32")))]
+use crate::keystore::KdfParams;
+use crate::serializer::{self, SerializeError, Transaction};
+use crate::signer::{DeterministicSigner, Signature, Signer};
+use crate::types::Address;
+#[derive(Error, Debug)]
+// Error struct for wallet operations
+pub enum WalletError {
+    #[error("Invalid hex private key: {0}")]
+    InvalidHex(#[from] hex::FromHexError),
+    #[error("Invalid private key")]
+    InvalidKey(k256::ecdsa::Error),
+    #[error("Failed to sign data")]
+    FailedToSign(k256::ecdsa::Error),
+    #[error("Invalid signature: {0}")]
+    InvalidSignature(String),
+    #[error("Invalid mnemonic: {0}")]
+    InvalidMnemonic(String),
+    #[error("Failed to derive key: {0}")]
+    FailedToDerive(String),
+    #[cfg(feature = "keystore")]
+    #[error("Failed to decrypt keystore: {0}")]
+    FailedToDecrypt(#[from] eth_keystore::KeystoreError),
+    #[cfg(feature = "keystore")]
+    #[error(transparent)]
+    Keystore(#[from] KeystoreError),
+    #[error(transparent)]
+    Serialize(#[from] SerializeError),
+    #[error("Signer failed: {0}")]
+    Signer(String),
+    #[error("Signed transaction hash doesn't match its params")]
+    HashMismatch,
+    #[error("Invalid `from` address: {0}")]
+    InvalidFrom(String),
+    #[error("Transaction is from {from} but was signed by {signer}")]
+    SignerMismatch { from: Address, signer: Address },
+    #[error("Failed to sign transaction {index} of the batch: {source}")]
+    Batch { index: usize, source: Box<WalletError> },
+    #[cfg(feature = "keystore")]
+    #[error("Wallet is locked")]
+    Locked,
+}
+/// BIP44 coin type registered for ICON
+pub const COIN_TYPE: u32 = 4801368;
+/// BIP44 derivation path of the first ICON account, as used by ICONex
+pub const DEFAULT_DERIVATION_PATH: &str = "m/44'/4801368'/0'/0/0";
+/// A transaction signed by a wallet, ready to be sent with `icx_sendTransaction`
+#[derive(Debug, Clone)]
+pub struct SignedTransaction {
+    pub method: String,
+    /// The params, with integers, booleans and bytes as the hex strings the serializer writes for them
+    pub params: serde_json::Value,
+    /// SHA3-256 hash of the serialized transaction
+    pub hash: [u8; 32],
+    /// Recoverable signature over the hash
+    pub signature: Signature,
+}
+impl SignedTransaction {
+    /// Returns the tx hash, the same value `icx_sendTransaction` returns
+    pub fn tx_hash(&self) -> crate::types::Hash256 {
+        self.hash.into()
+    }
+    /// Returns the params of the `icx_sendTransaction` request, with every value encoded as a string
+    /// exactly as it was serialized for signing, and the signature appended
+///
+/// # Example
+/// 
+///  ```
+/// # use serde::Serialize;
+/// # use icon_utils::{wallet, serializer::Transaction};
+/// # let wallet = wallet::Wallet::new("68ee9ca94b71c42ba79375b8677e29a717fb8072bcb17cd5ee288f9b77bc2894").unwrap();
+/// #[derive(Serialize, Transaction)]
+/// #[transaction(method = "icx_sendTransaction")]
+/// struct ExampleParams { to: String, nonce: u64, refund: bool }
+/// let signed = wallet.sign_transaction(&ExampleParams { to: "hx0".to_string(), nonce: 1, refund: true }).unwrap();
+/// let params = signed.to_rpc_params();
+/// assert_eq!(params["nonce"], "0x1");
+/// assert_eq!(params["refund"], "0x1");
+/// assert_eq!(params["signature"], signed.signature.to_base64());
+/// ```
+    pub fn to_rpc_params(&self) -> serde_json::Value {
+        let mut params = stringify_values(self.params.clone());
+        if let Some(map) = params.as_object_mut() {
+            map.insert("signature".to_owned(), serde_json::Value::String(self.signature.to_base64()));
+        }
+        params
+    }
+    /// Summarizes the transaction for review before it's sent, failing if its params aren't an `icx_sendTransaction`
+    /// transaction
+///
+/// # Example
+///
+///  ```
+/// # use icon_utils::{wallet, transaction::TransactionBuilder};
+/// # let wallet = wallet::Wallet::new("68ee9ca94b71c42ba79375b8677e29a717fb8072bcb17cd5ee288f9b77bc2894").unwrap();
+/// let tx = TransactionBuilder::new().from(wallet.address()).to("hx5bfdb090f43a808005ffc27c25b213145e80b7cd")
+///     .value(1_500_000_000_000_000_000u128).step_limit(100_000).nid(1).timestamp(1).build().unwrap();
+/// let summary = wallet.sign_transaction(&tx).unwrap().describe().unwrap();
+/// assert!(summary.to_string().contains("Value:     1.5 ICX"));
+/// assert!(summary.hash.is_some());
+/// ```
+    pub fn describe(&self) -> Result<crate::transaction::TransactionSummary, serde_json::Error> {
+        let tx: crate::transaction::IcxTransaction = serde_json::from_value(self.params.clone())?;
+        let mut summary = tx.describe();
+        summary.hash = Some(self.tx_hash());
+        Ok(summary)
+    }
+    /// Reads a signed transaction back from the params of an `icx_sendTransaction` request, such as a persisted
+    /// [`to_rpc_params`](SignedTransaction::to_rpc_params), recomputing its hash
+///
+/// # Example
+/// 
+///  ```
+/// # use icon_utils::{wallet::{self, verify_signed_transaction, SignedTransaction}, transaction::TransactionBuilder};
+/// # let wallet = wallet::Wallet::new("68ee9ca94b71c42ba79375b8677e29a717fb8072bcb17cd5ee288f9b77bc2894").unwrap();
+/// let tx = TransactionBuilder::new().from(wallet.address()).to("hx0000000000000000000000000000000000000000")
+///     .step_limit(100_000).nid(1).timestamp(1).build().unwrap();
+/// let signed = wallet.sign_transaction(&tx).unwrap();
+/// let stored = signed.to_rpc_params().to_string();
+///
+/// let loaded = SignedTransaction::from_rpc_params("icx_sendTransaction", serde_json::from_str(&stored).unwrap()).unwrap();
+/// assert_eq!(loaded.hash, signed.hash);
+/// assert_eq!(verify_signed_transaction(&loaded).unwrap().to_string(), wallet.address());
+/// ```
+    pub fn from_rpc_params(method: &str, mut params: serde_json::Value) -> Result<Self, WalletError> {
+        let signature = params
+            .as_object_mut()
+            .and_then(|map| map.remove("signature"))
+            .ok_or_else(|| WalletError::InvalidSignature("missing signature".to_owned()))?;
+        let signature: Signature = signature
+            .as_str()
+            .ok_or_else(|| WalletError::InvalidSignature("signature must be a string".to_owned()))?
+            .parse()?;
+        let serialized = serializer::serialize_value(method, &params)?;
+        let hash = Sha3_256::new_with_prefix(serialized).finalize().into();
+        Ok(SignedTransaction { method: method.to_owned(), params, hash, signature })
+    }
+}
+/// Converts numbers and booleans to the strings the serializer writes for them, since ICON only accepts string values
+fn stringify_values(value: serde_json::Value) -> serde_json::Value {
+    use serde_json::Value;
+    match value {
+        Value::Number(n) => Value::String(match (n.as_u64(), n.as_i64()) {
+            (Some(v), _) => crate::icon_hex::to_hex_no_pad(u128::from(v)),
+            (None, Some(v)) => format!("-{}", crate::icon_hex::to_hex_no_pad(u128::from(v.unsigned_abs()))),
+            // Floats the same way FloatMode::Decimal writes them, without an exponent
+            _ => n.as_f64().map_or_else(|| n.to_string(), |v| v.to_string()),
+        }),
+        Value::Bool(b) => Value::String(if b { "0x1" } else { "0x0" }.to_owned()),
+        Value::Array(values) => Value::Array(values.into_iter().map(stringify_values).collect()),
+        Value::Object(map) => Value::Object(map.into_iter().map(|(k, v)| (k, stringify_values(v))).collect()),
+        other => other,
+    }
+}
+/// A wallet holding a private key, which is only handed out by [`Wallet::export_private_key`]
 pub struct Wallet {
-    pub privkey: SigningKey,
+    privkey: SigningKey,
     pub pubkey: VerifyingKey
 }
 impl Wallet {
@@ -428,42 +1325,404 @@ impl Wallet {
 /// # Example
 /// 
 ///  ```
-/// let wallet = wallet::Wallet::new("68ee9ca94b71c42ba79375b8677e29a717fb8072bcb17cd5ee288f9b77bc2894");
+/// # use icon_utils::wallet;
+/// let wallet = wallet::Wallet::new("68ee9ca94b71c42ba79375b8677e29a717fb8072bcb17cd5ee288f9b77bc2894").unwrap();
 /// ```
-    pub fn new(key: &str) -> Self{
-        let signingkey = SigningKey::from_bytes(&hex::decode(key).unwrap()).unwrap();
-        let verifiying_key = signingkey.verifying_key();
-        Wallet {privkey: signingkey, pubkey: verifiying_key}
+    pub fn new(key: &str) -> Result<Self, WalletError> {
+        Wallet::from_bytes(&hex::decode(key)?)
+    }
+    /// Creates a wallet from a hex private key, panicking if the key is invalid
+    pub fn new_unchecked(key: &str) -> Self {
+        Wallet::new(key).unwrap()
     }
-    pub fn from_bytes(bytes: &[u8]) -> Self {
-        let signingkey = SigningKey::from_bytes(bytes).unwrap();
+    /// Creates a wallet from raw private key bytes
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, WalletError> {
+        let signingkey = SigningKey::from_bytes(bytes).map_err(WalletError::InvalidKey)?;
         let verifiying_key = signingkey.verifying_key();
-        Wallet {privkey: signingkey, pubkey: verifiying_key}
+        Ok(Wallet {privkey: signingkey, pubkey: verifiying_key})
     }
-    /// Signs a serialized transaction and returns it as a base64 String
+    /// Creates a wallet from the payload of a private key QR code, as exported by ICONex
+    ///
+    /// The payload is the hex private key, optionally with a `0x` prefix and surrounding whitespace.
+///
+/// # Example
+///
+///  ```
+/// # use icon_utils::wallet;
+/// let wallet = wallet::Wallet::from_qr("0x68ee9ca94b71c42ba79375b8677e29a717fb8072bcb17cd5ee288f9b77bc2894\n").unwrap();
+/// assert_eq!(wallet.address(), "hxb874d46c44dd5db9562f2b077a84e45c35dbfc64");
+/// ```
+    pub fn from_qr(payload: &str) -> Result<Self, WalletError> {
+        let key = payload.trim();
+        Wallet::new(key.strip_prefix("0x").unwrap_or(key))
+    }
+    /// Creates a wallet from raw private key bytes, panicking if the key is invalid
+    pub fn from_bytes_unchecked(bytes: &[u8]) -> Self {
+        Wallet::from_bytes(bytes).unwrap()
+    }
+    /// Creates a wallet from a BIP39 mnemonic and optional passphrase, using the first ICON account
 ///
 /// # Example
 /// 
 ///  ```
-/// let sig = wallet.sign("example.example");
+/// # use icon_utils::wallet;
+/// let phrase = wallet::Wallet::generate_mnemonic(12).unwrap();
+/// let wallet = wallet::Wallet::from_mnemonic(&phrase, "").unwrap();
+/// ```
+    pub fn from_mnemonic(phrase: &str, passphrase: &str) -> Result<Self, WalletError> {
+        HdWallet::from_mnemonic(phrase, passphrase)?.derive_account(0)
+    }
+    /// Generates a new random BIP39 mnemonic with the given number of words (12, 15, 18, 21 or 24)
+    pub fn generate_mnemonic(word_count: usize) -> Result<String, WalletError> {
+        if ![12, 15, 18, 21, 24].contains(&word_count) {
+            return Err(WalletError::InvalidMnemonic(format!("unsupported word count {}", word_count)));
+        }
+        let mut entropy = vec![0u8; word_count / 3 * 4];
+        OsRng.fill_bytes(&mut entropy);
+        let mnemonic = bip39::Mnemonic::from_entropy(&entropy).map_err(|e| WalletError::InvalidMnemonic(e.to_string()))?;
+        Ok(mnemonic.to_string())
+    }
+    /// Returns the hx address of the wallet
+///
+/// # Example
+/// 
+///  ```
+/// # use icon_utils::wallet;
+/// let wallet = wallet::Wallet::new("68ee9ca94b71c42ba79375b8677e29a717fb8072bcb17cd5ee288f9b77bc2894").unwrap();
+/// assert!(wallet.address().starts_with("hx"));
+/// ```
+    pub fn address(&self) -> String {
+        address_from_pubkey(&self.pubkey)
+    }
+    /// Signs a serialized transaction, returning a recoverable [`Signature`]
+    ///
+    /// The nonce is derived from the key and hash as in RFC6979, so signing is deterministic, see
+    /// [`DeterministicSigner`].
+///
+/// # Example
+/// 
+///  ```
+/// # use icon_utils::wallet;
+/// # let wallet = wallet::Wallet::new("68ee9ca94b71c42ba79375b8677e29a717fb8072bcb17cd5ee288f9b77bc2894").unwrap();
+/// let sig = wallet.sign("example.example").unwrap();
 /// ``` 
-    pub fn sign(&self, data: &str) -> String {
+    pub fn sign(&self, data: &str) -> Result<Signature, WalletError> {
         let newdata = Sha3_256::new_with_prefix(data).finalize();
-        let signature: recoverable::Signature = self.privkey.sign_prehash(&newdata).unwrap();
+        self.sign_digest(&newdata)
+    }
+    fn sign_digest(&self, hash: &[u8]) -> Result<Signature, WalletError> {
+        let signature: recoverable::Signature = self.privkey.sign_prehash(hash).map_err(WalletError::FailedToSign)?;
         
-        return base64::encode(signature.as_ref());
+        Ok(signature.into())
     }
-    /// Creates a wallet from a keystore and password
+    /// Checks that a signature over serialized data was made by this wallet
+///
+/// # Example
+/// 
+///  ```
+/// # use icon_utils::wallet;
+/// # let wallet = wallet::Wallet::new("68ee9ca94b71c42ba79375b8677e29a717fb8072bcb17cd5ee288f9b77bc2894").unwrap();
+/// let sig = wallet.sign("example.example").unwrap();
+/// assert!(wallet.verify("example.example", &sig).unwrap());
+/// ```
+    pub fn verify(&self, data: &str, signature: &Signature) -> Result<bool, WalletError> {
+        let signature = signature.to_recoverable()?;
+        let hash = Sha3_256::new_with_prefix(data).finalize();
+        Ok(self.pubkey.verify_prehash(&hash, &signature).is_ok())
+    }
+    /// Signs an off-chain message, prefixed with [`MESSAGE_PREFIX`] and its length before hashing
+    ///
+    /// The prefix keeps message signatures from ever being valid transaction signatures, so a login or
+    /// authentication flow can't be tricked into signing a transaction.
 ///
 /// # Example
 /// 
 ///  ```
-/// let wallet = wallet::Wallet::wallet_from_store(PathBuf::from("keystore.json"), "password");
+/// # use icon_utils::wallet::{self, recover_message_address};
+/// # let wallet = wallet::Wallet::new("68ee9ca94b71c42ba79375b8677e29a717fb8072bcb17cd5ee288f9b77bc2894").unwrap();
+/// let sig = wallet.sign_message(b"login nonce 1234").unwrap();
+/// assert!(wallet.verify_message(b"login nonce 1234", &sig).unwrap());
+/// assert_eq!(recover_message_address(b"login nonce 1234", &sig).unwrap().to_string(), wallet.address());
 /// ```
-    pub fn wallet_from_store(path: PathBuf,password: String) -> Wallet {
-        let bytes = decrypt_key(path.as_path(), password).unwrap();
+    pub fn sign_message(&self, msg: &[u8]) -> Result<Signature, WalletError> {
+        self.sign_digest(&message_hash(msg))
+    }
+    /// Checks that a signature made with [`sign_message`](Wallet::sign_message) was made by this wallet
+    pub fn verify_message(&self, msg: &[u8], signature: &Signature) -> Result<bool, WalletError> {
+        let signature = signature.to_recoverable()?;
+        Ok(self.pubkey.verify_prehash(&message_hash(msg), &signature).is_ok())
+    }
+    /// Serializes, hashes and signs a transaction in one call
+///
+/// # Example
+/// 
+///  ```
+/// # use serde::Serialize;
+/// # use icon_utils::{wallet, serializer::Transaction};
+/// # let wallet = wallet::Wallet::new("68ee9ca94b71c42ba79375b8677e29a717fb8072bcb17cd5ee288f9b77bc2894").unwrap();
+/// #[derive(Serialize)]
+/// struct ExampleParams { to: String }
+/// #[derive(Serialize)]
+/// struct ExampleTransaction { method: String, params: ExampleParams }
+/// impl Transaction for ExampleTransaction {
+///     type Params = ExampleParams;
+///     fn params(&self) -> &ExampleParams { &self.params }
+///     fn method(&self) -> &String { &self.method }
+/// }
+/// let tx = ExampleTransaction { method: "icx_sendTransaction".to_string(), params: ExampleParams { to: "hx0".to_string() } };
+/// let signed = wallet.sign_transaction(&tx).unwrap();
+/// println!("{}", signed.signature);
+/// ```
+    pub fn sign_transaction<T>(&self, tx: &T) -> Result<SignedTransaction, WalletError>
+    where
+        T: Transaction,
+        T::Params: serde::Serialize,
+    {
+        Signer::sign_transaction(self, tx)
+    }
+    /// Signs every transaction of a batch, stopping at the first one that fails
+///
+/// # Example
+/// 
+///  ```
+/// # use serde::Serialize;
+/// # use icon_utils::{wallet, serializer::Transaction};
+/// # let wallet = wallet::Wallet::new("68ee9ca94b71c42ba79375b8677e29a717fb8072bcb17cd5ee288f9b77bc2894").unwrap();
+/// #[derive(Serialize, Transaction)]
+/// #[transaction(method = "icx_sendTransaction")]
+/// struct ExampleParams { to: String, nonce: u64 }
+/// let txs: Vec<_> = (0..3).map(|nonce| ExampleParams { to: "hx0".to_string(), nonce }).collect();
+/// let signed = wallet.sign_batch(&txs).unwrap();
+/// assert_eq!(signed.len(), 3);
+/// ```
+    pub fn sign_batch<T>(&self, txs: &[T]) -> Result<Vec<SignedTransaction>, WalletError>
+    where
+        T: Transaction,
+        T::Params: serde::Serialize,
+    {
+        txs.iter()
+            .enumerate()
+            .map(|(index, tx)| self.sign_transaction(tx).map_err(|e| WalletError::Batch { index, source: Box::new(e) }))
+            .collect()
+    }
+    /// Signs every transaction of a batch in parallel on the rayon thread pool, stopping at the first one that fails
+    ///
+    /// The signed transactions are returned in the order of `txs`.
+///
+/// # Example
+/// 
+///  ```
+/// # use serde::Serialize;
+/// # use icon_utils::{wallet, serializer::Transaction};
+/// # let wallet = wallet::Wallet::new("68ee9ca94b71c42ba79375b8677e29a717fb8072bcb17cd5ee288f9b77bc2894").unwrap();
+/// # #[derive(Serialize, Transaction)]
+/// # #[transaction(method = "icx_sendTransaction")]
+/// # struct ExampleParams { to: String, nonce: u64 }
+/// let txs: Vec<_> = (0..100).map(|nonce| ExampleParams { to: "hx0".to_string(), nonce }).collect();
+/// let signed = wallet.par_sign_batch(&txs).unwrap();
+/// assert_eq!(signed[42].params["nonce"], "0x2a");
+/// ```
+    #[cfg(feature = "rayon")]
+    pub fn par_sign_batch<T>(&self, txs: &[T]) -> Result<Vec<SignedTransaction>, WalletError>
+    where
+        T: Transaction + Sync,
+        T::Params: serde::Serialize,
+    {
+        use rayon::prelude::*;
+        txs.par_iter()
+            .enumerate()
+            .map(|(index, tx)| self.sign_transaction(tx).map_err(|e| WalletError::Batch { index, source: Box::new(e) }))
+            .collect()
+    }
+    /// Signs a serialized transaction, panicking if signing fails
+    pub fn sign_unchecked(&self, data: &str) -> Signature {
+        self.sign(data).unwrap()
+    }
+    /// Creates a wallet from a keystore and password
+///
+/// # Example
+/// 
+///  ```no_run
+/// # use icon_utils::wallet;
+/// # use std::path::PathBuf;
+/// let wallet = wallet::Wallet::wallet_from_store(PathBuf::from("keystore.json"), "password".to_string()).unwrap();
+/// ```
+    #[cfg(all(feature = "keystore", not(target_arch = "wasm32")))]
+    pub fn wallet_from_store(path: PathBuf,password: String) -> Result<Wallet, WalletError> {
+        let bytes = decrypt_key(path.as_path(), password)?;
         Wallet::from_bytes(&bytes)
     }
+    /// Creates a wallet from a keystore and password, panicking if the keystore can't be decrypted
+    #[cfg(all(feature = "keystore", not(target_arch = "wasm32")))]
+    pub fn wallet_from_store_unchecked(path: PathBuf,password: String) -> Wallet {
+        Wallet::wallet_from_store(path, password).unwrap()
+    }
+    /// Creates a wallet from the JSON of a keystore and its password, without touching the filesystem
+///
+/// # Example
+///
+///  ```
+/// # use icon_utils::{wallet, keystore::{KdfParams, Keystore}};
+/// # let wallet = wallet::Wallet::new("68ee9ca94b71c42ba79375b8677e29a717fb8072bcb17cd5ee288f9b77bc2894").unwrap();
+/// # let json = Keystore::create(&wallet, "password", &KdfParams::Scrypt { n: 1024, r: 8, p: 1 }).unwrap().to_json().unwrap();
+/// let restored = wallet::Wallet::from_keystore_json(&json, "password").unwrap();
+/// assert_eq!(restored.address(), wallet.address());
+/// ```
+    #[cfg(feature = "keystore")]
+    pub fn from_keystore_json(json: &str, password: &str) -> Result<Wallet, WalletError> {
+        keystore::Keystore::from_json(json)?.decrypt(password)
+    }
+    /// Writes the wallet to an ICON keystore file, encrypted with the given password
+///
+/// # Example
+/// 
+///  ```no_run
+/// # use icon_utils::wallet;
+/// # use std::path::PathBuf;
+/// # let wallet = wallet::Wallet::new("68ee9ca94b71c42ba79375b8677e29a717fb8072bcb17cd5ee288f9b77bc2894").unwrap();
+/// wallet.export_keystore(PathBuf::from("keystore.json"), "password".to_string()).unwrap();
+/// ```
+    #[cfg(all(feature = "keystore", not(target_arch = "wasm32")))]
+    pub fn export_keystore(&self, path: PathBuf, password: String) -> Result<(), WalletError> {
+        self.export_keystore_with_params(path, password, KdfParams::default())
+    }
+    /// Writes the wallet to an ICON keystore file, using the given key derivation parameters
+///
+/// # Example
+/// 
+///  ```no_run
+/// # use icon_utils::{wallet, keystore::KdfParams};
+/// # use std::path::PathBuf;
+/// # let wallet = wallet::Wallet::new("68ee9ca94b71c42ba79375b8677e29a717fb8072bcb17cd5ee288f9b77bc2894").unwrap();
+/// let params = KdfParams::Scrypt { n: 1 << 18, r: 8, p: 1 };
+/// wallet.export_keystore_with_params(PathBuf::from("keystore.json"), "password".to_string(), params).unwrap();
+/// ```
+    #[cfg(all(feature = "keystore", not(target_arch = "wasm32")))]
+    pub fn export_keystore_with_params(&self, path: PathBuf, password: String, params: KdfParams) -> Result<(), WalletError> {
+        let store = keystore::encrypt(&*self.export_private_key(), &self.address(), &password, &params)?;
+        std::fs::write(path, serde_json::to_string(&store).map_err(KeystoreError::from)?).map_err(KeystoreError::from)?;
+        Ok(())
+    }
+    /// Returns the raw private key, which is wiped from memory when the returned value is dropped
+    ///
+    /// The key can't be read from the wallet any other way. Back wallets up with an encrypted keystore instead where
+    /// possible, this is for moving the key to software that can't read keystores.
+///
+/// # Example
+///
+///  ```
+/// # use icon_utils::wallet;
+/// let wallet = wallet::Wallet::new("68ee9ca94b71c42ba79375b8677e29a717fb8072bcb17cd5ee288f9b77bc2894").unwrap();
+/// let key = wallet.export_private_key();
+/// assert_eq!(hex::encode(*key), "68ee9ca94b71c42ba79375b8677e29a717fb8072bcb17cd5ee288f9b77bc2894");
+/// ```
+    pub fn export_private_key(&self) -> Zeroizing<[u8; 32]> {
+        let mut bytes = self.privkey.to_bytes();
+        let key = Zeroizing::new(bytes.into());
+        bytes.as_mut_slice().zeroize();
+        key
+    }
+}
+impl Signer for Wallet {
+    fn address(&self) -> Address {
+        Address::Eoa(pubkey_hash(&self.pubkey))
+    }
+    fn sign_hash(&self, hash: &[u8; 32]) -> Result<Signature, WalletError> {
+        self.sign_digest(hash)
+    }
+}
+impl DeterministicSigner for Wallet {}
+/// Serializes and hashes a transaction for signing, returning its method, JSON params and hash
+pub(crate) fn prepare_transaction<T>(tx: &T) -> Result<(String, serde_json::Value, [u8; 32]), WalletError>
+where
+    T: Transaction,
+    T::Params: serde::Serialize,
+{
+    let hash = serializer::tx_hash(tx)?;
+    let params = crate::params::to_value(tx.params())
+        .map_err(|e| SerializeError::FailedToSerialize(e.to_string()))?;
+    if !params.is_object() {
+        return Err(SerializeError::FailedToSerialize("transaction params must serialize to an object".to_owned()).into());
+    }
+    Ok((tx.method().to_owned(), params, hash))
+}
+/// Derives the hx address of a public key
+pub fn address_from_pubkey(pubkey: &VerifyingKey) -> String {
+    "hx".to_owned() + &hex::encode(pubkey_hash(pubkey))
+}
+pub(crate) fn pubkey_hash(pubkey: &VerifyingKey) -> [u8; 20] {
+    let point = pubkey.to_encoded_point(false);
+    let hash = Sha3_256::new_with_prefix(&point.as_bytes()[1..]).finalize();
+    let mut address = [0u8; 20];
+    address.copy_from_slice(&hash[12..]);
+    address
+}
+/// Recovers the hx address that signed a serialized transaction from its recoverable signature
+///
+/// # Example
+/// 
+///  ```
+/// # use icon_utils::wallet::{self, recover_address};
+/// # let wallet = wallet::Wallet::new("68ee9ca94b71c42ba79375b8677e29a717fb8072bcb17cd5ee288f9b77bc2894").unwrap();
+/// let sig = wallet.sign("example.example").unwrap();
+/// assert_eq!(recover_address("example.example", &sig).unwrap(), wallet.address());
+/// ```
+pub fn recover_address(serialized_tx: &str, signature: &Signature) -> Result<String, WalletError> {
+    let hash: [u8; 32] = Sha3_256::new_with_prefix(serialized_tx).finalize().into();
+    let pubkey = signature.recover_pubkey(&hash)?;
+    Ok(address_from_pubkey(&pubkey))
+}
+/// Prefix of messages signed with [`Wallet::sign_message`], followed by the length of the message in decimal
+pub const MESSAGE_PREFIX: &str = "\x19ICON Signed Message:\n";
+/// Returns the SHA3-256 hash signed by [`Wallet::sign_message`], over the prefix, the message length and the message
+pub fn message_hash(msg: &[u8]) -> [u8; 32] {
+    Sha3_256::new_with_prefix(MESSAGE_PREFIX)
+        .chain_update(msg.len().to_string())
+        .chain_update(msg)
+        .finalize()
+        .into()
+}
+/// Recovers the hx address that signed a message with [`Wallet::sign_message`]
+pub fn recover_message_address(msg: &[u8], signature: &Signature) -> Result<Address, WalletError> {
+    signature.recover_address(&message_hash(msg))
+}
+/// Verifies a signed transaction received from a third party, returning the address that signed it
+///
+/// The params are serialized and hashed again, the signer is recovered from the signature
+/// and checked against the `from` param.
+///
+/// # Example
+/// 
+///  ```
+/// # use serde::Serialize;
+/// # use icon_utils::{wallet::{self, verify_signed_transaction, WalletError}, serializer::Transaction};
+/// # let wallet = wallet::Wallet::new("68ee9ca94b71c42ba79375b8677e29a717fb8072bcb17cd5ee288f9b77bc2894").unwrap();
+/// #[derive(Serialize, Transaction)]
+/// #[transaction(method = "icx_sendTransaction")]
+/// struct ExampleParams { from: String, to: String }
+/// let tx = ExampleParams { from: wallet.address(), to: "hx0".to_string() };
+/// let mut signed = wallet.sign_transaction(&tx).unwrap();
+/// assert_eq!(verify_signed_transaction(&signed).unwrap().to_string(), wallet.address());
+///
+/// signed.params["to"] = "hx1".into();
+/// assert!(matches!(verify_signed_transaction(&signed), Err(WalletError::HashMismatch)));
+/// ```
+pub fn verify_signed_transaction(signed: &SignedTransaction) -> Result<Address, WalletError> {
+    let serialized = serializer::serialize_value(&signed.method, &signed.params)?;
+    let hash: [u8; 32] = Sha3_256::new_with_prefix(serialized).finalize().into();
+    if hash != signed.hash {
+        return Err(WalletError::HashMismatch);
+    }
+    let from: Address = signed.params.get("from")
+        .and_then(|from| from.as_str())
+        .ok_or_else(|| WalletError::InvalidFrom("missing".to_owned()))?
+        .parse()
+        .map_err(|e: crate::types::AddressError| WalletError::InvalidFrom(e.to_string()))?;
+    let signer = signed.signature.recover_address(&hash)?;
+    if signer != from {
+        return Err(WalletError::SignerMismatch { from, signer });
+    }
+    Ok(signer)
 }
 
-}
\ No newline at end of file
+}
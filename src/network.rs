@@ -0,0 +1,60 @@
+//! # Icon Networks
+//! `network` is a module with the network ids and default endpoints of the public ICON networks.
+
+/// An ICON network, identified by its network id
+///
+/// # Example
+///
+///  ```
+/// # use icon_utils::{network::Network, rpc::IconClient};
+/// let network = Network::Lisbon;
+/// assert_eq!(network.nid(), 2);
+/// let client = IconClient::for_network(&network);
+/// assert_eq!(client.endpoint(), "https://lisbon.net.solidwallet.io/api/v3");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Network {
+    Mainnet,
+    Lisbon,
+    Berlin,
+    Sejong,
+    /// Any other network, such as a local node
+    Custom { nid: u64, endpoint: String },
+}
+
+impl Network {
+    /// Returns the network id, used as the `nid` of transactions
+    pub fn nid(&self) -> u64 {
+        match self {
+            Network::Mainnet => 0x1,
+            Network::Lisbon => 0x2,
+            Network::Berlin => 0x7,
+            Network::Sejong => 0x53,
+            Network::Custom { nid, .. } => *nid,
+        }
+    }
+
+    /// Returns the public network with the network id `nid`, None for other networks
+    pub fn from_nid(nid: u64) -> Option<Network> {
+        [Network::Mainnet, Network::Lisbon, Network::Berlin, Network::Sejong].into_iter().find(|network| network.nid() == nid)
+    }
+
+    /// Returns the public network whose default endpoint is `endpoint`, ignoring a trailing slash
+    pub fn from_endpoint(endpoint: &str) -> Option<Network> {
+        let endpoint = endpoint.trim_end_matches('/');
+        [Network::Mainnet, Network::Lisbon, Network::Berlin, Network::Sejong]
+            .into_iter()
+            .find(|network| network.endpoint() == endpoint)
+    }
+
+    /// Returns the default JSON-RPC v3 endpoint of the network
+    pub fn endpoint(&self) -> &str {
+        match self {
+            Network::Mainnet => "https://api.icon.community/api/v3",
+            Network::Lisbon => "https://lisbon.net.solidwallet.io/api/v3",
+            Network::Berlin => "https://berlin.net.solidwallet.io/api/v3",
+            Network::Sejong => "https://sejong.net.solidwallet.io/api/v3",
+            Network::Custom { endpoint, .. } => endpoint,
+        }
+    }
+}
@@ -0,0 +1,467 @@
+//! # Icon SCORE Params
+//! `params` is a module for converting values to and from the string encoding ICON uses for SCORE params.
+//! Integers are `0x` prefixed hex, booleans are `0x1`/`0x0` and bytes are `0x` prefixed hex.
+use serde::de::{self, value::{MapDeserializer, SeqDeserializer}, DeserializeOwned, IntoDeserializer, Visitor};
+use serde::forward_to_deserialize_any;
+use serde::ser::{self, Serialize};
+use serde_json::{Error, Map, Value};
+
+use crate::serializer::BigNum;
+
+/// Trait for values that can be passed as SCORE params
+///
+/// # Example
+///
+///  ```
+/// # use icon_utils::params::IntoParam;
+/// assert_eq!(42u64.into_param(), "0x2a");
+/// assert_eq!(true.into_param(), "0x1");
+/// ```
+pub trait IntoParam {
+    fn into_param(self) -> Value;
+}
+
+impl IntoParam for Value {
+    fn into_param(self) -> Value {
+        self
+    }
+}
+
+impl IntoParam for String {
+    fn into_param(self) -> Value {
+        Value::String(self)
+    }
+}
+
+impl IntoParam for &str {
+    fn into_param(self) -> Value {
+        Value::String(self.to_owned())
+    }
+}
+
+impl IntoParam for &String {
+    fn into_param(self) -> Value {
+        Value::String(self.clone())
+    }
+}
+
+impl IntoParam for bool {
+    fn into_param(self) -> Value {
+        Value::String(if self { "0x1" } else { "0x0" }.to_owned())
+    }
+}
+
+impl IntoParam for &[u8] {
+    fn into_param(self) -> Value {
+        Value::String(crate::icon_hex::encode_bytes_0x(self))
+    }
+}
+
+impl IntoParam for BigNum {
+    fn into_param(self) -> Value {
+        Value::String(self.to_string())
+    }
+}
+
+impl<T: IntoParam> IntoParam for Vec<T> {
+    fn into_param(self) -> Value {
+        Value::Array(self.into_iter().map(IntoParam::into_param).collect())
+    }
+}
+
+impl<T: IntoParam> IntoParam for Option<T> {
+    fn into_param(self) -> Value {
+        self.map_or(Value::Null, IntoParam::into_param)
+    }
+}
+
+macro_rules! impl_unsigned_param {
+    ($($ty:ty),*) => {$(
+        impl IntoParam for $ty {
+            fn into_param(self) -> Value {
+                Value::String(crate::icon_hex::to_hex_no_pad(self as u128))
+            }
+        }
+    )*};
+}
+macro_rules! impl_signed_param {
+    ($($ty:ty),*) => {$(
+        impl IntoParam for $ty {
+            fn into_param(self) -> Value {
+                if self < 0 {
+                    Value::String(format!("-{}", crate::icon_hex::to_hex_no_pad(self.unsigned_abs() as u128)))
+                } else {
+                    Value::String(crate::icon_hex::to_hex_no_pad(self as u128))
+                }
+            }
+        }
+    )*};
+}
+impl_unsigned_param!(u8, u16, u32, u64, u128, usize);
+impl_signed_param!(i8, i16, i32, i64, i128, isize);
+
+/// Decodes a value returned by a SCORE into a Rust type, reading hex strings as integers, booleans and bytes where those are expected
+///
+/// # Example
+///
+///  ```
+/// # use icon_utils::params::from_value;
+/// let balance: u128 = from_value(serde_json::json!("0xde0b6b3a7640000")).unwrap();
+/// assert_eq!(balance, 1_000_000_000_000_000_000);
+/// ```
+pub fn from_value<T: DeserializeOwned>(value: Value) -> Result<T, Error> {
+    T::deserialize(ValueDeserializer(value))
+}
+
+/// Wraps a JSON value so that nested values are decoded with ICON's hex conventions
+struct ValueDeserializer(Value);
+
+impl<'de> IntoDeserializer<'de, Error> for ValueDeserializer {
+    type Deserializer = Self;
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+/// Visits an integer with the narrowest visitor method, since serde's primitive visitors don't accept 128 bit values
+fn visit_int<'de, V: Visitor<'de>>(value: &str, visitor: V) -> Result<V::Value, Error> {
+    if !value.starts_with('-') {
+        let v = crate::icon_hex::from_hex_int(value).map_err(de::Error::custom)?;
+        return match u64::try_from(v) {
+            Ok(v) => visitor.visit_u64(v),
+            Err(_) => visitor.visit_u128(v),
+        };
+    }
+    let v = crate::icon_hex::from_hex_signed(value).map_err(de::Error::custom)?;
+    match i64::try_from(v) {
+        Ok(v) => visitor.visit_i64(v),
+        Err(_) => visitor.visit_i128(v),
+    }
+}
+
+macro_rules! deserialize_int {
+    ($($method:ident),*) => {$(
+        fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+            match &self.0 {
+                Value::String(s) => visit_int(s, visitor),
+                _ => self.0.$method(visitor),
+            }
+        }
+    )*};
+}
+
+impl<'de> de::Deserializer<'de> for ValueDeserializer {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.0 {
+            Value::Array(values) => {
+                let mut seq = SeqDeserializer::new(values.into_iter().map(ValueDeserializer));
+                let result = visitor.visit_seq(&mut seq)?;
+                seq.end()?;
+                Ok(result)
+            }
+            Value::Object(map) => visit_map(map, visitor),
+            other => other.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.0.as_str() {
+            Some("0x1") => visitor.visit_bool(true),
+            Some("0x0") => visitor.visit_bool(false),
+            _ => self.0.deserialize_bool(visitor),
+        }
+    }
+
+    deserialize_int!(
+        deserialize_i8, deserialize_i16, deserialize_i32, deserialize_i64, deserialize_i128,
+        deserialize_u8, deserialize_u16, deserialize_u32, deserialize_u64, deserialize_u128
+    );
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_byte_buf(visitor)
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.0.as_str().filter(|s| s.starts_with("0x")) {
+            Some(s) => visitor.visit_byte_buf(crate::icon_hex::decode_bytes_0x(s).map_err(de::Error::custom)?),
+            None => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.0 {
+            Value::Null => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.0.deserialize_enum(name, variants, visitor)
+    }
+
+    forward_to_deserialize_any! {
+        f32 f64 char str string unit unit_struct seq tuple tuple_struct map struct identifier ignored_any
+    }
+}
+
+fn visit_map<'de, V: Visitor<'de>>(map: Map<String, Value>, visitor: V) -> Result<V::Value, Error> {
+    let mut map = MapDeserializer::new(map.into_iter().map(|(k, v)| (k, ValueDeserializer(v))));
+    let result = visitor.visit_map(&mut map)?;
+    map.end()?;
+    Ok(result)
+}
+
+/// Converts a value to JSON with ICON's hex conventions, writing integers of any width, booleans and bytes as the
+/// strings the serializer writes for them, where `serde_json::to_value` fails on integers above `u64::MAX`
+///
+/// # Example
+///
+///  ```
+/// # use icon_utils::params::to_value;
+/// # use serde_json::json;
+/// #[derive(serde::Serialize)]
+/// struct Transfer { value: u128, refund: bool, memo: Option<String> }
+/// let value = to_value(&Transfer { value: u128::MAX, refund: true, memo: None }).unwrap();
+/// assert_eq!(value, json!({"value": "0xffffffffffffffffffffffffffffffff", "refund": "0x1", "memo": null}));
+/// ```
+pub fn to_value<T: Serialize + ?Sized>(value: &T) -> Result<Value, Error> {
+    value.serialize(ValueSerializer)
+}
+
+/// Serializes into a JSON value, converting scalars with [`IntoParam`]
+struct ValueSerializer;
+
+macro_rules! serialize_param {
+    ($($method:ident: $ty:ty),*) => {$(
+        fn $method(self, v: $ty) -> Result<Value, Error> {
+            Ok(v.into_param())
+        }
+    )*};
+}
+
+impl ser::Serializer for ValueSerializer {
+    type Ok = Value;
+    type Error = Error;
+    type SerializeSeq = SeqSerializer;
+    type SerializeTuple = SeqSerializer;
+    type SerializeTupleStruct = SeqSerializer;
+    type SerializeTupleVariant = VariantSerializer<SeqSerializer>;
+    type SerializeMap = MapSerializer;
+    type SerializeStruct = MapSerializer;
+    type SerializeStructVariant = VariantSerializer<MapSerializer>;
+
+    serialize_param!(
+        serialize_bool: bool, serialize_i8: i8, serialize_i16: i16, serialize_i32: i32, serialize_i64: i64,
+        serialize_i128: i128, serialize_u8: u8, serialize_u16: u16, serialize_u32: u32, serialize_u64: u64,
+        serialize_u128: u128, serialize_str: &str, serialize_bytes: &[u8]
+    );
+
+    fn serialize_f32(self, v: f32) -> Result<Value, Error> {
+        serde_json::to_value(v)
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Value, Error> {
+        serde_json::to_value(v)
+    }
+
+    fn serialize_char(self, v: char) -> Result<Value, Error> {
+        Ok(Value::String(v.to_string()))
+    }
+
+    fn serialize_none(self) -> Result<Value, Error> {
+        Ok(Value::Null)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Value, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Value, Error> {
+        Ok(Value::Null)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Value, Error> {
+        Ok(Value::Null)
+    }
+
+    fn serialize_unit_variant(self, _name: &'static str, _index: u32, variant: &'static str) -> Result<Value, Error> {
+        Ok(Value::String(variant.to_owned()))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(self, _name: &'static str, value: &T) -> Result<Value, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Value, Error> {
+        Ok(Value::Object(Map::from_iter([(variant.to_owned(), value.serialize(self)?)])))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<SeqSerializer, Error> {
+        Ok(SeqSerializer(Vec::with_capacity(len.unwrap_or(0))))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<SeqSerializer, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, len: usize) -> Result<SeqSerializer, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<VariantSerializer<SeqSerializer>, Error> {
+        Ok(VariantSerializer { variant, inner: self.serialize_seq(Some(len))? })
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<MapSerializer, Error> {
+        Ok(MapSerializer { map: Map::new(), key: None })
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<MapSerializer, Error> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<VariantSerializer<MapSerializer>, Error> {
+        Ok(VariantSerializer { variant, inner: self.serialize_map(Some(len))? })
+    }
+}
+
+struct SeqSerializer(Vec<Value>);
+
+impl SeqSerializer {
+    fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.0.push(value.serialize(ValueSerializer)?);
+        Ok(())
+    }
+}
+
+impl ser::SerializeSeq for SeqSerializer {
+    type Ok = Value;
+    type Error = Error;
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+    fn end(self) -> Result<Value, Error> {
+        Ok(Value::Array(self.0))
+    }
+}
+
+impl ser::SerializeTuple for SeqSerializer {
+    type Ok = Value;
+    type Error = Error;
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+    fn end(self) -> Result<Value, Error> {
+        Ok(Value::Array(self.0))
+    }
+}
+
+impl ser::SerializeTupleStruct for SeqSerializer {
+    type Ok = Value;
+    type Error = Error;
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+    fn end(self) -> Result<Value, Error> {
+        Ok(Value::Array(self.0))
+    }
+}
+
+struct MapSerializer {
+    map: Map<String, Value>,
+    key: Option<String>,
+}
+
+impl MapSerializer {
+    fn insert<T: Serialize + ?Sized>(&mut self, key: String, value: &T) -> Result<(), Error> {
+        self.map.insert(key, value.serialize(ValueSerializer)?);
+        Ok(())
+    }
+}
+
+impl ser::SerializeMap for MapSerializer {
+    type Ok = Value;
+    type Error = Error;
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Error> {
+        match key.serialize(ValueSerializer)? {
+            Value::String(key) => self.key = Some(key),
+            key => return Err(ser::Error::custom(format!("map keys must be strings, got {}", key))),
+        }
+        Ok(())
+    }
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        let key = self.key.take().ok_or_else(|| <Error as ser::Error>::custom("map value without a key"))?;
+        self.insert(key, value)
+    }
+    fn end(self) -> Result<Value, Error> {
+        Ok(Value::Object(self.map))
+    }
+}
+
+impl ser::SerializeStruct for MapSerializer {
+    type Ok = Value;
+    type Error = Error;
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> Result<(), Error> {
+        self.insert(key.to_owned(), value)
+    }
+    fn end(self) -> Result<Value, Error> {
+        Ok(Value::Object(self.map))
+    }
+}
+
+/// Wraps the fields of an enum variant as `{variant: fields}`, as serde_json does
+struct VariantSerializer<S> {
+    variant: &'static str,
+    inner: S,
+}
+
+impl ser::SerializeTupleVariant for VariantSerializer<SeqSerializer> {
+    type Ok = Value;
+    type Error = Error;
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.inner.push(value)
+    }
+    fn end(self) -> Result<Value, Error> {
+        Ok(Value::Object(Map::from_iter([(self.variant.to_owned(), Value::Array(self.inner.0))])))
+    }
+}
+
+impl ser::SerializeStructVariant for VariantSerializer<MapSerializer> {
+    type Ok = Value;
+    type Error = Error;
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> Result<(), Error> {
+        self.inner.insert(key.to_owned(), value)
+    }
+    fn end(self) -> Result<Value, Error> {
+        Ok(Value::Object(Map::from_iter([(self.variant.to_owned(), Value::Object(self.inner.map))])))
+    }
+}
@@ -0,0 +1,766 @@
+//! # Icon RPC
+//! `rpc` is a module for sending transactions and queries to an ICON node over JSON-RPC.
+#[cfg(feature = "wallet")]
+use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use thiserror::Error;
+
+use crate::abi::ScoreApi;
+use crate::network::Network;
+use crate::params::IntoParam;
+use crate::transaction::IcxTransaction;
+#[cfg(feature = "wallet")]
+use crate::signer::Signer;
+use crate::types::{Address, Hash256};
+use crate::units::Loop;
+#[cfg(feature = "wallet")]
+use crate::units::Icx;
+#[cfg(feature = "wallet")]
+use crate::wallet::SignedTransaction;
+
+#[cfg(feature = "async")]
+pub mod async_client;
+pub mod btp;
+pub mod call;
+pub mod middleware;
+pub mod stream;
+#[cfg(feature = "wallet")]
+pub mod transfer;
+pub mod transport;
+pub mod types;
+#[cfg(feature = "websocket")]
+pub mod ws;
+
+pub use btp::BtpNetworkInfo;
+pub use call::CallBuilder;
+#[cfg(feature = "async")]
+pub use stream::AsyncBlockStream;
+pub use stream::BlockStream;
+#[cfg(feature = "wallet")]
+pub use transfer::{TransferError, TransferOptions};
+pub use transport::{HttpTransport, Transport};
+pub use types::{
+    Block, ConfirmedTransaction, ContractStatus, DepositInfo, EventLog, ExecutionTrace, FailureReason, ScoreContract, ScoreStatus,
+    TraceLog, TransactionData, TransactionResult, TxFailure,
+};
+
+/// Error codes returned by nodes while a transaction hasn't been finalized yet
+const PENDING_CODES: [i64; 3] = [-31002, -31003, -31004];
+/// Error codes returned by nodes when a blocking wait ended before the transaction was finalized
+const WAIT_TIMEOUT_CODES: [i64; 2] = [-31006, -31007];
+/// JSON-RPC code of SCORE errors, a SCORE failing with failure code `n` returns `SCORE_ERROR_CODE - n`
+const SCORE_ERROR_CODE: i64 = -30000;
+/// Error code returned by nodes for methods they don't implement
+const METHOD_NOT_FOUND_CODE: i64 = -32601;
+/// Interval between `icx_getTransactionResult` polls when a node can't wait for results itself
+const FALLBACK_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Error, Debug)]
+// Error struct for RPC requests
+pub enum RpcError {
+    /// The request didn't get a response, such as when the host can't be resolved, the connection was refused
+    /// or the request timed out
+    #[error("HTTP request failed: {0}")]
+    Transport(String),
+    /// The response had an error status and no JSON-RPC body, such as rate limiting by a proxy in front of the node
+    #[error("HTTP status {status} from {url}")]
+    Http { status: u16, url: String },
+    /// A JSON-RPC error object returned by the node, see [`RpcError::node_error`] for the named codes
+    #[error("Node returned error {code}: {message}")]
+    Node { code: i64, message: String, data: Option<Value> },
+    #[error("Invalid request: {0}")]
+    InvalidRequest(String),
+    #[error("Invalid response from node: {0}")]
+    InvalidResponse(String),
+    #[error("Timed out after {0:?}")]
+    Timeout(Duration),
+    #[error("WebSocket error: {0}")]
+    WebSocket(String),
+    #[error("Circuit breaker is open after repeated failures, retry in {0:?}")]
+    CircuitOpen(Duration),
+    #[error("Block {height} doesn't follow block {expected}, its previous hash is {prev_hash:?}")]
+    BrokenChain { height: u64, expected: Hash256, prev_hash: Option<Hash256> },
+}
+
+impl RpcError {
+    /// Returns true if the node reported that the transaction is still pending
+    pub fn is_pending(&self) -> bool {
+        matches!(self, RpcError::Node { code, .. } if PENDING_CODES.contains(code))
+    }
+
+    /// Returns true if the node doesn't implement the requested method
+    pub fn is_method_not_found(&self) -> bool {
+        matches!(self, RpcError::Node { code: METHOD_NOT_FOUND_CODE, .. })
+    }
+
+    /// Returns true if a blocking wait ended before the transaction was finalized, so polling can pick up from there
+    pub(crate) fn is_wait_incomplete(&self) -> bool {
+        self.is_pending() || matches!(self, RpcError::Node { code, .. } if WAIT_TIMEOUT_CODES.contains(code))
+    }
+
+    /// Returns true for errors worth retrying, where the request may not have reached the node
+    ///
+    /// These are transport failures, and the statuses proxies return while the node is busy or unreachable.
+    pub fn is_transient(&self) -> bool {
+        matches!(self, RpcError::Transport(_) | RpcError::Http { status: 429 | 502 | 503 | 504, .. })
+    }
+
+    /// Returns the named error of a JSON-RPC error object, None for every other error
+    ///
+    /// # Example
+    ///
+    ///  ```
+    /// # use icon_utils::rpc::{NodeError, RpcError};
+    /// let error = RpcError::Node { code: -30032, message: "Reverted(0)".to_owned(), data: None };
+    /// assert_eq!(error.node_error(), Some(NodeError::ScoreError(32)));
+    /// assert_eq!(error.score_failure().unwrap().code(), 32);
+    /// ```
+    pub fn node_error(&self) -> Option<NodeError> {
+        match self {
+            RpcError::Node { code, .. } => Some(NodeError::from_code(*code)),
+            _ => None,
+        }
+    }
+
+    /// Returns the failure of a SCORE call or transaction the node rejected with a SCORE error
+    pub fn score_failure(&self) -> Option<TxFailure> {
+        match (self, self.node_error()?) {
+            (RpcError::Node { message, .. }, NodeError::ScoreError(code)) => {
+                Some(TxFailure::from(&FailureReason { code, message: message.clone() }))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// The named JSON-RPC error codes of ICON nodes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NodeError {
+    ParseError,
+    InvalidRequest,
+    MethodNotFound,
+    InvalidParams,
+    InternalError,
+    /// One of the implementation defined server errors, between -32000 and -32099
+    ServerError(i64),
+    SystemError,
+    PoolOverflow,
+    /// The transaction is in the pool and hasn't been executed yet
+    Pending,
+    /// The transaction is being executed
+    Executing,
+    /// The transaction or block doesn't exist
+    NotFound,
+    LackOfResource,
+    /// A blocking wait timed out
+    Timeout,
+    /// A blocking wait timed out because of the node's own limit
+    SystemTimeout,
+    /// The SCORE failed, with the failure code a receipt would have, see [`TxFailure`]
+    ScoreError(i64),
+    Other(i64),
+}
+
+impl NodeError {
+    /// Maps a JSON-RPC error code to its name
+    pub fn from_code(code: i64) -> Self {
+        match code {
+            -32700 => NodeError::ParseError,
+            -32600 => NodeError::InvalidRequest,
+            -32601 => NodeError::MethodNotFound,
+            -32602 => NodeError::InvalidParams,
+            -32603 => NodeError::InternalError,
+            -32099..=-32000 => NodeError::ServerError(code),
+            -31000 => NodeError::SystemError,
+            -31001 => NodeError::PoolOverflow,
+            -31002 => NodeError::Pending,
+            -31003 => NodeError::Executing,
+            -31004 => NodeError::NotFound,
+            -31005 => NodeError::LackOfResource,
+            -31006 => NodeError::Timeout,
+            -31007 => NodeError::SystemTimeout,
+            -30999..=-30000 => NodeError::ScoreError(SCORE_ERROR_CODE - code),
+            code => NodeError::Other(code),
+        }
+    }
+
+    /// Returns the JSON-RPC error code
+    pub fn code(&self) -> i64 {
+        match self {
+            NodeError::ParseError => -32700,
+            NodeError::InvalidRequest => -32600,
+            NodeError::MethodNotFound => -32601,
+            NodeError::InvalidParams => -32602,
+            NodeError::InternalError => -32603,
+            NodeError::SystemError => -31000,
+            NodeError::PoolOverflow => -31001,
+            NodeError::Pending => -31002,
+            NodeError::Executing => -31003,
+            NodeError::NotFound => -31004,
+            NodeError::LackOfResource => -31005,
+            NodeError::Timeout => -31006,
+            NodeError::SystemTimeout => -31007,
+            NodeError::ScoreError(failure) => SCORE_ERROR_CODE - failure,
+            NodeError::ServerError(code) | NodeError::Other(code) => *code,
+        }
+    }
+}
+
+/// A client for the ICON JSON-RPC v3 API
+///
+/// Requests are sent over HTTP by default, any [`Transport`] can be used instead with `with_transport`.
+pub struct IconClient<T = HttpTransport> {
+    endpoint: String,
+    transport: T,
+    next_id: AtomicU64,
+    /// Cleared once the node turns out not to support the blocking wait methods
+    blocking_wait: AtomicBool,
+}
+
+impl IconClient {
+    /// Creates a client for the given endpoint URL
+    ///
+    /// # Example
+    ///
+    ///  ```
+    /// # use icon_utils::rpc::IconClient;
+    /// let client = IconClient::new("https://api.icon.community/api/v3");
+    /// ```
+    pub fn new(endpoint: &str) -> Self {
+        IconClient::with_transport(endpoint, HttpTransport::new())
+    }
+
+    /// Creates a client for the default endpoint of a network
+    pub fn for_network(network: &Network) -> Self {
+        IconClient::new(network.endpoint())
+    }
+}
+
+impl IconClient<middleware::Failover<HttpTransport>> {
+    /// Creates a client for `primary`, failing over to `fallbacks` in order while it's down
+    ///
+    /// See [`Failover`](middleware::Failover) for when an endpoint counts as down.
+    ///
+    /// # Example
+    ///
+    ///  ```
+    /// # use icon_utils::rpc::IconClient;
+    /// let client = IconClient::with_endpoints("https://api.icon.community/api/v3", &["https://ctz.solidwallet.io/api/v3"]);
+    /// assert_eq!(client.transport().endpoints().len(), 2);
+    /// ```
+    pub fn with_endpoints(primary: &str, fallbacks: &[&str]) -> Self {
+        let endpoints: Vec<&str> = std::iter::once(primary).chain(fallbacks.iter().copied()).collect();
+        IconClient::with_transport(primary, middleware::Failover::new(HttpTransport::new(), &endpoints))
+    }
+}
+
+impl<T: Transport> IconClient<T> {
+    /// Creates a client that sends requests through `transport`
+    pub fn with_transport(endpoint: &str, transport: T) -> Self {
+        IconClient { endpoint: endpoint.to_owned(), transport, next_id: AtomicU64::new(1), blocking_wait: AtomicBool::new(true) }
+    }
+
+    /// Returns the transport the client sends requests through
+    pub fn transport(&self) -> &T {
+        &self.transport
+    }
+
+    /// Returns the endpoint URL the client sends requests to
+    pub fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    /// Sends a signed transaction with `icx_sendTransaction` and returns its tx hash
+    ///
+    /// # Example
+    ///
+    ///  ```no_run
+    /// # use icon_utils::{rpc::IconClient, wallet::SignedTransaction};
+    /// # fn send(signed: SignedTransaction) {
+    /// let client = IconClient::new("https://api.icon.community/api/v3");
+    /// let hash = client.send_transaction(&signed).unwrap();
+    /// # }
+    /// ```
+    #[cfg(feature = "wallet")]
+    pub fn send_transaction(&self, tx: &SignedTransaction) -> Result<Hash256, RpcError> {
+        let hash = parse_hash(self.request(&tx.method, tx.to_rpc_params())?)?;
+        #[cfg(feature = "tracing")]
+        tracing::info!(tx_hash = %hash, endpoint = %self.endpoint, "sent transaction");
+        Ok(hash)
+    }
+
+    /// Sends `amount` ICX from `wallet` to `to`, returning the tx hash once the node accepts the transaction
+    ///
+    /// The transfer is built, its steps are estimated with `debug_estimateStep`, then it's signed and sent with
+    /// `icx_sendTransaction`. See [`TransferOptions`] for the defaults.
+    ///
+    /// # Example
+    ///
+    ///  ```
+    /// # use icon_utils::rpc::{transport::MockTransport, IconClient, TransferOptions};
+    /// # use icon_utils::wallet::Wallet;
+    /// # use serde_json::json;
+    /// # let wallet = Wallet::new("68ee9ca94b71c42ba79375b8677e29a717fb8072bcb17cd5ee288f9b77bc2894").unwrap();
+    /// let transport = MockTransport::new();
+    /// transport.respond("debug_estimateStep", json!("0x186a0"));
+    /// transport.respond("icx_sendTransaction", json!(format!("0x{}", "ab".repeat(32))));
+    /// let client = IconClient::with_transport("http://localhost:9000/api/v3", transport);
+    /// let to = "hx5bfdb090f43a808005ffc27c25b213145e80b7cd";
+    /// let hash = client.transfer(&wallet, to, "1.5".parse().unwrap(), TransferOptions::new().nid(3)).unwrap();
+    /// let sent = &client.transport().requests_for("icx_sendTransaction")[0].params;
+    /// assert_eq!(sent["value"], "0x14d1120d7b160000");
+    /// assert_eq!(sent["stepLimit"], "0x1adb0");
+    /// ```
+    #[cfg(feature = "wallet")]
+    pub fn transfer(&self, wallet: &impl Signer, to: impl fmt::Display, amount: Icx, options: TransferOptions) -> Result<Hash256, TransferError> {
+        let mut tx = options.build(&self.endpoint, wallet.address(), to, amount)?;
+        if options.needs_estimate() {
+            tx.step_limit = options.with_margin(self.estimate_step(&tx)?);
+        }
+        let signed = wallet.sign_transaction(&tx)?;
+        Ok(self.send_transaction(&signed)?)
+    }
+
+    /// Gets the receipt of a transaction with `icx_getTransactionResult`
+    ///
+    /// # Example
+    ///
+    ///  ```no_run
+    /// # use icon_utils::rpc::IconClient;
+    /// let client = IconClient::new("https://api.icon.community/api/v3");
+    /// let hash = "0x2e8e5c6f8a9e4c1b5d3f7a0b2c4d6e8f1a3b5c7d9e0f2a4b6c8d0e2f4a6b8c0d".parse().unwrap();
+    /// let result = client.get_transaction_result(&hash).unwrap();
+    /// println!("step used: {}", result.step_used);
+    /// ```
+    pub fn get_transaction_result(&self, hash: &Hash256) -> Result<TransactionResult, RpcError> {
+        let result = self.request("icx_getTransactionResult", json!({ "txHash": hash }))?;
+        from_result(result)
+    }
+
+    /// Gets a confirmed transaction with `icx_getTransactionByHash`, decoding its data
+    ///
+    /// # Example
+    ///
+    ///  ```no_run
+    /// # use icon_utils::rpc::{IconClient, TransactionData};
+    /// let client = IconClient::new("https://api.icon.community/api/v3");
+    /// let hash = "0x2e8e5c6f8a9e4c1b5d3f7a0b2c4d6e8f1a3b5c7d9e0f2a4b6c8d0e2f4a6b8c0d".parse().unwrap();
+    /// let tx = client.get_transaction_by_hash(&hash).unwrap();
+    /// if let TransactionData::Call { method, .. } = tx.data {
+    ///     println!("called {} on {}", method, tx.to);
+    /// }
+    /// ```
+    pub fn get_transaction_by_hash(&self, hash: &Hash256) -> Result<ConfirmedTransaction, RpcError> {
+        let result = self.request("icx_getTransactionByHash", json!({ "txHash": hash }))?;
+        from_result(result)
+    }
+
+    /// Polls `icx_getTransactionResult` every `interval` until the receipt is available, or `timeout` has passed
+    ///
+    /// # Example
+    ///
+    ///  ```no_run
+    /// # use icon_utils::rpc::IconClient;
+    /// # use std::time::Duration;
+    /// let client = IconClient::new("https://api.icon.community/api/v3");
+    /// let hash = "0x2e8e5c6f8a9e4c1b5d3f7a0b2c4d6e8f1a3b5c7d9e0f2a4b6c8d0e2f4a6b8c0d".parse().unwrap();
+    /// let result = client.wait_for_result(&hash, Duration::from_secs(30), Duration::from_secs(1)).unwrap();
+    /// assert!(result.is_success());
+    /// ```
+    pub fn wait_for_result(&self, hash: &Hash256, timeout: Duration, interval: Duration) -> Result<TransactionResult, RpcError> {
+        let start = Instant::now();
+        loop {
+            match self.get_transaction_result(hash) {
+                Err(e) if e.is_pending() => {}
+                result => return result,
+            }
+            if start.elapsed() + interval > timeout {
+                return Err(RpcError::Timeout(timeout));
+            }
+            std::thread::sleep(interval);
+        }
+    }
+
+    /// Sends a signed transaction with `icx_sendTransactionAndWait`, returning its receipt once it's finalized
+    ///
+    /// The node waits up to `timeout` for the receipt before answering. Nodes without the blocking method get the
+    /// transaction through `icx_sendTransaction` instead, and the receipt is polled for every second until `timeout`
+    /// has passed. Polling also takes over when the node stops waiting before `timeout`.
+    ///
+    /// # Example
+    ///
+    ///  ```
+    /// # use icon_utils::rpc::{IconClient, transport::MockTransport};
+    /// # use icon_utils::{transaction::TransactionBuilder, wallet::Wallet};
+    /// # use serde_json::json;
+    /// # use std::time::Duration;
+    /// # let wallet = Wallet::new("68ee9ca94b71c42ba79375b8677e29a717fb8072bcb17cd5ee288f9b77bc2894").unwrap();
+    /// # let tx = TransactionBuilder::new().from(wallet.address()).to("hx0000000000000000000000000000000000000000")
+    /// #     .step_limit(100_000).nid(1).timestamp(1).build().unwrap();
+    /// # let signed = wallet.sign_transaction(&tx).unwrap();
+    /// let transport = MockTransport::new();
+    /// transport.respond("icx_sendTransactionAndWait", json!({
+    ///     "status": "0x1", "txHash": signed.tx_hash(), "txIndex": "0x0", "blockHeight": "0x1",
+    ///     "blockHash": format!("0x{}", "00".repeat(32)), "cumulativeStepUsed": "0x0", "stepUsed": "0x186a0",
+    ///     "stepPrice": "0x2e90edd00", "eventLogs": []
+    /// }));
+    /// let client = IconClient::with_transport("http://localhost:9000/api/v3", transport);
+    /// let result = client.send_transaction_and_wait(&signed, Duration::from_secs(10)).unwrap();
+    /// assert!(result.is_success());
+    /// let request = &client.transport().requests()[0];
+    /// assert_eq!(request.headers, vec![("Icon-Options".to_owned(), "timeout=10000".to_owned())]);
+    /// ```
+    #[cfg(feature = "wallet")]
+    pub fn send_transaction_and_wait(&self, tx: &SignedTransaction, timeout: Duration) -> Result<TransactionResult, RpcError> {
+        let start = Instant::now();
+        if tx.method == "icx_sendTransaction" && self.blocking_wait.load(Ordering::Relaxed) {
+            match self.request_with_wait("icx_sendTransactionAndWait", tx.to_rpc_params(), timeout) {
+                Err(e) if e.is_method_not_found() => self.blocking_wait.store(false, Ordering::Relaxed),
+                Err(e) if e.is_wait_incomplete() => return self.poll_remaining(&tx.tx_hash(), start, timeout),
+                result => return result.and_then(from_result),
+            }
+        }
+        let hash = self.send_transaction(tx)?;
+        self.poll_remaining(&hash, start, timeout)
+    }
+
+    /// Waits for the receipt of a transaction with `icx_waitTransactionResult`, letting the node wait up to `timeout`
+    ///
+    /// Nodes without the blocking method are polled with `icx_getTransactionResult` every second instead, as are
+    /// nodes that stop waiting before `timeout`.
+    ///
+    /// # Example
+    ///
+    ///  ```
+    /// # use icon_utils::rpc::{IconClient, transport::MockTransport};
+    /// # use serde_json::json;
+    /// # use std::time::Duration;
+    /// # use icon_utils::types::Hash256;
+    /// let hash: Hash256 = "0x2e8e5c6f8a9e4c1b5d3f7a0b2c4d6e8f1a3b5c7d9e0f2a4b6c8d0e2f4a6b8c0d".parse().unwrap();
+    /// let transport = MockTransport::new();
+    /// transport.respond_error("icx_waitTransactionResult", -32601, "MethodNotFound");
+    /// transport.respond("icx_getTransactionResult", json!({
+    ///     "status": "0x1", "txHash": hash, "txIndex": "0x0", "blockHeight": "0x1",
+    ///     "blockHash": format!("0x{}", "00".repeat(32)), "cumulativeStepUsed": "0x0", "stepUsed": "0x186a0",
+    ///     "stepPrice": "0x2e90edd00", "eventLogs": []
+    /// }));
+    /// let client = IconClient::with_transport("http://localhost:9000/api/v3", transport);
+    /// let result = client.wait_transaction_result(&hash, Duration::from_secs(10)).unwrap();
+    /// assert!(result.is_success());
+    /// // The client remembers that the node can't wait, and polls straight away from then on
+    /// client.wait_transaction_result(&hash, Duration::from_secs(10)).unwrap();
+    /// assert_eq!(client.transport().requests_for("icx_waitTransactionResult").len(), 1);
+    /// ```
+    pub fn wait_transaction_result(&self, hash: &Hash256, timeout: Duration) -> Result<TransactionResult, RpcError> {
+        let start = Instant::now();
+        if self.blocking_wait.load(Ordering::Relaxed) {
+            match self.request_with_wait("icx_waitTransactionResult", json!({ "txHash": hash }), timeout) {
+                Err(e) if e.is_method_not_found() => self.blocking_wait.store(false, Ordering::Relaxed),
+                Err(e) if e.is_wait_incomplete() => return self.poll_remaining(hash, start, timeout),
+                result => return result.and_then(from_result),
+            }
+        }
+        self.poll_remaining(hash, start, timeout)
+    }
+
+    /// Polls for a receipt until `timeout` has passed since `start`
+    fn poll_remaining(&self, hash: &Hash256, start: Instant, timeout: Duration) -> Result<TransactionResult, RpcError> {
+        match self.wait_for_result(hash, timeout.saturating_sub(start.elapsed()), FALLBACK_POLL_INTERVAL) {
+            Err(RpcError::Timeout(_)) => Err(RpcError::Timeout(timeout)),
+            result => result,
+        }
+    }
+
+    /// Calls a read-only SCORE method with `icx_call`, decoding the result into `T`
+    ///
+    /// Hex strings in the result are decoded as integers, booleans or bytes where `T` expects them.
+    ///
+    /// # Example
+    ///
+    ///  ```no_run
+    /// # use icon_utils::rpc::{CallBuilder, IconClient};
+    /// let client = IconClient::new("https://api.icon.community/api/v3");
+    /// let call = CallBuilder::new("cx88fd7df7ddff82f7cc735c871dc519838cb235bb", "balanceOf")
+    ///     .param("_owner", "hxbe258ceb872e08851f1f59694dac2558708ece11");
+    /// let balance: u128 = client.call(&call).unwrap();
+    /// ```
+    pub fn call<R: DeserializeOwned>(&self, call: &CallBuilder) -> Result<R, RpcError> {
+        let result = self.request("icx_call", call.to_params())?;
+        decode_result(result)
+    }
+
+    /// Gets the latest block with `icx_getLastBlock`
+    ///
+    /// # Example
+    ///
+    ///  ```no_run
+    /// # use icon_utils::rpc::IconClient;
+    /// let client = IconClient::new("https://api.icon.community/api/v3");
+    /// let block = client.get_last_block().unwrap();
+    /// println!("height {} has {} transactions", block.height, block.transactions.len());
+    /// ```
+    pub fn get_last_block(&self) -> Result<Block, RpcError> {
+        let result = self.request("icx_getLastBlock", Value::Null)?;
+        from_result(result)
+    }
+
+    /// Gets the block at `height` with `icx_getBlockByHeight`
+    pub fn get_block_by_height(&self, height: u64) -> Result<Block, RpcError> {
+        let result = self.request("icx_getBlockByHeight", json!({ "height": height.into_param() }))?;
+        from_result(result)
+    }
+
+    /// Gets a block by its hash with `icx_getBlockByHash`
+    pub fn get_block_by_hash(&self, hash: &Hash256) -> Result<Block, RpcError> {
+        let result = self.request("icx_getBlockByHash", json!({ "hash": hash }))?;
+        from_result(result)
+    }
+
+    /// Gets the API of the SCORE at `address` with `icx_getScoreApi`, which can be turned into a typed client with [`abi::generate`](crate::abi::generate)
+    pub fn get_score_api(&self, address: &Address) -> Result<ScoreApi, RpcError> {
+        let result = self.request("icx_getScoreApi", json!({ "address": address }))?;
+        from_result(result)
+    }
+
+    /// Gets the deployment and audit state of the SCORE at `address` with `icx_getScoreStatus`
+    ///
+    /// # Example
+    ///
+    ///  ```no_run
+    /// # use icon_utils::rpc::IconClient;
+    /// let client = IconClient::new("https://api.icon.community/api/v3");
+    /// let status = client.get_score_status(&"cx88fd7df7ddff82f7cc735c871dc519838cb235bb".parse().unwrap()).unwrap();
+    /// if status.is_pending() {
+    ///     println!("waiting on audit of {:?}", status.next.unwrap().deploy_tx_hash);
+    /// }
+    /// ```
+    pub fn get_score_status(&self, address: &Address) -> Result<ScoreStatus, RpcError> {
+        let result = self.request("icx_getScoreStatus", json!({ "address": address }))?;
+        from_result(result)
+    }
+
+    /// Gets the ICX balance of an address with `icx_getBalance`
+    ///
+    /// # Example
+    ///
+    ///  ```no_run
+    /// # use icon_utils::rpc::IconClient;
+    /// let client = IconClient::new("https://api.icon.community/api/v3");
+    /// let address = "hxbe258ceb872e08851f1f59694dac2558708ece11".parse().unwrap();
+    /// let balance = client.get_balance(&address).unwrap();
+    /// println!("{} ICX", balance.format_icx());
+    /// ```
+    pub fn get_balance(&self, address: &Address) -> Result<Loop, RpcError> {
+        let result = self.request("icx_getBalance", json!({ "address": address }))?;
+        from_result(result)
+    }
+
+    /// Gets the total supply of ICX with `icx_getTotalSupply`
+    pub fn get_total_supply(&self) -> Result<Loop, RpcError> {
+        let result = self.request("icx_getTotalSupply", Value::Null)?;
+        from_result(result)
+    }
+
+    /// Gets a BTP network with `btp_getNetworkInfo`, at `height` or at the latest block when None
+    pub fn get_btp_network_info(&self, network_id: u64, height: Option<u64>) -> Result<BtpNetworkInfo, RpcError> {
+        let result = self.request("btp_getNetworkInfo", btp::network_info_params(network_id, height))?;
+        from_result(result)
+    }
+
+    /// Gets the messages sent to a BTP network in the block at `height` with `btp_getMessages`
+    pub fn get_btp_messages(&self, network_id: u64, height: u64) -> Result<Vec<Vec<u8>>, RpcError> {
+        let result = self.request("btp_getMessages", btp::block_params(network_id, height))?;
+        btp::decode_messages(result)
+    }
+
+    /// Gets the encoded BTP block header of a network at `height` with `btp_getHeader`
+    ///
+    /// # Example
+    ///
+    ///  ```
+    /// # use icon_utils::rpc::{IconClient, transport::MockTransport};
+    /// # use serde_json::json;
+    /// let transport = MockTransport::new();
+    /// transport.respond("btp_getHeader", json!("+E6CAQA="));
+    /// let client = IconClient::with_transport("http://localhost:9000/api/v3", transport);
+    /// let header = client.get_btp_header(1, 100).unwrap();
+    /// assert_eq!(header, [0xf8, 0x4e, 0x82, 0x01, 0x00]);
+    /// assert_eq!(client.transport().requests()[0].params, json!({ "networkID": "0x1", "height": "0x64" }));
+    /// ```
+    pub fn get_btp_header(&self, network_id: u64, height: u64) -> Result<Vec<u8>, RpcError> {
+        let result = self.request("btp_getHeader", btp::block_params(network_id, height))?;
+        btp::decode_bytes(result)
+    }
+
+    /// Gets the encoded proof of a network's BTP block at `height` with `btp_getProof`
+    pub fn get_btp_proof(&self, network_id: u64, height: u64) -> Result<Vec<u8>, RpcError> {
+        let result = self.request("btp_getProof", btp::block_params(network_id, height))?;
+        btp::decode_bytes(result)
+    }
+
+    /// Estimates the steps an unsigned transaction would use with `debug_estimateStep`
+    ///
+    /// The request is sent to the debug API of the node, `/api/v3d` next to the `/api/v3` endpoint.
+    ///
+    /// # Example
+    ///
+    ///  ```no_run
+    /// # use icon_utils::{rpc::IconClient, transaction::TransactionBuilder};
+    /// let client = IconClient::new("https://api.icon.community/api/v3");
+    /// let tx = TransactionBuilder::new()
+    ///     .from("hxbe258ceb872e08851f1f59694dac2558708ece11")
+    ///     .to("hx5bfdb090f43a808005ffc27c25b213145e80b7cd")
+    ///     .step_limit(0)
+    ///     .nid(1)
+    ///     .build()
+    ///     .unwrap();
+    /// let steps = client.estimate_step(&tx).unwrap();
+    /// ```
+    pub fn estimate_step(&self, tx: &IcxTransaction) -> Result<u64, RpcError> {
+        let result = self.post(&debug_endpoint(&self.endpoint), "debug_estimateStep", estimate_params(tx)?)?;
+        decode_result(result)
+    }
+
+    /// Gets the execution trace of a transaction with `debug_getTrace`, to find out why a SCORE call failed
+    ///
+    /// The request is sent to the debug API of the node, `/api/v3d` next to the `/api/v3` endpoint, which public
+    /// endpoints usually don't enable.
+    ///
+    /// # Example
+    ///
+    ///  ```
+    /// # use icon_utils::rpc::{transport::MockTransport, IconClient};
+    /// # use serde_json::json;
+    /// let transport = MockTransport::new();
+    /// transport.respond("debug_getTrace", json!({
+    ///     "logs": [
+    ///         { "level": 2, "msg": "START parent=root", "ts": 0 },
+    ///         { "level": 2, "msg": "CALL start from=hx1 to=cx1 value=0 steplimit=100000 dataType=call data={\"method\":\"transfer\"}", "ts": 12 },
+    ///         { "level": 2, "msg": "CALL done status=UserReverted(32) msg=Insufficient balance steps=12345", "ts": 890 }
+    ///     ],
+    ///     "status": "0x0"
+    /// }));
+    /// let client = IconClient::with_transport("http://localhost:9000/api/v3", transport);
+    /// let trace = client.get_trace(&format!("0x{}", "ab".repeat(32)).parse().unwrap()).unwrap();
+    /// assert!(trace.is_failure());
+    /// assert!(trace.logs.last().unwrap().msg.contains("Insufficient balance"));
+    /// assert_eq!(client.transport().requests()[0].url, "http://localhost:9000/api/v3d");
+    /// ```
+    pub fn get_trace(&self, hash: &Hash256) -> Result<ExecutionTrace, RpcError> {
+        let result = self.post(&debug_endpoint(&self.endpoint), "debug_getTrace", json!({ "txHash": hash }))?;
+        from_result(result)
+    }
+
+    /// Sends a raw JSON-RPC 2.0 request and returns the `result` field of the response, a null `params` is left out
+    pub fn request(&self, method: &str, params: Value) -> Result<Value, RpcError> {
+        self.post(&self.endpoint, method, params)
+    }
+
+    /// Sends a request to a blocking wait method, asking the node to wait up to `timeout`
+    fn request_with_wait(&self, method: &str, params: Value, timeout: Duration) -> Result<Value, RpcError> {
+        let options = wait_options(timeout);
+        self.post_with_headers(&self.endpoint, method, params, &[(ICON_OPTIONS_HEADER, &options)])
+    }
+
+    fn post(&self, url: &str, method: &str, params: Value) -> Result<Value, RpcError> {
+        self.post_with_headers(url, method, params, &[])
+    }
+
+    fn post_with_headers(&self, url: &str, method: &str, params: Value, headers: &[(&str, &str)]) -> Result<Value, RpcError> {
+        let body = build_request(self.next_id.fetch_add(1, Ordering::Relaxed), method, params);
+        #[cfg(feature = "tracing")]
+        let start = Instant::now();
+        let result = self.transport.send_with_headers(url, body, headers).and_then(parse_response);
+        #[cfg(feature = "tracing")]
+        trace_request(method, url, start, &result);
+        result
+    }
+}
+
+/// Records a finished request with its latency, failures other than pending transactions are warnings
+#[cfg(feature = "tracing")]
+pub(crate) fn trace_request(method: &str, endpoint: &str, start: Instant, result: &Result<Value, RpcError>) {
+    let latency_ms = start.elapsed().as_secs_f64() * 1000.0;
+    match result {
+        Ok(_) => tracing::debug!(method, endpoint, latency_ms, "rpc request"),
+        Err(error) if error.is_pending() => tracing::debug!(method, endpoint, latency_ms, %error, "rpc request"),
+        Err(error) => tracing::warn!(method, endpoint, latency_ms, %error, "rpc request failed"),
+    }
+}
+
+/// HTTP header carrying request options such as the wait timeout
+pub(crate) const ICON_OPTIONS_HEADER: &str = "Icon-Options";
+
+/// Builds the `Icon-Options` value asking a node to wait up to `timeout` before answering
+pub(crate) fn wait_options(timeout: Duration) -> String {
+    format!("timeout={}", timeout.as_millis())
+}
+
+/// Builds a JSON-RPC 2.0 request envelope, leaving out `params` when they are null
+pub(crate) fn build_request(id: u64, method: &str, params: Value) -> Value {
+    let mut request = json!({
+        "jsonrpc": "2.0",
+        "method": method,
+        "id": id,
+    });
+    if !params.is_null() {
+        request["params"] = params;
+    }
+    request
+}
+
+/// Returns the debug API endpoint next to a `/api/v3` endpoint
+pub(crate) fn debug_endpoint(endpoint: &str) -> String {
+    let endpoint = endpoint.trim_end_matches('/');
+    match endpoint.strip_suffix("/api/v3") {
+        Some(base) => format!("{}/api/v3d", base),
+        None => endpoint.to_owned(),
+    }
+}
+
+/// Builds the `debug_estimateStep` params of a transaction, which must not include a step limit
+pub(crate) fn estimate_params(tx: &IcxTransaction) -> Result<Value, RpcError> {
+    let mut params = serde_json::to_value(tx).map_err(|e| RpcError::InvalidRequest(e.to_string()))?;
+    if let Some(params) = params.as_object_mut() {
+        params.remove("stepLimit");
+    }
+    Ok(params)
+}
+
+/// Deserializes a result into a typed response
+pub(crate) fn from_result<T: DeserializeOwned>(result: Value) -> Result<T, RpcError> {
+    serde_json::from_value(result).map_err(|e| RpcError::InvalidResponse(e.to_string()))
+}
+
+/// Decodes a SCORE call result into a typed response
+pub(crate) fn decode_result<T: DeserializeOwned>(result: Value) -> Result<T, RpcError> {
+    crate::params::from_value(result).map_err(|e| RpcError::InvalidResponse(e.to_string()))
+}
+
+/// Reads a tx hash from a result
+#[cfg(feature = "wallet")]
+pub(crate) fn parse_hash(result: Value) -> Result<Hash256, RpcError> {
+    result
+        .as_str()
+        .and_then(|hash| hash.parse().ok())
+        .ok_or_else(|| RpcError::InvalidResponse(format!("expected tx hash, got {}", result)))
+}
+
+/// Extracts the result from a JSON-RPC 2.0 response, or the error object if there is one
+pub(crate) fn parse_response(mut response: Value) -> Result<Value, RpcError> {
+    if let Some(error) = response.get("error") {
+        return Err(RpcError::Node {
+            code: error.get("code").and_then(Value::as_i64).unwrap_or_default(),
+            message: error.get("message").and_then(Value::as_str).unwrap_or_default().to_owned(),
+            data: error.get("data").cloned(),
+        });
+    }
+    match response.get_mut("result") {
+        Some(result) => Ok(result.take()),
+        None => Err(RpcError::InvalidResponse(format!("missing result in {}", response))),
+    }
+}
@@ -0,0 +1,437 @@
+//! # Async Icon RPC
+//! `async_client` mirrors the blocking [`IconClient`](super::IconClient) on top of reqwest, for use inside async runtimes.
+#[cfg(feature = "wallet")]
+use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+
+use super::{
+    build_request, debug_endpoint, decode_result, estimate_params, from_result, parse_response, wait_options, Block,
+    CallBuilder, ConfirmedTransaction, ExecutionTrace, RpcError, ScoreStatus, TransactionResult, FALLBACK_POLL_INTERVAL, ICON_OPTIONS_HEADER,
+};
+#[cfg(feature = "wallet")]
+use super::{parse_hash, TransferError, TransferOptions};
+use super::btp::{self, BtpNetworkInfo};
+use super::middleware::Failover;
+use super::transport::{AsyncTransport, ReqwestTransport};
+use crate::abi::ScoreApi;
+use crate::network::Network;
+use crate::params::IntoParam;
+use crate::transaction::IcxTransaction;
+#[cfg(feature = "wallet")]
+use crate::signer::AsyncSigner;
+use crate::types::{Address, Hash256};
+use crate::units::Loop;
+#[cfg(feature = "wallet")]
+use crate::units::Icx;
+#[cfg(feature = "wallet")]
+use crate::wallet::SignedTransaction;
+
+/// An async client for the ICON JSON-RPC v3 API
+///
+/// Requests are sent with reqwest by default, any [`AsyncTransport`] can be used instead with `with_transport`.
+pub struct IconClient<T = ReqwestTransport> {
+    endpoint: String,
+    transport: T,
+    next_id: AtomicU64,
+    /// Cleared once the node turns out not to support the blocking wait methods
+    blocking_wait: AtomicBool,
+}
+
+impl IconClient {
+    /// Creates a client for the given endpoint URL
+    ///
+    /// # Example
+    ///
+    ///  ```
+    /// # use icon_utils::rpc::async_client::IconClient;
+    /// let client = IconClient::new("https://api.icon.community/api/v3");
+    /// ```
+    pub fn new(endpoint: &str) -> Self {
+        IconClient::with_http_client(endpoint, reqwest::Client::new())
+    }
+
+    /// Creates a client for the default endpoint of a network
+    pub fn for_network(network: &Network) -> Self {
+        IconClient::new(network.endpoint())
+    }
+
+    /// Creates a client that sends requests with an existing reqwest client
+    pub fn with_http_client(endpoint: &str, http: reqwest::Client) -> Self {
+        IconClient::with_transport(endpoint, ReqwestTransport::new(http))
+    }
+}
+
+impl IconClient<Failover<ReqwestTransport>> {
+    /// Creates a client for `primary`, failing over to `fallbacks` in order while it's down
+    ///
+    /// See [`Failover`] for when an endpoint counts as down.
+    pub fn with_endpoints(primary: &str, fallbacks: &[&str]) -> Self {
+        let endpoints: Vec<&str> = std::iter::once(primary).chain(fallbacks.iter().copied()).collect();
+        IconClient::with_transport(primary, Failover::new(ReqwestTransport::new(reqwest::Client::new()), &endpoints))
+    }
+}
+
+impl<T: AsyncTransport> IconClient<T> {
+    /// Creates a client that sends requests through `transport`
+    pub fn with_transport(endpoint: &str, transport: T) -> Self {
+        IconClient { endpoint: endpoint.to_owned(), transport, next_id: AtomicU64::new(1), blocking_wait: AtomicBool::new(true) }
+    }
+
+    /// Returns the transport the client sends requests through
+    pub fn transport(&self) -> &T {
+        &self.transport
+    }
+
+    /// Returns the endpoint URL the client sends requests to
+    pub fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    /// Sends a signed transaction with `icx_sendTransaction` and returns its tx hash
+    ///
+    /// # Example
+    ///
+    ///  ```no_run
+    /// # use icon_utils::{rpc::async_client::IconClient, wallet::SignedTransaction};
+    /// # async fn send(signed: SignedTransaction) {
+    /// let client = IconClient::new("https://api.icon.community/api/v3");
+    /// let hash = client.send_transaction(&signed).await.unwrap();
+    /// # }
+    /// ```
+    #[cfg(feature = "wallet")]
+    pub async fn send_transaction(&self, tx: &SignedTransaction) -> Result<Hash256, RpcError> {
+        let hash = parse_hash(self.request(&tx.method, tx.to_rpc_params()).await?)?;
+        #[cfg(feature = "tracing")]
+        tracing::info!(tx_hash = %hash, endpoint = %self.endpoint, "sent transaction");
+        Ok(hash)
+    }
+
+    /// Sends `amount` ICX from `wallet` to `to`, returning the tx hash once the node accepts the transaction
+    ///
+    /// Any [`AsyncSigner`] can sign the transfer, such as a wallet or a [`RemoteSigner`](crate::signer::remote::RemoteSigner).
+    /// See [`IconClient::transfer`](super::IconClient::transfer).
+    #[cfg(feature = "wallet")]
+    pub async fn transfer(
+        &self,
+        wallet: &(impl AsyncSigner + Sync),
+        to: impl fmt::Display,
+        amount: Icx,
+        options: TransferOptions,
+    ) -> Result<Hash256, TransferError> {
+        let mut tx = options.build(&self.endpoint, wallet.address(), to, amount)?;
+        if options.needs_estimate() {
+            tx.step_limit = options.with_margin(self.estimate_step(&tx).await?);
+        }
+        let signed = wallet.sign_transaction(&tx).await?;
+        Ok(self.send_transaction(&signed).await?)
+    }
+
+    /// Gets the receipt of a transaction with `icx_getTransactionResult`
+    pub async fn get_transaction_result(&self, hash: &Hash256) -> Result<TransactionResult, RpcError> {
+        let result = self.request("icx_getTransactionResult", json!({ "txHash": hash })).await?;
+        from_result(result)
+    }
+
+    /// Gets a confirmed transaction with `icx_getTransactionByHash`, decoding its data
+    pub async fn get_transaction_by_hash(&self, hash: &Hash256) -> Result<ConfirmedTransaction, RpcError> {
+        let result = self.request("icx_getTransactionByHash", json!({ "txHash": hash })).await?;
+        from_result(result)
+    }
+
+    /// Polls `icx_getTransactionResult` every `interval` until the receipt is available, or `timeout` has passed
+    pub async fn wait_for_result(&self, hash: &Hash256, timeout: Duration, interval: Duration) -> Result<TransactionResult, RpcError> {
+        let start = Instant::now();
+        loop {
+            match self.get_transaction_result(hash).await {
+                Err(e) if e.is_pending() => {}
+                result => return result,
+            }
+            if start.elapsed() + interval > timeout {
+                return Err(RpcError::Timeout(timeout));
+            }
+            tokio::time::sleep(interval).await;
+        }
+    }
+
+    /// Sends a signed transaction with `icx_sendTransactionAndWait`, returning its receipt once it's finalized
+    ///
+    /// Falls back to `icx_sendTransaction` and polling on nodes without the blocking method,
+    /// see [`IconClient::send_transaction_and_wait`](super::IconClient::send_transaction_and_wait).
+    #[cfg(feature = "wallet")]
+    pub async fn send_transaction_and_wait(&self, tx: &SignedTransaction, timeout: Duration) -> Result<TransactionResult, RpcError> {
+        let start = Instant::now();
+        if tx.method == "icx_sendTransaction" && self.blocking_wait.load(Ordering::Relaxed) {
+            match self.request_with_wait("icx_sendTransactionAndWait", tx.to_rpc_params(), timeout).await {
+                Err(e) if e.is_method_not_found() => self.blocking_wait.store(false, Ordering::Relaxed),
+                Err(e) if e.is_wait_incomplete() => return self.poll_remaining(&tx.tx_hash(), start, timeout).await,
+                result => return result.and_then(from_result),
+            }
+        }
+        let hash = self.send_transaction(tx).await?;
+        self.poll_remaining(&hash, start, timeout).await
+    }
+
+    /// Waits for the receipt of a transaction with `icx_waitTransactionResult`, letting the node wait up to `timeout`
+    ///
+    /// Falls back to polling on nodes without the blocking method,
+    /// see [`IconClient::wait_transaction_result`](super::IconClient::wait_transaction_result).
+    pub async fn wait_transaction_result(&self, hash: &Hash256, timeout: Duration) -> Result<TransactionResult, RpcError> {
+        let start = Instant::now();
+        if self.blocking_wait.load(Ordering::Relaxed) {
+            match self.request_with_wait("icx_waitTransactionResult", json!({ "txHash": hash }), timeout).await {
+                Err(e) if e.is_method_not_found() => self.blocking_wait.store(false, Ordering::Relaxed),
+                Err(e) if e.is_wait_incomplete() => return self.poll_remaining(hash, start, timeout).await,
+                result => return result.and_then(from_result),
+            }
+        }
+        self.poll_remaining(hash, start, timeout).await
+    }
+
+    /// Polls for a receipt until `timeout` has passed since `start`
+    async fn poll_remaining(&self, hash: &Hash256, start: Instant, timeout: Duration) -> Result<TransactionResult, RpcError> {
+        match self.wait_for_result(hash, timeout.saturating_sub(start.elapsed()), FALLBACK_POLL_INTERVAL).await {
+            Err(RpcError::Timeout(_)) => Err(RpcError::Timeout(timeout)),
+            result => result,
+        }
+    }
+
+    /// Calls a read-only SCORE method with `icx_call`, decoding the result into `T`
+    pub async fn call<R: DeserializeOwned>(&self, call: &CallBuilder) -> Result<R, RpcError> {
+        let result = self.request("icx_call", call.to_params()).await?;
+        decode_result(result)
+    }
+
+    /// Gets the latest block with `icx_getLastBlock`
+    pub async fn get_last_block(&self) -> Result<Block, RpcError> {
+        let result = self.request("icx_getLastBlock", Value::Null).await?;
+        from_result(result)
+    }
+
+    /// Gets the block at `height` with `icx_getBlockByHeight`
+    pub async fn get_block_by_height(&self, height: u64) -> Result<Block, RpcError> {
+        let result = self.request("icx_getBlockByHeight", json!({ "height": height.into_param() })).await?;
+        from_result(result)
+    }
+
+    /// Gets a block by its hash with `icx_getBlockByHash`
+    pub async fn get_block_by_hash(&self, hash: &Hash256) -> Result<Block, RpcError> {
+        let result = self.request("icx_getBlockByHash", json!({ "hash": hash })).await?;
+        from_result(result)
+    }
+
+    /// Gets the API of the SCORE at `address` with `icx_getScoreApi`
+    pub async fn get_score_api(&self, address: &Address) -> Result<ScoreApi, RpcError> {
+        let result = self.request("icx_getScoreApi", json!({ "address": address })).await?;
+        from_result(result)
+    }
+
+    /// Gets the deployment and audit state of the SCORE at `address` with `icx_getScoreStatus`
+    pub async fn get_score_status(&self, address: &Address) -> Result<ScoreStatus, RpcError> {
+        let result = self.request("icx_getScoreStatus", json!({ "address": address })).await?;
+        from_result(result)
+    }
+
+    /// Gets the ICX balance of an address with `icx_getBalance`
+    pub async fn get_balance(&self, address: &Address) -> Result<Loop, RpcError> {
+        let result = self.request("icx_getBalance", json!({ "address": address })).await?;
+        from_result(result)
+    }
+
+    /// Gets the total supply of ICX with `icx_getTotalSupply`
+    pub async fn get_total_supply(&self) -> Result<Loop, RpcError> {
+        let result = self.request("icx_getTotalSupply", Value::Null).await?;
+        from_result(result)
+    }
+
+    /// Gets a BTP network with `btp_getNetworkInfo`, at `height` or at the latest block when None
+    pub async fn get_btp_network_info(&self, network_id: u64, height: Option<u64>) -> Result<BtpNetworkInfo, RpcError> {
+        let result = self.request("btp_getNetworkInfo", btp::network_info_params(network_id, height)).await?;
+        from_result(result)
+    }
+
+    /// Gets the messages sent to a BTP network in the block at `height` with `btp_getMessages`
+    pub async fn get_btp_messages(&self, network_id: u64, height: u64) -> Result<Vec<Vec<u8>>, RpcError> {
+        let result = self.request("btp_getMessages", btp::block_params(network_id, height)).await?;
+        btp::decode_messages(result)
+    }
+
+    /// Gets the encoded BTP block header of a network at `height` with `btp_getHeader`
+    pub async fn get_btp_header(&self, network_id: u64, height: u64) -> Result<Vec<u8>, RpcError> {
+        let result = self.request("btp_getHeader", btp::block_params(network_id, height)).await?;
+        btp::decode_bytes(result)
+    }
+
+    /// Gets the encoded proof of a network's BTP block at `height` with `btp_getProof`
+    pub async fn get_btp_proof(&self, network_id: u64, height: u64) -> Result<Vec<u8>, RpcError> {
+        let result = self.request("btp_getProof", btp::block_params(network_id, height)).await?;
+        btp::decode_bytes(result)
+    }
+
+    /// Estimates the steps an unsigned transaction would use with `debug_estimateStep`, sent to the node's `/api/v3d` debug API
+    pub async fn estimate_step(&self, tx: &IcxTransaction) -> Result<u64, RpcError> {
+        let result = self.post(&debug_endpoint(&self.endpoint), "debug_estimateStep", estimate_params(tx)?).await?;
+        decode_result(result)
+    }
+
+    /// Gets the execution trace of a transaction with `debug_getTrace`, sent to the node's `/api/v3d` debug API
+    pub async fn get_trace(&self, hash: &Hash256) -> Result<ExecutionTrace, RpcError> {
+        let result = self.post(&debug_endpoint(&self.endpoint), "debug_getTrace", json!({ "txHash": hash })).await?;
+        from_result(result)
+    }
+
+    /// Subscribes to new blocks from `height` over the node's block websocket
+    ///
+    /// # Example
+    ///
+    ///  ```no_run
+    /// # use futures_util::StreamExt;
+    /// # use icon_utils::rpc::async_client::IconClient;
+    /// # async fn follow() {
+    /// let client = IconClient::new("https://api.icon.community/api/v3");
+    /// let blocks = client.subscribe_blocks(70_000_000).await.unwrap();
+    /// futures_util::pin_mut!(blocks);
+    /// while let Some(block) = blocks.next().await {
+    ///     println!("block {}", block.unwrap().height);
+    /// }
+    /// # }
+    /// ```
+    #[cfg(feature = "websocket")]
+    pub async fn subscribe_blocks(
+        &self,
+        height: u64,
+    ) -> Result<impl futures_util::Stream<Item = Result<super::ws::BlockNotification, RpcError>>, RpcError> {
+        let url = super::ws::websocket_url(&self.endpoint, "block");
+        super::ws::subscribe(&url, json!({ "height": height.into_param() })).await
+    }
+
+    /// Subscribes to events matching `filter` from `height` over the node's event websocket
+    #[cfg(feature = "websocket")]
+    pub async fn subscribe_events(
+        &self,
+        height: u64,
+        filter: &super::ws::EventFilter,
+    ) -> Result<impl futures_util::Stream<Item = Result<super::ws::EventNotification, RpcError>>, RpcError> {
+        let url = super::ws::websocket_url(&self.endpoint, "event");
+        let mut request = serde_json::to_value(filter).map_err(|e| RpcError::InvalidRequest(e.to_string()))?;
+        request["height"] = height.into_param();
+        super::ws::subscribe(&url, request).await
+    }
+
+    /// Subscribes to events matching `filter` from `height` and sends each one, decoded, to `sender`
+    ///
+    /// The node only reports which transactions emitted matching events, so their receipts are fetched and the
+    /// filter is applied again to their logs. Runs until the subscription ends, an error is sent, or the receiver
+    /// is dropped.
+    ///
+    /// # Example
+    ///
+    ///  ```no_run
+    /// # use icon_utils::{events::EventFilter, rpc::async_client::IconClient};
+    /// # async fn follow() {
+    /// let client = IconClient::new("https://api.icon.community/api/v3");
+    /// let filter = EventFilter::new("Transfer(Address,Address,int,bytes)")
+    ///     .address("cx88fd7df7ddff82f7cc735c871dc519838cb235bb".parse().unwrap());
+    /// let (sender, mut receiver) = tokio::sync::mpsc::channel(64);
+    /// let forward = client.forward_events(70_000_000, &filter, sender);
+    /// let print = async {
+    ///     while let Some(event) = receiver.recv().await {
+    ///         let event = event.unwrap();
+    ///         println!("{} {:?}", event.tx_hash, event.event.values);
+    ///     }
+    /// };
+    /// let (forwarded, _) = futures_util::future::join(forward, print).await;
+    /// forwarded.unwrap();
+    /// # }
+    /// ```
+    #[cfg(feature = "websocket")]
+    pub async fn forward_events(
+        &self,
+        height: u64,
+        filter: &super::ws::EventFilter,
+        sender: tokio::sync::mpsc::Sender<Result<super::ws::MatchedEvent, RpcError>>,
+    ) -> Result<(), RpcError> {
+        use futures_util::StreamExt;
+        let notifications = self.subscribe_events(height, filter).await?;
+        futures_util::pin_mut!(notifications);
+        while let Some(notification) = notifications.next().await {
+            let matched = match notification {
+                Ok(notification) => self.matched_events(&notification, filter).await,
+                Err(e) => Err(e),
+            };
+            match matched {
+                Ok(events) => {
+                    for event in events {
+                        if sender.send(Ok(event)).await.is_err() {
+                            return Ok(());
+                        }
+                    }
+                }
+                Err(e) => {
+                    // The receiver may already be gone, there's nothing more to do either way
+                    let _ = sender.send(Err(e)).await;
+                    break;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Fetches the receipt of the transaction in `notification` and decodes its logs matching `filter`
+    #[cfg(feature = "websocket")]
+    async fn matched_events(
+        &self,
+        notification: &super::ws::EventNotification,
+        filter: &super::ws::EventFilter,
+    ) -> Result<Vec<super::ws::MatchedEvent>, RpcError> {
+        let block = self.get_block_by_height(notification.height).await?;
+        let tx = block.transactions.get(notification.index as usize).cloned().ok_or_else(|| {
+            RpcError::InvalidResponse(format!("block {} has no transaction {}", notification.height, notification.index))
+        })?;
+        let tx: ConfirmedTransaction = from_result(tx)?;
+        let result = self.get_transaction_result(&tx.tx_hash).await?;
+        let mut events = Vec::new();
+        for (index, log) in result.event_logs.iter().enumerate() {
+            if !filter.matches(log) {
+                continue;
+            }
+            let event = crate::events::DecodedEvent::decode(log).map_err(|e| RpcError::InvalidResponse(e.to_string()))?;
+            events.push(super::ws::MatchedEvent {
+                height: notification.height,
+                block_hash: notification.hash,
+                tx_hash: tx.tx_hash,
+                index,
+                event,
+            });
+        }
+        Ok(events)
+    }
+
+    /// Sends a raw JSON-RPC 2.0 request and returns the `result` field of the response, a null `params` is left out
+    pub async fn request(&self, method: &str, params: Value) -> Result<Value, RpcError> {
+        self.post(&self.endpoint, method, params).await
+    }
+
+    /// Sends a request to a blocking wait method, asking the node to wait up to `timeout`
+    async fn request_with_wait(&self, method: &str, params: Value, timeout: Duration) -> Result<Value, RpcError> {
+        let options = wait_options(timeout);
+        self.post_with_headers(&self.endpoint, method, params, &[(ICON_OPTIONS_HEADER, &options)]).await
+    }
+
+    async fn post(&self, url: &str, method: &str, params: Value) -> Result<Value, RpcError> {
+        self.post_with_headers(url, method, params, &[]).await
+    }
+
+    async fn post_with_headers(&self, url: &str, method: &str, params: Value, headers: &[(&str, &str)]) -> Result<Value, RpcError> {
+        let body = build_request(self.next_id.fetch_add(1, Ordering::Relaxed), method, params);
+        #[cfg(feature = "tracing")]
+        let start = Instant::now();
+        let result = self.transport.send_with_headers(url, body, headers).await.and_then(parse_response);
+        #[cfg(feature = "tracing")]
+        super::trace_request(method, url, start, &result);
+        result
+    }
+}
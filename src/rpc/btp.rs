@@ -0,0 +1,77 @@
+//! # Icon BTP
+//! `btp` holds the typed responses of the BTP 2.0 `btp_*` methods, which relayers use to follow messages sent from ICON.
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use super::types::hex_u64;
+use super::RpcError;
+use crate::params::IntoParam;
+use crate::types::{Address, HexBytes};
+
+/// A BTP network, returned by `btp_getNetworkInfo`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BtpNetworkInfo {
+    /// Height of the block the network was opened in
+    #[serde(deserialize_with = "hex_u64")]
+    pub start_height: u64,
+    #[serde(rename = "networkTypeID", deserialize_with = "hex_u64")]
+    pub network_type_id: u64,
+    pub network_type_name: String,
+    #[serde(rename = "networkID", deserialize_with = "hex_u64")]
+    pub network_id: u64,
+    pub network_name: String,
+    /// 1 while the network is open, 0 once it's closed
+    #[serde(deserialize_with = "hex_u64")]
+    pub open: u64,
+    pub owner: Address,
+    /// Sequence number the next message sent to the network gets
+    #[serde(rename = "nextMessageSN", deserialize_with = "hex_u64")]
+    pub next_message_sn: u64,
+    /// 1 if the proof context changes in the next block
+    #[serde(deserialize_with = "hex_u64")]
+    pub next_proof_context_changed: u64,
+    #[serde(rename = "prevNSHash")]
+    pub prev_ns_hash: HexBytes,
+    #[serde(rename = "lastNSHash")]
+    pub last_ns_hash: HexBytes,
+}
+
+impl BtpNetworkInfo {
+    /// Returns true if the network is still open
+    pub fn is_open(&self) -> bool {
+        self.open == 1
+    }
+}
+
+/// Builds the params of a `btp_*` request for the network `network_id` at `height`
+pub(crate) fn block_params(network_id: u64, height: u64) -> Value {
+    json!({ "networkID": network_id.into_param(), "height": height.into_param() })
+}
+
+/// Builds the params of `btp_getNetworkInfo`, leaving out the height to read the latest state
+pub(crate) fn network_info_params(network_id: u64, height: Option<u64>) -> Value {
+    let mut params = json!({ "id": network_id.into_param() });
+    if let Some(height) = height {
+        params["height"] = height.into_param();
+    }
+    params
+}
+
+/// Decodes a base64 result, such as a BTP block header or proof
+pub(crate) fn decode_bytes(result: Value) -> Result<Vec<u8>, RpcError> {
+    let encoded = result
+        .as_str()
+        .ok_or_else(|| RpcError::InvalidResponse(format!("expected base64 string, got {}", result)))?;
+    STANDARD.decode(encoded).map_err(|e| RpcError::InvalidResponse(e.to_string()))
+}
+
+/// Decodes the base64 messages returned by `btp_getMessages`, where null means there are none
+pub(crate) fn decode_messages(result: Value) -> Result<Vec<Vec<u8>>, RpcError> {
+    match result {
+        Value::Null => Ok(Vec::new()),
+        Value::Array(messages) => messages.into_iter().map(decode_bytes).collect(),
+        other => Err(RpcError::InvalidResponse(format!("expected list of messages, got {}", other))),
+    }
+}
@@ -0,0 +1,58 @@
+//! # Icon Call
+//! `call` builds the params of `icx_call`, used for read-only SCORE queries.
+use serde_json::{json, Map, Value};
+
+use crate::params::IntoParam;
+
+/// Builder for a read-only SCORE call
+///
+/// # Example
+///
+///  ```
+/// # use icon_utils::rpc::CallBuilder;
+/// let call = CallBuilder::new("cx88fd7df7ddff82f7cc735c871dc519838cb235bb", "balanceOf")
+///     .param("_owner", "hxbe258ceb872e08851f1f59694dac2558708ece11");
+/// ```
+#[derive(Debug, Clone)]
+pub struct CallBuilder {
+    from: Option<String>,
+    to: String,
+    method: String,
+    params: Map<String, Value>,
+}
+
+impl CallBuilder {
+    /// Creates a call to `method` on the SCORE at `to`
+    pub fn new(to: &str, method: &str) -> Self {
+        CallBuilder { from: None, to: to.to_owned(), method: method.to_owned(), params: Map::new() }
+    }
+
+    /// Sets the address the call is made from
+    pub fn from(mut self, from: &str) -> Self {
+        self.from = Some(from.to_owned());
+        self
+    }
+
+    /// Adds a param to the call, encoded the way ICON expects
+    pub fn param(mut self, name: &str, value: impl IntoParam) -> Self {
+        self.params.insert(name.to_owned(), value.into_param());
+        self
+    }
+
+    /// Returns the params of the `icx_call` request
+    pub fn to_params(&self) -> Value {
+        let mut data = json!({ "method": self.method });
+        if !self.params.is_empty() {
+            data["params"] = Value::Object(self.params.clone());
+        }
+        let mut params = json!({
+            "to": self.to,
+            "dataType": "call",
+            "data": data,
+        });
+        if let Some(from) = &self.from {
+            params["from"] = Value::String(from.clone());
+        }
+        params
+    }
+}
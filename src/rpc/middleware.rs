@@ -0,0 +1,367 @@
+//! # Icon RPC Middleware
+//! `middleware` holds transports that wrap another [`Transport`], adding retries with exponential backoff, circuit breaking
+//! and failover between endpoints.
+//! Layers are combined by nesting them, timeouts are set on the innermost HTTP transport.
+//!
+//! # Example
+//!
+//!  ```
+//! # use std::time::Duration;
+//! # use icon_utils::rpc::{IconClient, HttpTransport, middleware::{CircuitBreaker, Retry}};
+//! let transport = CircuitBreaker::new(Retry::new(HttpTransport::with_timeout(Duration::from_secs(10))).max_retries(5));
+//! let client = IconClient::with_transport("https://api.icon.community/api/v3", transport);
+//! ```
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde_json::Value;
+
+use super::transport::Transport;
+#[cfg(feature = "async")]
+use super::transport::AsyncTransport;
+use super::RpcError;
+
+/// Retries transient failures of the inner transport, doubling the delay between attempts
+#[derive(Debug, Clone)]
+pub struct Retry<T> {
+    inner: T,
+    max_retries: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+}
+
+impl<T> Retry<T> {
+    /// Wraps `inner`, retrying up to 3 times starting with a 200ms delay
+    pub fn new(inner: T) -> Self {
+        Retry { inner, max_retries: 3, initial_backoff: Duration::from_millis(200), max_backoff: Duration::from_secs(10) }
+    }
+
+    /// Sets the number of retries after the first attempt
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Sets the delay before the first retry
+    pub fn initial_backoff(mut self, backoff: Duration) -> Self {
+        self.initial_backoff = backoff;
+        self
+    }
+
+    /// Sets the longest delay between retries
+    pub fn max_backoff(mut self, backoff: Duration) -> Self {
+        self.max_backoff = backoff;
+        self
+    }
+
+    /// Returns the delay before retry number `retry`, starting at 0
+    fn backoff(&self, retry: u32) -> Duration {
+        self.initial_backoff.saturating_mul(2u32.saturating_pow(retry)).min(self.max_backoff)
+    }
+}
+
+impl<T: Transport> Transport for Retry<T> {
+    fn send(&self, url: &str, request: Value) -> Result<Value, RpcError> {
+        self.send_with_headers(url, request, &[])
+    }
+
+    fn send_with_headers(&self, url: &str, request: Value, headers: &[(&str, &str)]) -> Result<Value, RpcError> {
+        let mut retry = 0;
+        loop {
+            match self.inner.send_with_headers(url, request.clone(), headers) {
+                Err(e) if e.is_transient() && retry < self.max_retries => {
+                    std::thread::sleep(self.backoff(retry));
+                    retry += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T: AsyncTransport + Sync> AsyncTransport for Retry<T> {
+    async fn send(&self, url: &str, request: Value) -> Result<Value, RpcError> {
+        self.send_with_headers(url, request, &[]).await
+    }
+
+    async fn send_with_headers(&self, url: &str, request: Value, headers: &[(&str, &str)]) -> Result<Value, RpcError> {
+        let mut retry = 0;
+        loop {
+            match self.inner.send_with_headers(url, request.clone(), headers).await {
+                Err(e) if e.is_transient() && retry < self.max_retries => {
+                    tokio::time::sleep(self.backoff(retry)).await;
+                    retry += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+/// Stops sending requests for a cooldown period after repeated transient failures, failing fast instead
+#[derive(Debug)]
+pub struct CircuitBreaker<T> {
+    inner: T,
+    failure_threshold: u32,
+    cooldown: Duration,
+    state: Mutex<BreakerState>,
+}
+
+#[derive(Debug, Default)]
+struct BreakerState {
+    failures: u32,
+    opened_at: Option<Instant>,
+}
+
+impl<T> CircuitBreaker<T> {
+    /// Wraps `inner`, opening after 5 consecutive failures for 30 seconds
+    pub fn new(inner: T) -> Self {
+        CircuitBreaker { inner, failure_threshold: 5, cooldown: Duration::from_secs(30), state: Mutex::default() }
+    }
+
+    /// Sets the number of consecutive transient failures that open the circuit
+    pub fn failure_threshold(mut self, failures: u32) -> Self {
+        self.failure_threshold = failures.max(1);
+        self
+    }
+
+    /// Sets how long the circuit stays open before requests are tried again
+    pub fn cooldown(mut self, cooldown: Duration) -> Self {
+        self.cooldown = cooldown;
+        self
+    }
+
+    /// Returns true if requests are currently being rejected
+    pub fn is_open(&self) -> bool {
+        self.check().is_err()
+    }
+
+    /// Fails with the remaining cooldown while the circuit is open
+    fn check(&self) -> Result<(), RpcError> {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        match state.opened_at {
+            Some(opened_at) if opened_at.elapsed() < self.cooldown => {
+                Err(RpcError::CircuitOpen(self.cooldown - opened_at.elapsed()))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Updates the failure count with the result of a request
+    fn record(&self, result: &Result<Value, RpcError>) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        match result {
+            Err(e) if e.is_transient() => {
+                state.failures += 1;
+                if state.failures >= self.failure_threshold {
+                    state.opened_at = Some(Instant::now());
+                }
+            }
+            _ => *state = BreakerState::default(),
+        }
+    }
+}
+
+impl<T: Transport> Transport for CircuitBreaker<T> {
+    fn send(&self, url: &str, request: Value) -> Result<Value, RpcError> {
+        self.send_with_headers(url, request, &[])
+    }
+
+    fn send_with_headers(&self, url: &str, request: Value, headers: &[(&str, &str)]) -> Result<Value, RpcError> {
+        self.check()?;
+        let result = self.inner.send_with_headers(url, request, headers);
+        self.record(&result);
+        result
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T: AsyncTransport + Sync> AsyncTransport for CircuitBreaker<T> {
+    async fn send(&self, url: &str, request: Value) -> Result<Value, RpcError> {
+        self.send_with_headers(url, request, &[]).await
+    }
+
+    async fn send_with_headers(&self, url: &str, request: Value, headers: &[(&str, &str)]) -> Result<Value, RpcError> {
+        self.check()?;
+        let result = self.inner.send_with_headers(url, request, headers).await;
+        self.record(&result);
+        result
+    }
+}
+
+/// Sends requests to the first healthy endpoint of a list, failing over to the next one when an endpoint fails
+///
+/// Endpoints are tried in the order they're given. An endpoint that fails to respond, times out, returns an HTTP
+/// error status or an invalid body is skipped for the cooldown, then checked with `icx_getLastBlock` before it's used
+/// again. JSON-RPC errors are answers from a working node, so they're returned without failing over. When every
+/// endpoint is down they're all tried anyway, and the last error is returned.
+///
+/// The transport rewrites the URL the client posts to, so the client's endpoint should be one of the list, as it is
+/// with [`IconClient::with_endpoints`](super::IconClient::with_endpoints). Requests to the debug API move with it.
+///
+/// # Example
+///
+///  ```
+/// # use icon_utils::rpc::{middleware::Failover, transport::Transport, IconClient, RpcError};
+/// # use serde_json::{json, Value};
+/// struct FlakyTransport;
+/// impl Transport for FlakyTransport {
+///     fn send(&self, url: &str, request: Value) -> Result<Value, RpcError> {
+///         match url {
+///             "http://primary/api/v3" => Err(RpcError::Transport("connection refused".to_owned())),
+///             _ => Ok(json!({ "jsonrpc": "2.0", "id": request["id"], "result": "0x1" })),
+///         }
+///     }
+/// }
+/// let endpoints = ["http://primary/api/v3", "http://fallback/api/v3"];
+/// let client = IconClient::with_transport(endpoints[0], Failover::new(FlakyTransport, &endpoints));
+/// assert_eq!(client.get_total_supply().unwrap().0, 1u8.into());
+/// assert_eq!(client.transport().active_endpoint(), "http://fallback/api/v3");
+/// ```
+#[derive(Debug)]
+pub struct Failover<T> {
+    inner: T,
+    endpoints: Vec<String>,
+    cooldown: Duration,
+    down_until: Mutex<Vec<Option<Instant>>>,
+}
+
+impl<T> Failover<T> {
+    /// Wraps `inner`, skipping failed endpoints for 30 seconds
+    pub fn new(inner: T, endpoints: &[&str]) -> Self {
+        Failover {
+            inner,
+            endpoints: endpoints.iter().map(|endpoint| endpoint.trim_end_matches('/').to_owned()).collect(),
+            cooldown: Duration::from_secs(30),
+            down_until: Mutex::new(vec![None; endpoints.len()]),
+        }
+    }
+
+    /// Sets how long a failed endpoint is skipped before it's checked again
+    pub fn cooldown(mut self, cooldown: Duration) -> Self {
+        self.cooldown = cooldown;
+        self
+    }
+
+    /// Returns the endpoints in the order they're tried
+    pub fn endpoints(&self) -> &[String] {
+        &self.endpoints
+    }
+
+    /// Returns the endpoint the next request is sent to, the first one that isn't down
+    pub fn active_endpoint(&self) -> &str {
+        let index = self.candidates().first().map_or(0, |&(index, _)| index);
+        self.endpoints.get(index).map_or("", String::as_str)
+    }
+
+    /// Returns the endpoints to try in order, and whether each one needs a health check first
+    fn candidates(&self) -> Vec<(usize, bool)> {
+        let down_until = self.down_until.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        let candidates: Vec<_> = down_until
+            .iter()
+            .enumerate()
+            .filter(|(_, until)| until.is_none_or(|until| until <= now))
+            .map(|(index, until)| (index, until.is_some()))
+            .collect();
+        if candidates.is_empty() {
+            return (0..self.endpoints.len()).map(|index| (index, false)).collect();
+        }
+        candidates
+    }
+
+    /// Marks an endpoint as up, or as down for the cooldown
+    fn record(&self, index: usize, up: bool) {
+        let mut down_until = self.down_until.lock().unwrap_or_else(|e| e.into_inner());
+        down_until[index] = if up { None } else { Some(Instant::now() + self.cooldown) };
+    }
+
+    /// Moves `url` from the endpoint it was meant for to endpoint `index`, None if it isn't for any of them
+    fn rewrite(&self, url: &str, index: usize) -> Option<String> {
+        let url = url.trim_end_matches('/');
+        let target = &self.endpoints[index];
+        self.endpoints.iter().find_map(|endpoint| {
+            if url == endpoint {
+                Some(target.clone())
+            } else if url == super::debug_endpoint(endpoint) {
+                Some(super::debug_endpoint(target))
+            } else {
+                None
+            }
+        })
+    }
+}
+
+/// Returns true for errors that mean the endpoint, rather than the request, is at fault
+fn is_endpoint_failure(error: &RpcError) -> bool {
+    matches!(error, RpcError::Transport(_) | RpcError::Http { .. } | RpcError::InvalidResponse(_))
+}
+
+/// The request a recovering endpoint has to answer before it's used again
+fn health_check_request() -> Value {
+    serde_json::json!({ "jsonrpc": "2.0", "id": 0, "method": "icx_getLastBlock" })
+}
+
+impl<T: Transport> Transport for Failover<T> {
+    fn send(&self, url: &str, request: Value) -> Result<Value, RpcError> {
+        self.send_with_headers(url, request, &[])
+    }
+
+    fn send_with_headers(&self, url: &str, request: Value, headers: &[(&str, &str)]) -> Result<Value, RpcError> {
+        if self.rewrite(url, 0).is_none() {
+            return self.inner.send_with_headers(url, request, headers);
+        }
+        let mut last_error = None;
+        for (index, check) in self.candidates() {
+            if check && self.inner.send(&self.endpoints[index], health_check_request()).is_err() {
+                self.record(index, false);
+                continue;
+            }
+            let target = self.rewrite(url, index).unwrap_or_else(|| url.to_owned());
+            match self.inner.send_with_headers(&target, request.clone(), headers) {
+                Err(e) if is_endpoint_failure(&e) => {
+                    self.record(index, false);
+                    last_error = Some(e);
+                }
+                result => {
+                    self.record(index, true);
+                    return result;
+                }
+            }
+        }
+        Err(last_error.unwrap_or_else(|| RpcError::Transport("every endpoint failed its health check".to_owned())))
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T: AsyncTransport + Sync> AsyncTransport for Failover<T> {
+    async fn send(&self, url: &str, request: Value) -> Result<Value, RpcError> {
+        self.send_with_headers(url, request, &[]).await
+    }
+
+    async fn send_with_headers(&self, url: &str, request: Value, headers: &[(&str, &str)]) -> Result<Value, RpcError> {
+        if self.rewrite(url, 0).is_none() {
+            return self.inner.send_with_headers(url, request, headers).await;
+        }
+        let mut last_error = None;
+        for (index, check) in self.candidates() {
+            if check && self.inner.send(&self.endpoints[index], health_check_request()).await.is_err() {
+                self.record(index, false);
+                continue;
+            }
+            let target = self.rewrite(url, index).unwrap_or_else(|| url.to_owned());
+            match self.inner.send_with_headers(&target, request.clone(), headers).await {
+                Err(e) if is_endpoint_failure(&e) => {
+                    self.record(index, false);
+                    last_error = Some(e);
+                }
+                result => {
+                    self.record(index, true);
+                    return result;
+                }
+            }
+        }
+        Err(last_error.unwrap_or_else(|| RpcError::Transport("every endpoint failed its health check".to_owned())))
+    }
+}
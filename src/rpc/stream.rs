@@ -0,0 +1,199 @@
+//! # Icon Block Stream
+//! `stream` follows the chain block by block from a starting height, as a base for indexers.
+use std::time::Duration;
+
+use super::transport::{HttpTransport, Transport};
+use super::{Block, IconClient, RpcError};
+use crate::types::Hash256;
+
+/// How long to wait before asking the node for new blocks again, about one ICON block
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Tracks the next height to read and checks that each block follows the one before
+#[derive(Debug, Clone)]
+struct Cursor {
+    next_height: u64,
+    last_hash: Option<Hash256>,
+    tip: u64,
+    confirmations: u64,
+}
+
+impl Cursor {
+    fn new(start: u64) -> Self {
+        Cursor { next_height: start, last_hash: None, tip: 0, confirmations: 0 }
+    }
+
+    /// Returns true if the next block is buried under enough blocks below the last known tip
+    fn ready(&self) -> bool {
+        self.next_height.saturating_add(self.confirmations) <= self.tip
+    }
+
+    /// Moves past `block`, if it's the one expected next
+    fn accept(&mut self, block: Block) -> Result<Block, RpcError> {
+        if block.height != self.next_height {
+            return Err(RpcError::InvalidResponse(format!("asked for block {}, got block {}", self.next_height, block.height)));
+        }
+        if let Some(expected) = self.last_hash {
+            if block.prev_hash != Some(expected) {
+                return Err(RpcError::BrokenChain { height: block.height, expected, prev_hash: block.prev_hash });
+            }
+        }
+        self.last_hash = Some(block.hash);
+        self.next_height += 1;
+        Ok(block)
+    }
+}
+
+/// An iterator over the blocks of the chain from a starting height, which waits for new blocks at the tip
+///
+/// Blocks behind the tip are read back to back until the stream catches up, then the node is polled for new
+/// ones. Every block is checked to follow the one before it. When it doesn't, a [`RpcError::BrokenChain`] is
+/// returned and the next call reads the same height again, which lets a load balancer that answered from a
+/// lagging node recover. Errors don't end the iterator, so callers decide whether to retry or stop.
+///
+/// # Example
+///
+///  ```no_run
+/// # use icon_utils::rpc::{stream::BlockStream, IconClient};
+/// let client = IconClient::new("https://api.icon.community/api/v3");
+/// for block in BlockStream::from_height(&client, 80_000_000).confirmations(2) {
+///     let block = block.unwrap();
+///     println!("{} {} transactions", block.height, block.transactions.len());
+/// }
+/// ```
+pub struct BlockStream<'a, T = HttpTransport> {
+    client: &'a IconClient<T>,
+    cursor: Cursor,
+    poll_interval: Duration,
+}
+
+impl<'a, T: Transport> BlockStream<'a, T> {
+    /// Creates a stream starting at the block at `start`
+    ///
+    /// # Example
+    ///
+    ///  ```
+    /// # use icon_utils::rpc::{stream::BlockStream, transport::MockTransport, IconClient, RpcError};
+    /// # use serde_json::json;
+    /// let block = |height: u64, hash: u8, prev: u8| json!({
+    ///     "height": height, "block_hash": format!("{:064x}", hash), "prev_block_hash": format!("{:064x}", prev),
+    ///     "time_stamp": 0, "confirmed_transaction_list": []
+    /// });
+    /// let transport = MockTransport::new();
+    /// transport.respond("icx_getLastBlock", block(12, 12, 11));
+    /// for response in [block(10, 10, 9), block(11, 11, 10), block(12, 12, 7)] {
+    ///     transport.respond("icx_getBlockByHeight", response);
+    /// }
+    /// let client = IconClient::with_transport("http://localhost:9000/api/v3", transport);
+    /// let mut blocks = BlockStream::from_height(&client, 10);
+    /// assert_eq!(blocks.next().unwrap().unwrap().height, 10);
+    /// assert_eq!(blocks.next().unwrap().unwrap().height, 11);
+    /// assert!(matches!(blocks.next().unwrap(), Err(RpcError::BrokenChain { height: 12, .. })));
+    /// assert_eq!(blocks.next_height(), 12);
+    /// ```
+    pub fn from_height(client: &'a IconClient<T>, start: u64) -> Self {
+        BlockStream { client, cursor: Cursor::new(start), poll_interval: DEFAULT_POLL_INTERVAL }
+    }
+
+    /// Only returns blocks with at least `confirmations` blocks on top of them, defaults to 0
+    pub fn confirmations(mut self, confirmations: u64) -> Self {
+        self.cursor.confirmations = confirmations;
+        self
+    }
+
+    /// Sets how long to wait before polling again once the stream has caught up
+    pub fn poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Returns the height of the next block the stream returns
+    pub fn next_height(&self) -> u64 {
+        self.cursor.next_height
+    }
+}
+
+impl<T: Transport> Iterator for BlockStream<'_, T> {
+    type Item = Result<Block, RpcError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.cursor.ready() {
+            match self.client.get_last_block() {
+                Ok(tip) => self.cursor.tip = tip.height,
+                Err(e) => return Some(Err(e)),
+            }
+            if !self.cursor.ready() {
+                std::thread::sleep(self.poll_interval);
+            }
+        }
+        let block = self.client.get_block_by_height(self.cursor.next_height);
+        Some(block.and_then(|block| self.cursor.accept(block)))
+    }
+}
+
+/// The async version of [`BlockStream`], read with [`AsyncBlockStream::into_stream`]
+///
+/// # Example
+///
+///  ```no_run
+/// # use futures_util::StreamExt;
+/// # use icon_utils::rpc::{async_client::IconClient, stream::AsyncBlockStream};
+/// # async fn example() {
+/// let client = IconClient::new("https://api.icon.community/api/v3");
+/// let blocks = AsyncBlockStream::from_height(&client, 80_000_000).confirmations(2).into_stream();
+/// let mut blocks = std::pin::pin!(blocks);
+/// while let Some(block) = blocks.next().await {
+///     println!("{}", block.unwrap().height);
+/// }
+/// # }
+/// ```
+#[cfg(feature = "async")]
+pub struct AsyncBlockStream<'a, T = super::transport::ReqwestTransport> {
+    client: &'a super::async_client::IconClient<T>,
+    cursor: Cursor,
+    poll_interval: Duration,
+}
+
+#[cfg(feature = "async")]
+impl<'a, T: super::transport::AsyncTransport> AsyncBlockStream<'a, T> {
+    /// Creates a stream starting at the block at `start`
+    pub fn from_height(client: &'a super::async_client::IconClient<T>, start: u64) -> Self {
+        AsyncBlockStream { client, cursor: Cursor::new(start), poll_interval: DEFAULT_POLL_INTERVAL }
+    }
+
+    /// Only returns blocks with at least `confirmations` blocks on top of them, defaults to 0
+    pub fn confirmations(mut self, confirmations: u64) -> Self {
+        self.cursor.confirmations = confirmations;
+        self
+    }
+
+    /// Sets how long to wait before polling again once the stream has caught up
+    pub fn poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Returns the height of the next block the stream returns
+    pub fn next_height(&self) -> u64 {
+        self.cursor.next_height
+    }
+
+    /// Turns the stream into a [`Stream`](futures_util::Stream) of blocks, which never ends
+    pub fn into_stream(self) -> impl futures_util::Stream<Item = Result<Block, RpcError>> + 'a {
+        futures_util::stream::unfold(self, |mut stream| async move {
+            let block = stream.next_block().await;
+            Some((block, stream))
+        })
+    }
+
+    async fn next_block(&mut self) -> Result<Block, RpcError> {
+        while !self.cursor.ready() {
+            self.cursor.tip = self.client.get_last_block().await?.height;
+            if !self.cursor.ready() {
+                tokio::time::sleep(self.poll_interval).await;
+            }
+        }
+        let block = self.client.get_block_by_height(self.cursor.next_height).await?;
+        self.cursor.accept(block)
+    }
+}
@@ -0,0 +1,110 @@
+//! # Icon Transfers
+//! `transfer` holds the options of [`IconClient::transfer`](super::IconClient::transfer), which sends ICX from a wallet
+//! in one call: the transaction is built, its steps estimated, then it's signed and submitted.
+use std::fmt;
+
+use thiserror::Error;
+
+use super::RpcError;
+use crate::network::Network;
+use crate::transaction::{BuildError, IcxTransaction, TransactionBuilder};
+use crate::types::Address;
+use crate::units::Icx;
+use crate::wallet::WalletError;
+
+/// The margin added to the estimated steps of a transfer, in percent
+pub const DEFAULT_MARGIN_PERCENT: u64 = 10;
+
+#[derive(Error, Debug)]
+// Error struct for transfers
+pub enum TransferError {
+    #[error("No network id is known for {0}, set it with TransferOptions::nid")]
+    UnknownNetwork(String),
+    #[error(transparent)]
+    Build(#[from] BuildError),
+    #[error(transparent)]
+    Wallet(#[from] WalletError),
+    #[error(transparent)]
+    Rpc(#[from] RpcError),
+}
+
+/// Options for [`IconClient::transfer`](super::IconClient::transfer), every field has a default
+///
+/// The network id defaults to the network whose default endpoint the client uses, other endpoints need it set.
+/// Without a step limit the node's estimate is used, plus [`DEFAULT_MARGIN_PERCENT`].
+#[derive(Debug, Clone, Default)]
+pub struct TransferOptions {
+    nid: Option<u64>,
+    step_limit: Option<u64>,
+    margin_percent: Option<u64>,
+    nonce: Option<u64>,
+    timestamp: Option<u64>,
+}
+
+impl TransferOptions {
+    /// Creates options with every default
+    pub fn new() -> Self {
+        TransferOptions::default()
+    }
+
+    /// Sets the network id the transfer is valid on
+    pub fn nid(mut self, nid: u64) -> Self {
+        self.nid = Some(nid);
+        self
+    }
+
+    /// Sets the network id from a network preset
+    pub fn network(self, network: &Network) -> Self {
+        self.nid(network.nid())
+    }
+
+    /// Sets the step limit, skipping the estimate
+    pub fn step_limit(mut self, step_limit: u64) -> Self {
+        self.step_limit = Some(step_limit);
+        self
+    }
+
+    /// Sets the margin added to the estimated steps, in percent
+    pub fn margin_percent(mut self, margin_percent: u64) -> Self {
+        self.margin_percent = Some(margin_percent);
+        self
+    }
+
+    /// Sets the nonce of the transaction
+    pub fn nonce(mut self, nonce: u64) -> Self {
+        self.nonce = Some(nonce);
+        self
+    }
+
+    /// Sets the timestamp of the transaction, in microseconds since the unix epoch, defaults to the current time
+    pub fn timestamp(mut self, timestamp: u64) -> Self {
+        self.timestamp = Some(timestamp);
+        self
+    }
+
+    /// Whether the step limit has to be estimated by the node
+    pub(crate) fn needs_estimate(&self) -> bool {
+        self.step_limit.is_none()
+    }
+
+    /// Returns the estimated steps with the margin added
+    pub(crate) fn with_margin(&self, steps: u64) -> u64 {
+        crate::transaction::with_margin(steps, self.margin_percent.unwrap_or(DEFAULT_MARGIN_PERCENT))
+    }
+
+    /// Builds the transfer, with a step limit of 0 if it has to be estimated
+    pub(crate) fn build(&self, endpoint: &str, from: Address, to: impl fmt::Display, amount: Icx) -> Result<IcxTransaction, TransferError> {
+        let nid = match self.nid {
+            Some(nid) => nid,
+            None => Network::from_endpoint(endpoint).ok_or_else(|| TransferError::UnknownNetwork(endpoint.to_owned()))?.nid(),
+        };
+        let mut builder = TransactionBuilder::new().from(from).to(to).value(amount).nid(nid).step_limit(self.step_limit.unwrap_or(0));
+        if let Some(nonce) = self.nonce {
+            builder = builder.nonce(nonce);
+        }
+        if let Some(timestamp) = self.timestamp {
+            builder = builder.timestamp(timestamp);
+        }
+        Ok(builder.build()?)
+    }
+}
@@ -0,0 +1,276 @@
+//! # Icon RPC Transports
+//! `transport` abstracts how JSON-RPC requests reach a node, so clients can be tested against canned responses.
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+
+use serde_json::{json, Value};
+
+use super::RpcError;
+
+/// Sends JSON-RPC requests for the blocking [`IconClient`](super::IconClient)
+pub trait Transport {
+    /// Posts a JSON-RPC request to `url`, returning the response body
+    fn send(&self, url: &str, request: Value) -> Result<Value, RpcError>;
+
+    /// Posts a JSON-RPC request to `url` with extra HTTP headers
+    ///
+    /// Transports that can't set headers ignore them, which is the default.
+    fn send_with_headers(&self, url: &str, request: Value, headers: &[(&str, &str)]) -> Result<Value, RpcError> {
+        let _ = headers;
+        self.send(url, request)
+    }
+}
+
+/// Sends JSON-RPC requests for the async [`IconClient`](super::async_client::IconClient)
+#[cfg(feature = "async")]
+pub trait AsyncTransport {
+    /// Posts a JSON-RPC request to `url`, returning the response body
+    fn send(&self, url: &str, request: Value) -> impl std::future::Future<Output = Result<Value, RpcError>> + Send;
+
+    /// Posts a JSON-RPC request to `url` with extra HTTP headers
+    ///
+    /// Transports that can't set headers ignore them, which is the default.
+    fn send_with_headers(
+        &self,
+        url: &str,
+        request: Value,
+        headers: &[(&str, &str)],
+    ) -> impl std::future::Future<Output = Result<Value, RpcError>> + Send {
+        let _ = headers;
+        self.send(url, request)
+    }
+}
+
+/// The default blocking transport, sending requests over HTTP with ureq
+///
+/// Blocking HTTP isn't available on `wasm32`, where every request fails and a custom [`Transport`] has to be used instead.
+#[derive(Debug, Clone)]
+pub struct HttpTransport {
+    #[cfg(not(target_arch = "wasm32"))]
+    agent: ureq::Agent,
+}
+
+impl HttpTransport {
+    /// Creates a transport without a request timeout
+    pub fn new() -> Self {
+        #[cfg(not(target_arch = "wasm32"))]
+        return HttpTransport { agent: ureq::Agent::new() };
+        #[cfg(target_arch = "wasm32")]
+        return HttpTransport {};
+    }
+
+    /// Creates a transport that fails requests taking longer than `timeout`
+    #[cfg_attr(target_arch = "wasm32", allow(unused_variables))]
+    pub fn with_timeout(timeout: Duration) -> Self {
+        #[cfg(not(target_arch = "wasm32"))]
+        return HttpTransport { agent: ureq::AgentBuilder::new().timeout(timeout).build() };
+        #[cfg(target_arch = "wasm32")]
+        return HttpTransport {};
+    }
+}
+
+impl Default for HttpTransport {
+    fn default() -> Self {
+        HttpTransport::new()
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Transport for HttpTransport {
+    fn send(&self, url: &str, request: Value) -> Result<Value, RpcError> {
+        self.send_with_headers(url, request, &[])
+    }
+
+    fn send_with_headers(&self, url: &str, request: Value, headers: &[(&str, &str)]) -> Result<Value, RpcError> {
+        let mut builder = self.agent.post(url);
+        for (name, value) in headers {
+            builder = builder.set(name, value);
+        }
+        match builder.send_json(request) {
+            Ok(response) => response.into_json().map_err(|e| RpcError::InvalidResponse(e.to_string())),
+            // ICON nodes return JSON-RPC errors with a non-2xx status, other error statuses such as
+            // rate limiting come from proxies in front of the node and have no JSON body
+            Err(ureq::Error::Status(status, response)) => response
+                .into_json()
+                .map_err(|_| RpcError::Http { status, url: url.to_owned() }),
+            Err(e) => Err(RpcError::Transport(e.to_string())),
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl Transport for HttpTransport {
+    fn send(&self, url: &str, _request: Value) -> Result<Value, RpcError> {
+        Err(RpcError::Transport(format!("{}: blocking HTTP is not available on wasm32", url)))
+    }
+}
+
+/// The default async transport, sending requests over HTTP with reqwest
+#[cfg(feature = "async")]
+#[derive(Debug, Clone, Default)]
+pub struct ReqwestTransport {
+    http: reqwest::Client,
+    timeout: Option<Duration>,
+}
+
+#[cfg(feature = "async")]
+impl ReqwestTransport {
+    /// Creates a transport that sends requests with an existing reqwest client
+    pub fn new(http: reqwest::Client) -> Self {
+        ReqwestTransport { http, timeout: None }
+    }
+
+    /// Fails requests taking longer than `timeout`
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+}
+
+#[cfg(feature = "async")]
+impl AsyncTransport for ReqwestTransport {
+    fn send(&self, url: &str, request: Value) -> impl std::future::Future<Output = Result<Value, RpcError>> + Send {
+        self.send_with_headers(url, request, &[])
+    }
+
+    fn send_with_headers(
+        &self,
+        url: &str,
+        request: Value,
+        headers: &[(&str, &str)],
+    ) -> impl std::future::Future<Output = Result<Value, RpcError>> + Send {
+        // ICON nodes return JSON-RPC errors with a non-2xx status, so the status is only checked without a JSON body
+        let mut builder = self.http.post(url).json(&request);
+        for (name, value) in headers {
+            builder = builder.header(*name, *value);
+        }
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+        let response = builder.send();
+        let url = url.to_owned();
+        async move {
+            let response = response.await.map_err(|e| RpcError::Transport(e.to_string()))?;
+            let status = response.status();
+            match response.json().await {
+                Ok(body) => Ok(body),
+                // Error statuses without a JSON body come from proxies in front of the node
+                Err(_) if !status.is_success() => Err(RpcError::Http { status: status.as_u16(), url }),
+                Err(e) => Err(RpcError::InvalidResponse(e.to_string())),
+            }
+        }
+    }
+}
+
+/// A request recorded by [`MockTransport`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedRequest {
+    pub url: String,
+    pub method: String,
+    pub params: Value,
+    /// Extra HTTP headers the request was sent with
+    pub headers: Vec<(String, String)>,
+}
+
+/// A transport that records requests and answers them with canned responses, for testing code built on the client
+///
+/// Responses for a method are returned in the order they were added, and the last one is repeated.
+///
+/// # Example
+///
+///  ```
+/// # use icon_utils::rpc::{IconClient, transport::MockTransport};
+/// # use serde_json::json;
+/// let transport = MockTransport::new();
+/// transport.respond("icx_getTotalSupply", json!("0x2961fff8ca4a62327800000"));
+/// let client = IconClient::with_transport("http://localhost:9000/api/v3", transport);
+/// let supply = client.get_total_supply().unwrap();
+/// assert_eq!(supply.format_icx(), "800460000");
+/// assert_eq!(client.transport().requests()[0].method, "icx_getTotalSupply");
+/// ```
+#[derive(Debug, Default)]
+pub struct MockTransport {
+    responses: Mutex<HashMap<String, VecDeque<Result<Value, Value>>>>,
+    requests: Mutex<Vec<RecordedRequest>>,
+}
+
+impl MockTransport {
+    /// Creates a transport with no responses
+    pub fn new() -> Self {
+        MockTransport::default()
+    }
+
+    /// Adds a successful `result` for `method`
+    pub fn respond(&self, method: &str, result: Value) {
+        lock(&self.responses).entry(method.to_owned()).or_default().push_back(Ok(result));
+    }
+
+    /// Adds a JSON-RPC error response for `method`
+    pub fn respond_error(&self, method: &str, code: i64, message: &str) {
+        let error = json!({ "code": code, "message": message });
+        lock(&self.responses).entry(method.to_owned()).or_default().push_back(Err(error));
+    }
+
+    /// Returns every request sent so far
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        lock(&self.requests).clone()
+    }
+
+    /// Returns the requests sent so far for `method`
+    pub fn requests_for(&self, method: &str) -> Vec<RecordedRequest> {
+        lock(&self.requests).iter().filter(|r| r.method == method).cloned().collect()
+    }
+
+    fn answer(&self, url: &str, request: Value, headers: &[(&str, &str)]) -> Result<Value, RpcError> {
+        let method = request.get("method").and_then(Value::as_str).unwrap_or_default().to_owned();
+        let id = request.get("id").cloned().unwrap_or(Value::Null);
+        lock(&self.requests).push(RecordedRequest {
+            url: url.to_owned(),
+            method: method.clone(),
+            params: request.get("params").cloned().unwrap_or(Value::Null),
+            headers: headers.iter().map(|(name, value)| ((*name).to_owned(), (*value).to_owned())).collect(),
+        });
+        let mut responses = lock(&self.responses);
+        let queue = responses
+            .get_mut(&method)
+            .filter(|queue| !queue.is_empty())
+            .ok_or_else(|| RpcError::Transport(format!("no mock response for {}", method)))?;
+        let response = if queue.len() > 1 { queue.pop_front() } else { queue.front().cloned() };
+        Ok(match response.expect("queue is not empty") {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err(error) => json!({ "jsonrpc": "2.0", "id": id, "error": error }),
+        })
+    }
+}
+
+impl Transport for MockTransport {
+    fn send(&self, url: &str, request: Value) -> Result<Value, RpcError> {
+        self.answer(url, request, &[])
+    }
+
+    fn send_with_headers(&self, url: &str, request: Value, headers: &[(&str, &str)]) -> Result<Value, RpcError> {
+        self.answer(url, request, headers)
+    }
+}
+
+#[cfg(feature = "async")]
+impl AsyncTransport for MockTransport {
+    fn send(&self, url: &str, request: Value) -> impl std::future::Future<Output = Result<Value, RpcError>> + Send {
+        std::future::ready(self.answer(url, request, &[]))
+    }
+
+    fn send_with_headers(
+        &self,
+        url: &str,
+        request: Value,
+        headers: &[(&str, &str)],
+    ) -> impl std::future::Future<Output = Result<Value, RpcError>> + Send {
+        std::future::ready(self.answer(url, request, headers))
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    // The mock is only used in tests, a panic while holding the lock shouldn't hide later requests
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
@@ -0,0 +1,528 @@
+//! # Icon RPC Types
+//! `types` holds the typed responses returned by [`IconClient`](super::IconClient).
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+use thiserror::Error;
+
+pub use crate::transaction::TransactionData;
+use crate::types::{Address, Hash256, HexBytes};
+use crate::units::Loop;
+
+/// Receipt of a transaction, returned by `icx_getTransactionResult`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionResult {
+    /// 1 on success, 0 on failure
+    #[serde(deserialize_with = "hex_u64")]
+    pub status: u64,
+    pub to: Option<Address>,
+    pub tx_hash: Hash256,
+    #[serde(deserialize_with = "hex_u64")]
+    pub tx_index: u64,
+    #[serde(deserialize_with = "hex_u64")]
+    pub block_height: u64,
+    pub block_hash: Hash256,
+    #[serde(deserialize_with = "hex_u64")]
+    pub cumulative_step_used: u64,
+    #[serde(deserialize_with = "hex_u64")]
+    pub step_used: u64,
+    #[serde(deserialize_with = "hex_u64")]
+    pub step_price: u64,
+    /// Address of the SCORE deployed by the transaction, if any
+    pub score_address: Option<Address>,
+    #[serde(default)]
+    pub event_logs: Vec<EventLog>,
+    pub logs_bloom: Option<HexBytes>,
+    /// Reason the transaction failed, only present when status is 0
+    pub failure: Option<FailureReason>,
+}
+
+impl TransactionResult {
+    /// Returns true if the transaction executed successfully
+    pub fn is_success(&self) -> bool {
+        self.status == 1
+    }
+
+    /// Returns the typed reason the transaction failed, or None if it succeeded
+    pub fn failure(&self) -> Option<TxFailure> {
+        if self.is_success() {
+            return None;
+        }
+        Some(match &self.failure {
+            Some(reason) => TxFailure::from(reason),
+            None => TxFailure::Unknown("receipt has no failure reason".to_owned()),
+        })
+    }
+
+    /// Returns the receipt if the transaction succeeded, or the reason it failed
+    ///
+    /// # Example
+    ///
+    ///  ```
+    /// # use icon_utils::rpc::{TransactionResult, TxFailure};
+    /// let receipt: TransactionResult = serde_json::from_value(serde_json::json!({
+    ///     "status": "0x0", "txHash": format!("0x{}", "00".repeat(32)), "txIndex": "0x0", "blockHeight": "0x1",
+    ///     "blockHash": format!("0x{}", "00".repeat(32)), "cumulativeStepUsed": "0x0", "stepUsed": "0x186a0",
+    ///     "stepPrice": "0x2e90edd00", "failure": {"code": "0xa", "message": "OutOfStep"}
+    /// })).unwrap();
+    /// match receipt.into_result() {
+    ///     Err(TxFailure::OutOfStep(_)) => println!("raise the step limit"),
+    ///     other => panic!("{:?}", other),
+    /// }
+    /// ```
+    pub fn into_result(self) -> Result<TransactionResult, TxFailure> {
+        match self.failure() {
+            Some(failure) => Err(failure),
+            None => Ok(self),
+        }
+    }
+}
+
+/// An event emitted by a SCORE during a transaction
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventLog {
+    pub score_address: Address,
+    /// The event signature followed by the indexed params
+    pub indexed: Vec<Option<String>>,
+    #[serde(default)]
+    pub data: Vec<Option<String>>,
+}
+
+/// The failure object of a failed transaction receipt
+#[derive(Debug, Clone, Deserialize)]
+pub struct FailureReason {
+    #[serde(deserialize_with = "hex_or_int")]
+    pub code: i64,
+    pub message: String,
+}
+
+/// Code of the first user defined failure, a SCORE reverting with code `n` fails with `n + USER_REVERTED_CODE`
+pub const USER_REVERTED_CODE: i64 = 32;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+// Error struct for failed transactions, mapped from the failure codes of the receipt
+pub enum TxFailure {
+    #[error("Transaction failed: {0}")]
+    Unknown(String),
+    #[error("Contract not found: {0}")]
+    ContractNotFound(String),
+    #[error("Method not found: {0}")]
+    MethodNotFound(String),
+    #[error("Method not payable: {0}")]
+    MethodNotPayable(String),
+    #[error("Illegal format: {0}")]
+    IllegalFormat(String),
+    #[error("Invalid parameter: {0}")]
+    InvalidParameter(String),
+    #[error("Invalid instance: {0}")]
+    InvalidInstance(String),
+    #[error("Invalid container access: {0}")]
+    InvalidContainerAccess(String),
+    #[error("Access denied: {0}")]
+    AccessDenied(String),
+    #[error("Out of step: {0}")]
+    OutOfStep(String),
+    #[error("Out of balance: {0}")]
+    OutOfBalance(String),
+    #[error("Timed out: {0}")]
+    Timeout(String),
+    #[error("Stack overflow: {0}")]
+    StackOverflow(String),
+    #[error("Transaction skipped: {0}")]
+    Skipped(String),
+    /// The SCORE reverted with its own code, `code` is the code given to `revert` by the SCORE
+    #[error("Reverted with code {code}: {message}")]
+    Reverted { code: i64, message: String },
+    #[error("Failed with code {code}: {message}")]
+    Other { code: i64, message: String },
+}
+
+impl TxFailure {
+    /// Returns the failure code of the receipt
+    pub fn code(&self) -> i64 {
+        match self {
+            TxFailure::Unknown(_) => 1,
+            TxFailure::ContractNotFound(_) => 2,
+            TxFailure::MethodNotFound(_) => 3,
+            TxFailure::MethodNotPayable(_) => 4,
+            TxFailure::IllegalFormat(_) => 5,
+            TxFailure::InvalidParameter(_) => 6,
+            TxFailure::InvalidInstance(_) => 7,
+            TxFailure::InvalidContainerAccess(_) => 8,
+            TxFailure::AccessDenied(_) => 9,
+            TxFailure::OutOfStep(_) => 10,
+            TxFailure::OutOfBalance(_) => 11,
+            TxFailure::Timeout(_) => 12,
+            TxFailure::StackOverflow(_) => 13,
+            TxFailure::Skipped(_) => 14,
+            TxFailure::Reverted { code, .. } => code + USER_REVERTED_CODE,
+            TxFailure::Other { code, .. } => *code,
+        }
+    }
+
+    /// Returns the failure message of the receipt
+    pub fn message(&self) -> &str {
+        match self {
+            TxFailure::Unknown(message)
+            | TxFailure::ContractNotFound(message)
+            | TxFailure::MethodNotFound(message)
+            | TxFailure::MethodNotPayable(message)
+            | TxFailure::IllegalFormat(message)
+            | TxFailure::InvalidParameter(message)
+            | TxFailure::InvalidInstance(message)
+            | TxFailure::InvalidContainerAccess(message)
+            | TxFailure::AccessDenied(message)
+            | TxFailure::OutOfStep(message)
+            | TxFailure::OutOfBalance(message)
+            | TxFailure::Timeout(message)
+            | TxFailure::StackOverflow(message)
+            | TxFailure::Skipped(message) => message,
+            TxFailure::Reverted { message, .. } | TxFailure::Other { message, .. } => message,
+        }
+    }
+}
+
+impl From<&FailureReason> for TxFailure {
+    fn from(reason: &FailureReason) -> Self {
+        let message = reason.message.clone();
+        match reason.code {
+            1 => TxFailure::Unknown(message),
+            2 => TxFailure::ContractNotFound(message),
+            3 => TxFailure::MethodNotFound(message),
+            4 => TxFailure::MethodNotPayable(message),
+            5 => TxFailure::IllegalFormat(message),
+            6 => TxFailure::InvalidParameter(message),
+            7 => TxFailure::InvalidInstance(message),
+            8 => TxFailure::InvalidContainerAccess(message),
+            9 => TxFailure::AccessDenied(message),
+            10 => TxFailure::OutOfStep(message),
+            11 => TxFailure::OutOfBalance(message),
+            12 => TxFailure::Timeout(message),
+            13 => TxFailure::StackOverflow(message),
+            14 => TxFailure::Skipped(message),
+            code @ USER_REVERTED_CODE..=999 => TxFailure::Reverted { code: code - USER_REVERTED_CODE, message },
+            code => TxFailure::Other { code, message },
+        }
+    }
+}
+
+impl From<FailureReason> for TxFailure {
+    fn from(reason: FailureReason) -> Self {
+        TxFailure::from(&reason)
+    }
+}
+
+/// A block, returned by `icx_getLastBlock`, `icx_getBlockByHeight` and `icx_getBlockByHash`
+///
+/// Nodes return blocks in either the v2 format (`block_hash`, `time_stamp`, integers and unprefixed hashes)
+/// or the v3 format (`hash`, `timeStamp`, hex integers), both are accepted.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Block {
+    #[serde(deserialize_with = "hex_or_int_u64")]
+    pub height: u64,
+    #[serde(alias = "block_hash", deserialize_with = "loose_hash")]
+    pub hash: Hash256,
+    /// Hash of the previous block, missing for the genesis block
+    #[serde(alias = "prev_block_hash", alias = "prevHash", default, deserialize_with = "loose_hash_option")]
+    pub prev_hash: Option<Hash256>,
+    /// Block timestamp, in microseconds since the unix epoch
+    #[serde(alias = "time_stamp", alias = "timeStamp", deserialize_with = "hex_or_int_u64")]
+    pub timestamp: u64,
+    /// The transactions confirmed in the block
+    #[serde(alias = "confirmed_transaction_list", default)]
+    pub transactions: Vec<Value>,
+    #[serde(default)]
+    pub version: Option<String>,
+}
+
+/// A transaction included in a block, returned by `icx_getTransactionByHash`
+///
+/// Version 2 transactions have no version, step limit or nid, and carry a `fee` and a `tx_hash` without a `0x`
+/// prefix. Use [`TransactionV2`](crate::transaction::TransactionV2) on the raw params to check their hash.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(try_from = "RawTransaction")]
+pub struct ConfirmedTransaction {
+    pub version: Option<u64>,
+    pub from: Address,
+    pub to: Address,
+    pub value: Option<Loop>,
+    pub step_limit: Option<u64>,
+    /// Fee paid by version 2 transactions, which have no step limit
+    pub fee: Option<Loop>,
+    pub timestamp: u64,
+    pub nid: Option<u64>,
+    pub nonce: Option<u64>,
+    pub signature: Option<String>,
+    pub tx_hash: Hash256,
+    pub tx_index: Option<u64>,
+    pub block_height: Option<u64>,
+    pub block_hash: Option<Hash256>,
+    /// The decoded `dataType` and `data` of the transaction
+    pub data: TransactionData,
+}
+
+/// The wire format of a confirmed transaction, before its data is decoded
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawTransaction {
+    #[serde(default, deserialize_with = "crate::transaction::hex_u64_option::deserialize")]
+    version: Option<u64>,
+    from: Address,
+    to: Address,
+    value: Option<Loop>,
+    #[serde(default, deserialize_with = "crate::transaction::hex_u64_option::deserialize")]
+    step_limit: Option<u64>,
+    fee: Option<Loop>,
+    #[serde(deserialize_with = "hex_or_int_u64")]
+    timestamp: u64,
+    #[serde(default, deserialize_with = "crate::transaction::hex_u64_option::deserialize")]
+    nid: Option<u64>,
+    /// Version 2 transactions write the nonce in decimal
+    #[serde(default, deserialize_with = "hex_or_int_u64_option")]
+    nonce: Option<u64>,
+    signature: Option<String>,
+    #[serde(alias = "tx_hash", deserialize_with = "loose_hash")]
+    tx_hash: Hash256,
+    #[serde(default, deserialize_with = "crate::transaction::hex_u64_option::deserialize")]
+    tx_index: Option<u64>,
+    #[serde(default, deserialize_with = "crate::transaction::hex_u64_option::deserialize")]
+    block_height: Option<u64>,
+    block_hash: Option<Hash256>,
+    data_type: Option<String>,
+    data: Option<Value>,
+}
+
+impl TryFrom<RawTransaction> for ConfirmedTransaction {
+    type Error = String;
+    fn try_from(raw: RawTransaction) -> Result<Self, String> {
+        let data = TransactionData::decode(raw.data_type.as_deref(), raw.data)?;
+        Ok(ConfirmedTransaction {
+            version: raw.version,
+            from: raw.from,
+            to: raw.to,
+            value: raw.value,
+            step_limit: raw.step_limit,
+            fee: raw.fee,
+            timestamp: raw.timestamp,
+            nid: raw.nid,
+            nonce: raw.nonce,
+            signature: raw.signature,
+            tx_hash: raw.tx_hash,
+            tx_index: raw.tx_index,
+            block_height: raw.block_height,
+            block_hash: raw.block_hash,
+            data,
+        })
+    }
+}
+
+/// The deployment state of a SCORE, returned by `icx_getScoreStatus`
+///
+/// `current` is the code that runs, `next` a deploy or update still waiting on audit. Nodes leave out
+/// `disabled` and `blocked` unless they're set.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScoreStatus {
+    pub owner: Address,
+    pub current: Option<ScoreContract>,
+    pub next: Option<ScoreContract>,
+    pub deposit_info: Option<DepositInfo>,
+    /// Set when the owner disabled the SCORE
+    #[serde(default, deserialize_with = "hex_bool")]
+    pub disabled: bool,
+    /// Set when governance blocked the SCORE
+    #[serde(default, deserialize_with = "hex_bool")]
+    pub blocked: bool,
+}
+
+impl ScoreStatus {
+    /// Returns true if the current code is active and the SCORE is neither disabled nor blocked
+    pub fn is_active(&self) -> bool {
+        !self.disabled && !self.blocked && self.current.as_ref().is_some_and(|c| c.status == ContractStatus::Active)
+    }
+
+    /// Returns true if a deploy or update is waiting on audit
+    pub fn is_pending(&self) -> bool {
+        self.next.as_ref().is_some_and(|c| c.status == ContractStatus::Pending)
+    }
+}
+
+/// A deployed version of a SCORE's code
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScoreContract {
+    /// `python` or `java`
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub code_hash: Hash256,
+    pub deploy_tx_hash: Hash256,
+    /// The audit transaction that accepted or rejected the code, missing on networks without audit
+    pub audit_tx_hash: Option<Hash256>,
+    pub status: ContractStatus,
+}
+
+/// The audit state of a deployed version of a SCORE
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ContractStatus {
+    /// Waiting on audit
+    Pending,
+    /// Accepted and running
+    Active,
+    /// Replaced by an update
+    Inactive,
+    /// Rejected by audit
+    Rejected,
+    #[serde(other)]
+    Unknown,
+}
+
+/// The fee sharing deposits of a SCORE
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DepositInfo {
+    pub score_address: Option<Address>,
+    /// The deposit left to pay fees with
+    pub available_deposit: Option<Loop>,
+    /// The raw deposit entries, whose fields differ between fee sharing versions
+    #[serde(default)]
+    pub deposits: Vec<Value>,
+}
+
+/// The execution trace of a transaction, returned by `debug_getTrace`
+///
+/// The logs are what the node recorded while running the transaction, such as the SCORE calls it made, the steps
+/// they used and where it failed, so they read like a stack trace of a failed call.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExecutionTrace {
+    /// The status of the receipt, 1 on success and 0 on failure
+    #[serde(default, deserialize_with = "hex_or_int_u64_option")]
+    pub status: Option<u64>,
+    #[serde(default)]
+    pub logs: Vec<TraceLog>,
+}
+
+impl ExecutionTrace {
+    /// Returns true if the traced transaction failed
+    pub fn is_failure(&self) -> bool {
+        self.status == Some(0)
+    }
+
+    /// Returns the messages of the trace in order, one per line
+    pub fn to_text(&self) -> String {
+        self.logs.iter().map(|log| log.msg.as_str()).collect::<Vec<_>>().join("\n")
+    }
+}
+
+/// A line of an [`ExecutionTrace`]
+#[derive(Debug, Clone, Deserialize)]
+pub struct TraceLog {
+    #[serde(deserialize_with = "hex_or_int")]
+    pub level: i64,
+    pub msg: String,
+    /// Microseconds since the transaction started executing
+    #[serde(default, deserialize_with = "hex_or_int_u64")]
+    pub ts: u64,
+}
+
+impl TraceLog {
+    /// Trace level of the node's own logs, the other levels are SCORE debug and trace logs
+    pub const SYSTEM: i64 = 2;
+}
+
+/// Deserializes a `0x1`/`0x0` flag
+fn hex_bool<'de, D>(deserializer: D) -> Result<bool, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(hex_u64(deserializer)? != 0)
+}
+
+/// Deserializes a hash that may be missing its `0x` prefix, as in v2 blocks
+fn loose_hash<'de, D>(deserializer: D) -> Result<Hash256, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    use serde::de::Error;
+    let value = String::deserialize(deserializer)?;
+    match value.strip_prefix("0x") {
+        Some(_) => value.parse(),
+        None => format!("0x{}", value).parse(),
+    }
+    .map_err(Error::custom)
+}
+
+fn loose_hash_option<'de, D>(deserializer: D) -> Result<Option<Hash256>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    // The genesis block has an empty previous hash
+    match Option::<String>::deserialize(deserializer)? {
+        None => Ok(None),
+        Some(value) if value.is_empty() => Ok(None),
+        Some(value) => {
+            let value = serde::de::value::StringDeserializer::<D::Error>::new(value);
+            loose_hash(value).map(Some)
+        }
+    }
+}
+
+/// Deserializes a `0x` prefixed hex string to a u64
+pub(crate) fn hex_u64<'de, D>(deserializer: D) -> Result<u64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    use serde::de::Error;
+    let value = String::deserialize(deserializer)?;
+    let parsed = crate::icon_hex::from_hex_int(&value).map_err(Error::custom)?;
+    u64::try_from(parsed).map_err(|_| Error::custom(format!("{} doesn't fit in a u64", value)))
+}
+
+/// Deserializes a non-negative number that nodes return either as hex or as a plain integer
+fn hex_or_int_u64<'de, D>(deserializer: D) -> Result<u64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    use serde::de::Error;
+    let value = hex_or_int(deserializer)?;
+    u64::try_from(value).map_err(|_| Error::custom(format!("expected a non-negative number, got {}", value)))
+}
+
+fn hex_or_int_u64_option<'de, D>(deserializer: D) -> Result<Option<u64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    struct Wrapper(#[serde(deserialize_with = "hex_or_int_u64")] u64);
+    Ok(Option::<Wrapper>::deserialize(deserializer)?.map(|Wrapper(value)| value))
+}
+
+/// Deserializes a number that nodes return either as hex or as a plain integer
+fn hex_or_int<'de, D>(deserializer: D) -> Result<i64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    use serde::de::Error;
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Raw {
+        Int(i64),
+        Str(String),
+    }
+    match Raw::deserialize(deserializer)? {
+        Raw::Int(v) => Ok(v),
+        Raw::Str(v) => {
+            let (negative, v) = match v.strip_prefix('-') {
+                Some(rest) => (true, rest),
+                None => (false, v.as_str()),
+            };
+            let parsed = match v.strip_prefix("0x") {
+                Some(digits) => i64::from_str_radix(digits, 16),
+                None => v.parse(),
+            }
+            .map_err(Error::custom)?;
+            Ok(if negative { -parsed } else { parsed })
+        }
+    }
+}
@@ -0,0 +1,101 @@
+//! # Icon WebSockets
+//! `ws` follows new blocks and events over the node's `icon_dex` websockets, so indexers don't have to poll.
+use futures_util::{SinkExt, Stream, StreamExt};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::Value;
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+
+use super::types::hex_u64;
+use super::RpcError;
+pub use crate::events::EventFilter;
+use crate::events::DecodedEvent;
+use crate::types::Hash256;
+
+/// A new block, sent by `subscribe_blocks`
+#[derive(Debug, Clone, Deserialize)]
+pub struct BlockNotification {
+    pub hash: Hash256,
+    #[serde(deserialize_with = "hex_u64")]
+    pub height: u64,
+}
+
+/// A block with transactions that emitted a matching event, sent by `subscribe_events`
+#[derive(Debug, Clone, Deserialize)]
+pub struct EventNotification {
+    /// Hash of the block containing the events
+    pub hash: Hash256,
+    #[serde(deserialize_with = "hex_u64")]
+    pub height: u64,
+    /// Index of the transaction in the block
+    #[serde(deserialize_with = "hex_u64")]
+    pub index: u64,
+    /// Indexes of the matching events in the transaction's event logs
+    #[serde(default)]
+    pub events: Vec<String>,
+}
+
+/// An event that matched an [`EventFilter`], decoded and sent to a channel by `forward_events`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MatchedEvent {
+    pub height: u64,
+    /// Hash of the block containing the event
+    pub block_hash: Hash256,
+    pub tx_hash: Hash256,
+    /// Index of the event in the transaction's event logs
+    pub index: usize,
+    pub event: DecodedEvent,
+}
+
+/// Returns the `icon_dex` websocket URL next to a `/api/v3` endpoint
+pub(crate) fn websocket_url(endpoint: &str, path: &str) -> String {
+    let endpoint = endpoint.trim_end_matches('/');
+    let endpoint = match endpoint.split_once("://") {
+        Some(("https", rest)) => format!("wss://{}", rest),
+        Some(("http", rest)) => format!("ws://{}", rest),
+        _ => endpoint.to_owned(),
+    };
+    format!("{}/icon_dex/{}", endpoint, path)
+}
+
+/// Connects to `url`, sends the subscription request and returns the stream of notifications
+pub(crate) async fn subscribe<T: DeserializeOwned>(
+    url: &str,
+    request: Value,
+) -> Result<impl Stream<Item = Result<T, RpcError>>, RpcError> {
+    let (mut socket, _) = connect_async(url).await.map_err(ws_error)?;
+    socket.send(Message::Text(request.to_string())).await.map_err(ws_error)?;
+    // The node acknowledges the request with a code before sending notifications
+    loop {
+        match socket.next().await {
+            Some(Ok(Message::Text(text))) => {
+                let ack: Value = serde_json::from_str(&text).map_err(|e| RpcError::InvalidResponse(e.to_string()))?;
+                match ack.get("code").and_then(Value::as_i64) {
+                    Some(0) => break,
+                    Some(code) => {
+                        return Err(RpcError::Node {
+                            code,
+                            message: ack.get("message").and_then(Value::as_str).unwrap_or_default().to_owned(),
+                            data: None,
+                        })
+                    }
+                    None => return Err(RpcError::InvalidResponse(format!("expected subscription ack, got {}", text))),
+                }
+            }
+            Some(Ok(_)) => {}
+            Some(Err(e)) => return Err(ws_error(e)),
+            None => return Err(RpcError::WebSocket("connection closed before the subscription was acknowledged".to_owned())),
+        }
+    }
+    Ok(socket.filter_map(|message| async move {
+        match message {
+            Ok(Message::Text(text)) => Some(serde_json::from_str(&text).map_err(|e| RpcError::InvalidResponse(e.to_string()))),
+            Ok(_) => None,
+            Err(e) => Some(Err(ws_error(e))),
+        }
+    }))
+}
+
+fn ws_error(e: tokio_tungstenite::tungstenite::Error) -> RpcError {
+    RpcError::WebSocket(e.to_string())
+}
@@ -0,0 +1,285 @@
+//! # Icon Signer
+//! `signer` is a module for signing transactions without depending on where the private key is kept.
+//!
+//! [`Wallet`](crate::wallet::Wallet) signs with an in-memory key. Hardware wallets, KMS or remote signers
+//! can implement [`Signer`], or [`AsyncSigner`] when signing needs to await a network call.
+//! [`KmsSigner`](kms::KmsSigner) and [`RemoteSigner`](remote::RemoteSigner) are provided for AWS KMS and signing services.
+//! Signatures from several signers over one transaction are collected with [`threshold`].
+#[cfg(feature = "kms")]
+pub mod kms;
+#[cfg(feature = "async")]
+pub mod remote;
+pub mod threshold;
+
+use std::fmt;
+use std::str::FromStr;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use k256::ecdsa::{recoverable, VerifyingKey};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::serializer::Transaction;
+use crate::types::Address;
+use crate::wallet::{prepare_transaction, pubkey_hash, SignedTransaction, WalletError};
+
+/// Length of an encoded signature, `r || s || recovery id`
+pub const SIGNATURE_LEN: usize = 65;
+
+/// A recoverable secp256k1 signature, sent to ICON as the base64 of `r || s || recovery id`
+///
+/// # Example
+///
+///  ```
+/// # use icon_utils::{signer::Signature, wallet::Wallet};
+/// let wallet = Wallet::new("68ee9ca94b71c42ba79375b8677e29a717fb8072bcb17cd5ee288f9b77bc2894").unwrap();
+/// let signature = wallet.sign("example.example").unwrap();
+/// assert!(signature.recovery_id() <= 1);
+/// assert_eq!(Signature::from_base64(&signature.to_base64()).unwrap(), signature);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Signature {
+    r: [u8; 32],
+    s: [u8; 32],
+    recovery_id: u8,
+}
+
+impl Signature {
+    /// Creates a signature from its parts, checking that they form a valid recoverable signature
+    pub fn new(r: [u8; 32], s: [u8; 32], recovery_id: u8) -> Result<Self, WalletError> {
+        let mut bytes = [0u8; SIGNATURE_LEN];
+        bytes[..32].copy_from_slice(&r);
+        bytes[32..64].copy_from_slice(&s);
+        bytes[64] = recovery_id;
+        Signature::from_bytes(&bytes)
+    }
+
+    /// Reads a signature from its 65 byte `r || s || recovery id` encoding
+    ///
+    /// ICON nodes take a recovery id of 0 or 1. Ethereum style ids of 27 or 28 are accepted and stored as 0 or 1,
+    /// so signatures from other tools are sent in the layout nodes expect.
+    ///
+    /// # Example
+    ///
+    ///  ```
+    /// # use icon_utils::{signer::Signature, wallet::Wallet};
+    /// # let wallet = Wallet::new("68ee9ca94b71c42ba79375b8677e29a717fb8072bcb17cd5ee288f9b77bc2894").unwrap();
+    /// let signature = wallet.sign("example.example").unwrap();
+    /// let mut bytes = signature.to_bytes();
+    /// bytes[64] += 27;
+    /// assert_eq!(Signature::from_bytes(&bytes).unwrap(), signature);
+    /// bytes[64] = 2;
+    /// assert!(Signature::from_bytes(&bytes).is_err());
+    /// ```
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, WalletError> {
+        if bytes.len() != SIGNATURE_LEN {
+            return Err(WalletError::InvalidSignature(format!("expected {} bytes, got {}", SIGNATURE_LEN, bytes.len())));
+        }
+        let mut bytes: [u8; SIGNATURE_LEN] = bytes.try_into().expect("length was checked");
+        bytes[64] = match bytes[64] {
+            id @ (0 | 1) => id,
+            id @ (27 | 28) => id - 27,
+            id => return Err(WalletError::InvalidSignature(format!("recovery id must be 0 or 1, got {}", id))),
+        };
+        let signature =
+            recoverable::Signature::try_from(bytes.as_slice()).map_err(|e| WalletError::InvalidSignature(e.to_string()))?;
+        Ok(signature.into())
+    }
+
+    /// Reads a signature from base64, as found in the `signature` field of a transaction
+    pub fn from_base64(signature: &str) -> Result<Self, WalletError> {
+        let bytes = STANDARD.decode(signature).map_err(|e| WalletError::InvalidSignature(e.to_string()))?;
+        Signature::from_bytes(&bytes)
+    }
+
+    /// Returns the r value of the signature
+    pub fn r(&self) -> &[u8; 32] {
+        &self.r
+    }
+
+    /// Returns the s value of the signature
+    pub fn s(&self) -> &[u8; 32] {
+        &self.s
+    }
+
+    /// Returns the recovery id of the signature, 0 or 1
+    pub fn recovery_id(&self) -> u8 {
+        self.recovery_id
+    }
+
+    /// Returns the 65 byte `r || s || recovery id` encoding of the signature
+    pub fn to_bytes(&self) -> [u8; SIGNATURE_LEN] {
+        let mut bytes = [0u8; SIGNATURE_LEN];
+        bytes[..32].copy_from_slice(&self.r);
+        bytes[32..64].copy_from_slice(&self.s);
+        bytes[64] = self.recovery_id;
+        bytes
+    }
+
+    /// Returns the base64 encoding ICON expects in the `signature` field of a transaction
+    pub fn to_base64(&self) -> String {
+        STANDARD.encode(self.to_bytes())
+    }
+
+    /// Recovers the public key that signed a SHA3-256 hash
+    pub fn recover_pubkey(&self, hash: &[u8; 32]) -> Result<VerifyingKey, WalletError> {
+        self.to_recoverable()?
+            .recover_verifying_key_from_digest_bytes(hash.into())
+            .map_err(|e| WalletError::InvalidSignature(e.to_string()))
+    }
+
+    /// Recovers the hx address that signed a SHA3-256 hash
+    ///
+    /// # Example
+    ///
+    ///  ```
+    /// # use icon_utils::{signer::Signer, wallet::Wallet};
+    /// let wallet = Wallet::new("68ee9ca94b71c42ba79375b8677e29a717fb8072bcb17cd5ee288f9b77bc2894").unwrap();
+    /// let hash = [7u8; 32];
+    /// let signature = wallet.sign_hash(&hash).unwrap();
+    /// assert_eq!(signature.recover_address(&hash).unwrap(), Signer::address(&wallet));
+    /// ```
+    pub fn recover_address(&self, hash: &[u8; 32]) -> Result<Address, WalletError> {
+        Ok(Address::Eoa(pubkey_hash(&self.recover_pubkey(hash)?)))
+    }
+
+    pub(crate) fn to_recoverable(self) -> Result<recoverable::Signature, WalletError> {
+        recoverable::Signature::try_from(self.to_bytes().as_slice()).map_err(|e| WalletError::InvalidSignature(e.to_string()))
+    }
+}
+
+impl From<recoverable::Signature> for Signature {
+    fn from(signature: recoverable::Signature) -> Self {
+        let bytes = signature.as_ref();
+        let mut r = [0u8; 32];
+        let mut s = [0u8; 32];
+        r.copy_from_slice(&bytes[..32]);
+        s.copy_from_slice(&bytes[32..64]);
+        Signature { r, s, recovery_id: bytes[64] }
+    }
+}
+
+impl fmt::Display for Signature {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.to_base64())
+    }
+}
+
+impl FromStr for Signature {
+    type Err = WalletError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Signature::from_base64(s)
+    }
+}
+
+impl Serialize for Signature {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_base64())
+    }
+}
+
+impl<'de> Deserialize<'de> for Signature {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        value.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Anything that can sign ICON transaction hashes for an address
+///
+/// # Example
+///
+///  ```
+/// # use icon_utils::{signer::Signer, wallet::Wallet};
+/// fn signer_address(signer: &impl Signer) -> String {
+///     signer.address().to_string()
+/// }
+/// let wallet = Wallet::new("68ee9ca94b71c42ba79375b8677e29a717fb8072bcb17cd5ee288f9b77bc2894").unwrap();
+/// assert_eq!(signer_address(&wallet), wallet.address());
+/// ```
+pub trait Signer {
+    /// Returns the hx address signatures are made for
+    fn address(&self) -> Address;
+
+    /// Signs a SHA3-256 hash, returning a recoverable signature
+    fn sign_hash(&self, hash: &[u8; 32]) -> Result<Signature, WalletError>;
+
+    /// Serializes, hashes and signs a transaction
+    fn sign_transaction<T>(&self, tx: &T) -> Result<SignedTransaction, WalletError>
+    where
+        T: Transaction,
+        T::Params: serde::Serialize,
+    {
+        let (method, params, hash) = prepare_transaction(tx)?;
+        let signature = self.sign_hash(&hash)?;
+        let signed = SignedTransaction { method, params, hash, signature };
+        #[cfg(feature = "tracing")]
+        trace_signed(&signed, &self.address());
+        Ok(signed)
+    }
+}
+
+/// A [`Signer`] whose signatures are deterministic, with nonces derived from the key and hash as in RFC6979
+///
+/// Signing the same hash with the same key always gives the same bytes, matching the official Java and Python
+/// SDKs, which derive their nonces the same way. Code that compares or deduplicates signatures can require this
+/// instead of [`Signer`]. KMS and hardware signers generally pick random nonces and don't implement it.
+///
+/// # Example
+///
+///  ```
+/// # use icon_utils::{signer::DeterministicSigner, wallet::Wallet};
+/// fn sign_twice(signer: &impl DeterministicSigner, hash: &[u8; 32]) -> bool {
+///     signer.sign_hash(hash).unwrap() == signer.sign_hash(hash).unwrap()
+/// }
+/// let wallet = Wallet::new("68ee9ca94b71c42ba79375b8677e29a717fb8072bcb17cd5ee288f9b77bc2894").unwrap();
+/// assert!(sign_twice(&wallet, &[7u8; 32]));
+/// ```
+pub trait DeterministicSigner: Signer {}
+
+/// A [`Signer`] that signs asynchronously, such as a remote or KMS signer
+///
+/// Every [`Signer`] that is `Sync` is also an `AsyncSigner`.
+#[cfg(feature = "async")]
+pub trait AsyncSigner {
+    /// Returns the hx address signatures are made for
+    fn address(&self) -> Address;
+
+    /// Signs a SHA3-256 hash, returning a recoverable signature
+    fn sign_hash(&self, hash: &[u8; 32]) -> impl std::future::Future<Output = Result<Signature, WalletError>> + Send;
+
+    /// Serializes, hashes and signs a transaction
+    fn sign_transaction<T>(&self, tx: &T) -> impl std::future::Future<Output = Result<SignedTransaction, WalletError>> + Send
+    where
+        Self: Sync,
+        T: Transaction,
+        T::Params: serde::Serialize,
+    {
+        let prepared = prepare_transaction(tx);
+        async move {
+            let (method, params, hash) = prepared?;
+            let signature = self.sign_hash(&hash).await?;
+            let signed = SignedTransaction { method, params, hash, signature };
+            #[cfg(feature = "tracing")]
+            trace_signed(&signed, &self.address());
+            Ok(signed)
+        }
+    }
+}
+
+/// Records a signed transaction with its hash and signer
+#[cfg(feature = "tracing")]
+fn trace_signed(signed: &SignedTransaction, signer: &Address) {
+    tracing::debug!(method = %signed.method, tx_hash = %signed.tx_hash(), %signer, "signed transaction");
+}
+
+#[cfg(feature = "async")]
+impl<S: Signer + Sync> AsyncSigner for S {
+    fn address(&self) -> Address {
+        Signer::address(self)
+    }
+
+    async fn sign_hash(&self, hash: &[u8; 32]) -> Result<Signature, WalletError> {
+        Signer::sign_hash(self, hash)
+    }
+}
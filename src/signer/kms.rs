@@ -0,0 +1,93 @@
+//! # Icon KMS Signer
+//! `kms` is a module for signing transactions with a secp256k1 key held in AWS KMS.
+use aws_sdk_kms::error::DisplayErrorContext;
+use aws_sdk_kms::primitives::Blob;
+use aws_sdk_kms::types::{MessageType, SigningAlgorithmSpec};
+use aws_sdk_kms::Client;
+use k256::ecdsa::{self, recoverable, VerifyingKey};
+use k256::pkcs8::DecodePublicKey;
+
+use super::{AsyncSigner, Signature};
+use crate::types::Address;
+use crate::wallet::{pubkey_hash, WalletError};
+
+/// A signer backed by an `ECC_SECG_P256K1` key in AWS KMS
+///
+/// The private key never leaves KMS, each hash is signed with the KMS `Sign` API and the recovery id
+/// ICON expects is found locally from the key's public key.
+///
+/// # Example
+///
+///  ```no_run
+/// # use icon_utils::{signer::{kms::KmsSigner, AsyncSigner}, transaction::TransactionBuilder};
+/// # async fn sign(client: aws_sdk_kms::Client) {
+/// let signer = KmsSigner::new(client, "alias/icon-hot-wallet").await.unwrap();
+/// let tx = TransactionBuilder::new()
+///     .from(signer.address())
+///     .to("hx0000000000000000000000000000000000000000")
+///     .value(1u64)
+///     .build()
+///     .unwrap();
+/// let signed = signer.sign_transaction(&tx).await.unwrap();
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct KmsSigner {
+    client: Client,
+    key_id: String,
+    pubkey: VerifyingKey,
+    address: Address,
+}
+
+impl KmsSigner {
+    /// Creates a signer for a KMS key id, ARN or alias, fetching its public key with `GetPublicKey`
+    pub async fn new(client: Client, key_id: impl Into<String>) -> Result<Self, WalletError> {
+        let key_id = key_id.into();
+        let output = client.get_public_key().key_id(&key_id).send().await.map_err(kms_error)?;
+        let der = output.public_key().ok_or_else(|| WalletError::Signer("KMS returned no public key".to_owned()))?;
+        let pubkey = VerifyingKey::from_public_key_der(der.as_ref())
+            .map_err(|e| WalletError::Signer(format!("KMS key {} is not a secp256k1 key: {}", key_id, e)))?;
+        let address = Address::Eoa(pubkey_hash(&pubkey));
+        Ok(KmsSigner { client, key_id, pubkey, address })
+    }
+
+    /// Returns the KMS key the signer signs with
+    pub fn key_id(&self) -> &str {
+        &self.key_id
+    }
+
+    /// Returns the public key of the KMS key
+    pub fn public_key(&self) -> &VerifyingKey {
+        &self.pubkey
+    }
+}
+
+impl AsyncSigner for KmsSigner {
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    async fn sign_hash(&self, hash: &[u8; 32]) -> Result<Signature, WalletError> {
+        let output = self
+            .client
+            .sign()
+            .key_id(&self.key_id)
+            .message(Blob::new(hash.to_vec()))
+            .message_type(MessageType::Digest)
+            .signing_algorithm(SigningAlgorithmSpec::EcdsaSha256)
+            .send()
+            .await
+            .map_err(kms_error)?;
+        let der = output.signature().ok_or_else(|| WalletError::Signer("KMS returned no signature".to_owned()))?;
+        let signature = ecdsa::Signature::from_der(der.as_ref()).map_err(|e| WalletError::InvalidSignature(e.to_string()))?;
+        // KMS can return a high s, which ICON rejects as malleable
+        let signature = signature.normalize_s().unwrap_or(signature);
+        let signature = recoverable::Signature::from_digest_bytes_trial_recovery(&self.pubkey, hash.into(), &signature)
+            .map_err(|e| WalletError::InvalidSignature(e.to_string()))?;
+        Ok(signature.into())
+    }
+}
+
+fn kms_error<E: std::error::Error + 'static>(error: E) -> WalletError {
+    WalletError::Signer(DisplayErrorContext(error).to_string())
+}
@@ -0,0 +1,151 @@
+//! # Icon Remote Signer
+//! `remote` is a module for signing transactions with a key held by a separate signing service, reached over HTTP.
+//!
+//! The service is sent a JSON body with the address and the `0x` prefixed hash to sign:
+//! `{"address": "hx...", "hash": "0x..."}`, and answers with the base64 ICON signature: `{"signature": "..."}`.
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use super::{AsyncSigner, Signature};
+use crate::types::{Address, Hash256};
+use crate::wallet::WalletError;
+
+/// Computes extra headers from the body of a signing request, such as an HMAC of the body or a short-lived token
+type AuthHook = Arc<dyn Fn(&[u8]) -> Vec<(String, String)> + Send + Sync>;
+
+#[derive(Serialize)]
+struct SignRequest {
+    address: Address,
+    hash: Hash256,
+}
+
+#[derive(Deserialize)]
+struct SignResponse {
+    signature: Signature,
+}
+
+/// A signer that asks a signing service for each signature
+///
+/// The private key never leaves the service. Every signature is checked to recover to the signer's address, so a
+/// misconfigured service can't return a signature for the wrong key.
+///
+/// # Example
+///
+///  ```no_run
+/// # use icon_utils::{signer::{remote::RemoteSigner, AsyncSigner}, transaction::TransactionBuilder};
+/// # async fn sign() {
+/// let signer = RemoteSigner::new("https://signer.internal/sign", "hxbe258ceb872e08851f1f59694dac2558708ece11".parse().unwrap())
+///     .header("Authorization", "Bearer secret-token");
+/// let tx = TransactionBuilder::new()
+///     .from(signer.address())
+///     .to("hx0000000000000000000000000000000000000000")
+///     .value(1u64)
+///     .step_limit(100_000)
+///     .nid(1)
+///     .build()
+///     .unwrap();
+/// let signed = signer.sign_transaction(&tx).await.unwrap();
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct RemoteSigner {
+    http: reqwest::Client,
+    url: String,
+    address: Address,
+    headers: Vec<(String, String)>,
+    auth: Option<AuthHook>,
+    timeout: Option<Duration>,
+}
+
+impl RemoteSigner {
+    /// Creates a signer for `address` that posts signing requests to `url`
+    pub fn new(url: &str, address: Address) -> Self {
+        RemoteSigner::with_client(reqwest::Client::new(), url, address)
+    }
+
+    /// Creates a signer that sends requests with an existing reqwest client
+    pub fn with_client(http: reqwest::Client, url: &str, address: Address) -> Self {
+        RemoteSigner { http, url: url.to_owned(), address, headers: Vec::new(), auth: None, timeout: None }
+    }
+
+    /// Sends a header with every signing request, such as an `Authorization` header
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_owned(), value.to_owned()));
+        self
+    }
+
+    /// Computes headers for each signing request from its body, for services that authenticate requests individually
+    ///
+    /// # Example
+    ///
+    ///  ```
+    /// # use icon_utils::signer::remote::RemoteSigner;
+    /// # use sha3::{Digest, Sha3_256};
+    /// let signer = RemoteSigner::new("https://signer.internal/sign", "hxbe258ceb872e08851f1f59694dac2558708ece11".parse().unwrap())
+    ///     .auth(|body| vec![("X-Body-Digest".to_owned(), hex::encode(Sha3_256::digest(body)))]);
+    /// ```
+    pub fn auth(mut self, hook: impl Fn(&[u8]) -> Vec<(String, String)> + Send + Sync + 'static) -> Self {
+        self.auth = Some(Arc::new(hook));
+        self
+    }
+
+    /// Fails signing requests taking longer than `timeout`
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Returns the URL signing requests are posted to
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    fn error(&self, error: reqwest::Error) -> WalletError {
+        WalletError::Signer(format!("remote signer at {} failed: {}", self.url, error))
+    }
+}
+
+impl fmt::Debug for RemoteSigner {
+    // Headers are left out, they usually hold credentials
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RemoteSigner").field("url", &self.url).field("address", &format_args!("{}", self.address)).finish_non_exhaustive()
+    }
+}
+
+impl AsyncSigner for RemoteSigner {
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    async fn sign_hash(&self, hash: &[u8; 32]) -> Result<Signature, WalletError> {
+        let body = serde_json::to_vec(&SignRequest { address: self.address, hash: Hash256(*hash) })
+            .map_err(|e| WalletError::Signer(e.to_string()))?;
+        let mut builder = self.http.post(&self.url).header(reqwest::header::CONTENT_TYPE, "application/json");
+        for (name, value) in &self.headers {
+            builder = builder.header(name, value);
+        }
+        if let Some(auth) = &self.auth {
+            for (name, value) in auth(&body) {
+                builder = builder.header(name, value);
+            }
+        }
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+        let response = builder.body(body).send().await.map_err(|e| self.error(e))?;
+        let response = response.error_for_status().map_err(|e| self.error(e))?;
+        let signature = response
+            .json::<SignResponse>()
+            .await
+            .map_err(|e| WalletError::InvalidSignature(format!("unexpected response from {}: {}", self.url, e)))?
+            .signature;
+        let signer = signature.recover_address(hash)?;
+        if signer != self.address {
+            return Err(WalletError::SignerMismatch { from: self.address, signer });
+        }
+        Ok(signature)
+    }
+}
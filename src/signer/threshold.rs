@@ -0,0 +1,236 @@
+//! # Icon Threshold Signatures
+//! `threshold` is a module for collecting signatures from several [`Signer`]s over the same transaction hash,
+//! such as the keys of a custody policy that requires every party to approve a transaction.
+//!
+//! A [`Coordinator`] is created for a transaction and its participants, each participant signs the hash and sends
+//! back a [`PartialSignature`], and once enough have signed the coordinator produces an [`AggregateSignature`]
+//! that anyone can verify against the participant list.
+//!
+//! ICON accepts a single secp256k1 signature per transaction, so the aggregate is checked off-chain and doesn't
+//! replace the signature the transaction is sent with. Partials are plain ECDSA signatures kept side by side rather
+//! than combined into one key, which leaves room for MPC signers that produce a single signature later on.
+//! Approvals enforced on-chain need the multisig wallet SCORE, which `contracts::multisig` wraps with the `rpc` feature.
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::{Signature, Signer};
+use crate::serializer::{self, Transaction};
+use crate::types::{Address, Hash256};
+use crate::wallet::WalletError;
+
+#[derive(Error, Debug)]
+// Error struct for collecting threshold signatures
+pub enum ThresholdError {
+    #[error("A threshold signature needs at least one participant")]
+    NoParticipants,
+    #[error("Participant {0} is listed more than once")]
+    DuplicateParticipant(Address),
+    #[error("Threshold {threshold} must be between 1 and the {participants} participants")]
+    InvalidThreshold { threshold: usize, participants: usize },
+    #[error("{0} is not a participant")]
+    UnknownSigner(Address),
+    #[error("{0} has already signed")]
+    AlreadySigned(Address),
+    #[error("Signature claims to be from {claimed} but was signed by {signer}")]
+    SignerMismatch { claimed: Address, signer: Address },
+    #[error("{signed} of the {threshold} required signatures have been collected")]
+    Incomplete { signed: usize, threshold: usize },
+    #[error(transparent)]
+    Wallet(#[from] WalletError),
+}
+
+/// A participant's signature over the hash of a [`Coordinator`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PartialSignature {
+    pub signer: Address,
+    pub signature: Signature,
+}
+
+impl PartialSignature {
+    /// Signs `hash` with `signer`, for participants who sign away from the coordinator
+    pub fn sign(signer: &impl Signer, hash: &[u8; 32]) -> Result<Self, ThresholdError> {
+        Ok(PartialSignature { signer: signer.address(), signature: signer.sign_hash(hash)? })
+    }
+
+    /// Checks that the signature recovers to `signer` for `hash`
+    pub fn verify(&self, hash: &[u8; 32]) -> Result<(), ThresholdError> {
+        let signer = self.signature.recover_address(hash)?;
+        if signer != self.signer {
+            return Err(ThresholdError::SignerMismatch { claimed: self.signer, signer });
+        }
+        Ok(())
+    }
+}
+
+/// Collects partial signatures over one hash until `threshold` of the participants have signed
+///
+/// Every partial is verified as it's added, so only signatures from participants over the right hash are kept.
+/// All participants have to sign unless a lower threshold is set with [`Coordinator::with_threshold`].
+///
+/// # Example
+///
+///  ```
+/// # use icon_utils::{signer::{threshold::{Coordinator, PartialSignature}, Signer}, transaction::TransactionBuilder, wallet::Wallet};
+/// let alice = Wallet::new("68ee9ca94b71c42ba79375b8677e29a717fb8072bcb17cd5ee288f9b77bc2894").unwrap();
+/// let bob = Wallet::new("0f4f5b1e6c3a6da5f8b6f7b64c0b2c93d6f1d4a1d9e8c3b2a1f0e9d8c7b6a5f4").unwrap();
+/// let tx = TransactionBuilder::new()
+///     .from(alice.address())
+///     .to("hx0000000000000000000000000000000000000000")
+///     .value(1u64)
+///     .step_limit(100_000)
+///     .nid(1)
+///     .timestamp(1)
+///     .build()
+///     .unwrap();
+/// let mut coordinator = Coordinator::for_transaction(&tx, &[Signer::address(&alice), Signer::address(&bob)]).unwrap();
+/// coordinator.sign_with(&alice).unwrap();
+/// assert!(!coordinator.is_complete());
+///
+/// // Bob signs elsewhere and sends back his partial signature
+/// let partial = PartialSignature::sign(&bob, coordinator.hash()).unwrap();
+/// coordinator.add(partial).unwrap();
+///
+/// let aggregate = coordinator.finish().unwrap();
+/// aggregate.verify().unwrap();
+/// assert_eq!(aggregate.signers().len(), 2);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Coordinator {
+    hash: [u8; 32],
+    participants: Vec<Address>,
+    threshold: usize,
+    partials: BTreeMap<Address, Signature>,
+}
+
+impl Coordinator {
+    /// Creates a coordinator that needs every participant to sign `hash`
+    pub fn new(hash: [u8; 32], participants: &[Address]) -> Result<Self, ThresholdError> {
+        Coordinator::with_threshold(hash, participants, participants.len())
+    }
+
+    /// Creates a coordinator for the hash of a transaction that needs every participant to sign
+    pub fn for_transaction<T>(tx: &T, participants: &[Address]) -> Result<Self, ThresholdError>
+    where
+        T: Transaction,
+        T::Params: Serialize,
+    {
+        let hash = serializer::tx_hash(tx).map_err(WalletError::from)?;
+        Coordinator::new(hash, participants)
+    }
+
+    /// Creates a coordinator that needs `threshold` of the participants to sign `hash`
+    pub fn with_threshold(hash: [u8; 32], participants: &[Address], threshold: usize) -> Result<Self, ThresholdError> {
+        check_participants(participants, threshold)?;
+        Ok(Coordinator { hash, participants: participants.to_vec(), threshold, partials: BTreeMap::new() })
+    }
+
+    /// Returns the hash every participant signs
+    pub fn hash(&self) -> &[u8; 32] {
+        &self.hash
+    }
+
+    /// Returns the participants in the order they were given
+    pub fn participants(&self) -> &[Address] {
+        &self.participants
+    }
+
+    /// Returns how many signatures are needed
+    pub fn threshold(&self) -> usize {
+        self.threshold
+    }
+
+    /// Returns the participants who haven't signed yet
+    pub fn pending(&self) -> Vec<Address> {
+        self.participants.iter().filter(|address| !self.partials.contains_key(address)).copied().collect()
+    }
+
+    /// Returns true once `threshold` participants have signed
+    pub fn is_complete(&self) -> bool {
+        self.partials.len() >= self.threshold
+    }
+
+    /// Verifies and adds a participant's partial signature
+    pub fn add(&mut self, partial: PartialSignature) -> Result<(), ThresholdError> {
+        if !self.participants.contains(&partial.signer) {
+            return Err(ThresholdError::UnknownSigner(partial.signer));
+        }
+        if self.partials.contains_key(&partial.signer) {
+            return Err(ThresholdError::AlreadySigned(partial.signer));
+        }
+        partial.verify(&self.hash)?;
+        self.partials.insert(partial.signer, partial.signature);
+        Ok(())
+    }
+
+    /// Signs the hash with `signer` and adds its partial signature
+    pub fn sign_with(&mut self, signer: &impl Signer) -> Result<(), ThresholdError> {
+        let partial = PartialSignature::sign(signer, &self.hash)?;
+        self.add(partial)
+    }
+
+    /// Signs the hash with an async signer, such as a KMS or remote signer, and adds its partial signature
+    #[cfg(feature = "async")]
+    pub async fn sign_with_async(&mut self, signer: &(impl super::AsyncSigner + Sync)) -> Result<(), ThresholdError> {
+        let signature = signer.sign_hash(&self.hash).await?;
+        self.add(PartialSignature { signer: signer.address(), signature })
+    }
+
+    /// Returns the aggregate signature, failing until `threshold` participants have signed
+    pub fn finish(self) -> Result<AggregateSignature, ThresholdError> {
+        if !self.is_complete() {
+            return Err(ThresholdError::Incomplete { signed: self.partials.len(), threshold: self.threshold });
+        }
+        // Partials are kept in participant order, so the same signatures always aggregate to the same value
+        let signatures = self
+            .participants
+            .iter()
+            .filter_map(|address| self.partials.get(address).map(|signature| PartialSignature { signer: *address, signature: *signature }))
+            .collect();
+        Ok(AggregateSignature { hash: Hash256(self.hash), threshold: self.threshold, participants: self.participants, signatures })
+    }
+}
+
+/// The partial signatures of a completed [`Coordinator`], with the participants and threshold needed to verify them
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AggregateSignature {
+    pub hash: Hash256,
+    pub threshold: usize,
+    pub participants: Vec<Address>,
+    pub signatures: Vec<PartialSignature>,
+}
+
+impl AggregateSignature {
+    /// Checks that at least `threshold` distinct participants signed the hash
+    ///
+    /// An aggregate read from elsewhere should be verified before it's trusted, and its participants and threshold
+    /// compared against the policy they are meant to satisfy.
+    pub fn verify(&self) -> Result<(), ThresholdError> {
+        let mut coordinator = Coordinator::with_threshold(self.hash.0, &self.participants, self.threshold)?;
+        for partial in &self.signatures {
+            coordinator.add(*partial)?;
+        }
+        coordinator.finish().map(|_| ())
+    }
+
+    /// Returns the addresses that signed
+    pub fn signers(&self) -> Vec<Address> {
+        self.signatures.iter().map(|partial| partial.signer).collect()
+    }
+}
+
+fn check_participants(participants: &[Address], threshold: usize) -> Result<(), ThresholdError> {
+    if participants.is_empty() {
+        return Err(ThresholdError::NoParticipants);
+    }
+    for (i, address) in participants.iter().enumerate() {
+        if participants[..i].contains(address) {
+            return Err(ThresholdError::DuplicateParticipant(*address));
+        }
+    }
+    if threshold == 0 || threshold > participants.len() {
+        return Err(ThresholdError::InvalidThreshold { threshold, participants: participants.len() });
+    }
+    Ok(())
+}
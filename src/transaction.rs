@@ -0,0 +1,890 @@
+//! # Icon Transactions
+//! `transaction` is a module for building `icx_sendTransaction` transactions, ready to be signed by a wallet.
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use thiserror::Error;
+
+use crate::network::Network;
+use crate::params::IntoParam;
+#[cfg(feature = "rpc")]
+use crate::rpc::{IconClient, RpcError, Transport};
+use crate::serializer::Transaction;
+use crate::types::{Address, AddressError, HexBytes, Timestamp};
+use crate::units::Loop;
+
+#[cfg(feature = "deploy")]
+pub mod deploy;
+pub mod describe;
+pub mod nonce;
+pub mod v2;
+pub mod validate;
+
+#[cfg(feature = "deploy")]
+pub use deploy::DeployContent;
+pub use describe::TransactionSummary;
+pub use nonce::NonceManager;
+pub use v2::TransactionV2;
+pub use validate::{validate, validate_with, ValidationIssue, ValidationOptions};
+
+/// The transaction version produced by the builders
+pub const VERSION: u64 = 3;
+
+/// The base step cost of a transaction on mainnet
+pub const DEFAULT_STEPS: u64 = 100_000;
+
+/// The step price on mainnet, in loop
+pub const DEFAULT_STEP_PRICE: u64 = 12_500_000_000;
+
+/// The step cost per byte of transaction data on mainnet
+pub const INPUT_STEPS_PER_BYTE: u64 = 200;
+
+#[derive(Error, Debug)]
+// Error struct for building transactions
+pub enum BuildError {
+    #[error("Missing required field {0}")]
+    MissingField(&'static str),
+    #[error(transparent)]
+    InvalidAddress(#[from] AddressError),
+    #[error("Invalid SCORE content: {0}")]
+    InvalidContent(String),
+    #[error("Failed to read SCORE content")]
+    Io(#[from] std::io::Error),
+    #[cfg(feature = "rpc")]
+    #[error("Failed to estimate the step limit")]
+    Estimate(#[from] RpcError),
+}
+
+/// Returns the current time in microseconds since the unix epoch, as used for transaction timestamps
+///
+/// Nodes reject transactions whose timestamp is too far from their own clock, so timestamps should be taken just before sending.
+pub fn current_timestamp() -> u64 {
+    Timestamp::now().as_micros()
+}
+
+/// The params of an `icx_sendTransaction` request
+///
+/// Numbers are stored as integers and encoded as `0x` prefixed hex, as ICON expects.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IcxTransaction {
+    #[serde(with = "hex_u64")]
+    pub version: u64,
+    pub from: Address,
+    pub to: Address,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<Loop>,
+    #[serde(with = "hex_u64")]
+    pub step_limit: u64,
+    #[serde(with = "hex_u64")]
+    pub timestamp: u64,
+    #[serde(with = "hex_u64")]
+    pub nid: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "hex_u64_option")]
+    pub nonce: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+impl IcxTransaction {
+    /// Decodes the `dataType` and `data` of the transaction
+    ///
+    /// # Example
+    ///
+    ///  ```
+    /// # use icon_utils::transaction::{CallTransactionBuilder, TransactionData};
+    /// let tx = CallTransactionBuilder::new()
+    ///     .from("hxbe258ceb872e08851f1f59694dac2558708ece11")
+    ///     .to("cx88fd7df7ddff82f7cc735c871dc519838cb235bb")
+    ///     .method("transfer")
+    ///     .param("_value", 1u64)
+    ///     .step_limit(1_000_000)
+    ///     .nid(1)
+    ///     .build()
+    ///     .unwrap();
+    /// match tx.decode_data().unwrap() {
+    ///     TransactionData::Call { method, params } => assert_eq!((method.as_str(), &params["_value"]), ("transfer", &"0x1".into())),
+    ///     other => panic!("{:?}", other),
+    /// }
+    /// ```
+    pub fn decode_data(&self) -> Result<TransactionData, String> {
+        TransactionData::decode(self.data_type.as_deref(), self.data.clone())
+    }
+
+    /// Replaces the `dataType` and `data` of the transaction
+    pub fn set_data(&mut self, data: TransactionData) {
+        self.data_type = data.data_type().map(str::to_owned);
+        self.data = data.encode();
+    }
+}
+
+impl Transaction for IcxTransaction {
+    type Params = IcxTransaction;
+    fn params(&self) -> &Self::Params {
+        self
+    }
+    fn method(&self) -> &String {
+        static METHOD: OnceLock<String> = OnceLock::new();
+        METHOD.get_or_init(|| "icx_sendTransaction".to_owned())
+    }
+}
+
+/// Generates `estimate_step_limit` for a builder, which builds a copy of the transaction to estimate it
+macro_rules! step_estimation {
+    () => {
+        /// Sets the step limit to the node's `debug_estimateStep` estimate, plus `margin_percent`
+        #[cfg(feature = "rpc")]
+        pub fn estimate_step_limit<T: Transport>(self, client: &IconClient<T>, margin_percent: u64) -> Result<Self, BuildError> {
+            let steps = client.estimate_step(&self.clone().step_limit(0).build()?)?;
+            Ok(self.step_limit(with_margin(steps, margin_percent)))
+        }
+
+        /// Sets the step limit to the node's `debug_estimateStep` estimate, plus `margin_percent`
+        #[cfg(feature = "async")]
+        pub async fn estimate_step_limit_async<T: crate::rpc::transport::AsyncTransport>(
+            self,
+            client: &crate::rpc::async_client::IconClient<T>,
+            margin_percent: u64,
+        ) -> Result<Self, BuildError> {
+            let steps = client.estimate_step(&self.clone().step_limit(0).build()?).await?;
+            Ok(self.step_limit(with_margin(steps, margin_percent)))
+        }
+    };
+}
+
+#[cfg(feature = "rpc")]
+pub(crate) fn with_margin(steps: u64, margin_percent: u64) -> u64 {
+    steps.saturating_add(steps.saturating_mul(margin_percent) / 100)
+}
+
+/// Generates setters for the shared fields on builders that wrap a `TransactionBuilder` in `base`
+macro_rules! base_setters {
+    () => {
+        /// Sets the address sending the transaction
+        pub fn from(mut self, from: impl fmt::Display) -> Self {
+            self.base = self.base.from(from);
+            self
+        }
+
+        /// Sets the amount of ICX sent with the transaction
+        pub fn value(mut self, value: impl Into<Loop>) -> Self {
+            self.base = self.base.value(value);
+            self
+        }
+
+        /// Sets the maximum number of steps the transaction can use
+        pub fn step_limit(mut self, step_limit: u64) -> Self {
+            self.base = self.base.step_limit(step_limit);
+            self
+        }
+
+        /// Sets the timestamp of the transaction, in microseconds since the unix epoch, defaults to the time it is built
+        pub fn timestamp(mut self, timestamp: u64) -> Self {
+            self.base = self.base.timestamp(timestamp);
+            self
+        }
+
+        /// Sets the network id the transaction is valid on
+        pub fn nid(mut self, nid: u64) -> Self {
+            self.base = self.base.nid(nid);
+            self
+        }
+
+        /// Sets the network id from a network preset
+        pub fn network(mut self, network: &Network) -> Self {
+            self.base = self.base.network(network);
+            self
+        }
+
+        /// Sets the nonce of the transaction
+        pub fn nonce(mut self, nonce: u64) -> Self {
+            self.base = self.base.nonce(nonce);
+            self
+        }
+
+        /// Sets the transaction version, defaults to 3
+        pub fn version(mut self, version: u64) -> Self {
+            self.base = self.base.version(version);
+            self
+        }
+    };
+}
+
+/// Builder for plain ICX transfers, and the fields shared by every transaction
+///
+/// # Example
+///
+///  ```
+/// # use icon_utils::{network::Network, transaction::TransactionBuilder, units::Icx};
+/// let tx = TransactionBuilder::new()
+///     .from("hxbe258ceb872e08851f1f59694dac2558708ece11")
+///     .to("hx5bfdb090f43a808005ffc27c25b213145e80b7cd")
+///     .value(Icx::new(1))
+///     .step_limit(100_000)
+///     .network(&Network::Mainnet)
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TransactionBuilder {
+    version: Option<u64>,
+    from: Option<String>,
+    to: Option<String>,
+    value: Option<Loop>,
+    step_limit: Option<u64>,
+    timestamp: Option<u64>,
+    nid: Option<u64>,
+    nonce: Option<u64>,
+}
+
+impl TransactionBuilder {
+    /// Creates an empty builder
+    pub fn new() -> Self {
+        TransactionBuilder::default()
+    }
+
+    /// Sets the address sending the transaction, either an `Address` or a string that is validated when building
+    pub fn from(mut self, from: impl fmt::Display) -> Self {
+        self.from = Some(from.to_string());
+        self
+    }
+
+    /// Sets the address receiving the transaction, either an `Address` or a string that is validated when building
+    pub fn to(mut self, to: impl fmt::Display) -> Self {
+        self.to = Some(to.to_string());
+        self
+    }
+
+    /// Sets the amount of ICX sent with the transaction
+    pub fn value(mut self, value: impl Into<Loop>) -> Self {
+        self.value = Some(value.into());
+        self
+    }
+
+    /// Sets the maximum number of steps the transaction can use
+    pub fn step_limit(mut self, step_limit: u64) -> Self {
+        self.step_limit = Some(step_limit);
+        self
+    }
+
+    /// Sets the timestamp of the transaction, in microseconds since the unix epoch
+    ///
+    /// Defaults to the time the transaction is built. Other times can be converted with [`Timestamp`].
+    pub fn timestamp(mut self, timestamp: u64) -> Self {
+        self.timestamp = Some(timestamp);
+        self
+    }
+
+    /// Sets the network id the transaction is valid on
+    pub fn nid(mut self, nid: u64) -> Self {
+        self.nid = Some(nid);
+        self
+    }
+
+    /// Sets the network id from a network preset
+    pub fn network(self, network: &Network) -> Self {
+        self.nid(network.nid())
+    }
+
+    /// Sets the nonce of the transaction
+    pub fn nonce(mut self, nonce: u64) -> Self {
+        self.nonce = Some(nonce);
+        self
+    }
+
+    /// Sets the transaction version, defaults to 3
+    pub fn version(mut self, version: u64) -> Self {
+        self.version = Some(version);
+        self
+    }
+
+    step_estimation!();
+
+    /// Builds a plain transfer transaction
+    pub fn build(self) -> Result<IcxTransaction, BuildError> {
+        self.build_with_data(None, None)
+    }
+
+    /// Builds the transaction with the given dataType and data
+    pub(crate) fn build_with_data(self, data_type: Option<&str>, data: Option<Value>) -> Result<IcxTransaction, BuildError> {
+        Ok(IcxTransaction {
+            version: self.version.unwrap_or(VERSION),
+            from: self.from.ok_or(BuildError::MissingField("from"))?.parse()?,
+            to: self.to.ok_or(BuildError::MissingField("to"))?.parse()?,
+            value: self.value,
+            step_limit: self.step_limit.ok_or(BuildError::MissingField("stepLimit"))?,
+            timestamp: self.timestamp.unwrap_or_else(current_timestamp),
+            nid: self.nid.ok_or(BuildError::MissingField("nid"))?,
+            nonce: self.nonce,
+            data_type: data_type.map(str::to_owned),
+            data,
+        })
+    }
+}
+
+/// Builder for SCORE method calls (`dataType: call`)
+///
+/// Params are encoded as ICON strings, hex for integers and `0x1`/`0x0` for booleans.
+///
+/// # Example
+///
+///  ```
+/// # use icon_utils::transaction::CallTransactionBuilder;
+/// let tx = CallTransactionBuilder::new()
+///     .from("hxbe258ceb872e08851f1f59694dac2558708ece11")
+///     .to("cx88fd7df7ddff82f7cc735c871dc519838cb235bb")
+///     .method("transfer")
+///     .param("_to", "hx5bfdb090f43a808005ffc27c25b213145e80b7cd")
+///     .param("_value", 1_000_000_000_000_000_000u128)
+///     .step_limit(1_000_000)
+///     .nid(1)
+///     .timestamp(1_700_000_000_000_000)
+///     .build()
+///     .unwrap();
+/// assert_eq!(tx.data.unwrap()["params"]["_value"], "0xde0b6b3a7640000");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CallTransactionBuilder {
+    base: TransactionBuilder,
+    method: Option<String>,
+    params: Map<String, Value>,
+}
+
+impl CallTransactionBuilder {
+    /// Creates an empty builder
+    pub fn new() -> Self {
+        CallTransactionBuilder::default()
+    }
+
+    base_setters!();
+
+    step_estimation!();
+
+    /// Sets the address of the SCORE being called
+    pub fn to(mut self, to: impl fmt::Display) -> Self {
+        self.base = self.base.to(to);
+        self
+    }
+
+    /// Sets the SCORE method to call
+    pub fn method(mut self, method: &str) -> Self {
+        self.method = Some(method.to_owned());
+        self
+    }
+
+    /// Adds a param to the call
+    pub fn param(mut self, name: &str, value: impl IntoParam) -> Self {
+        self.params.insert(name.to_owned(), value.into_param());
+        self
+    }
+
+    /// Builds the call transaction
+    pub fn build(self) -> Result<IcxTransaction, BuildError> {
+        let mut data = json!({ "method": self.method.ok_or(BuildError::MissingField("method"))? });
+        if !self.params.is_empty() {
+            data["params"] = Value::Object(self.params);
+        }
+        self.base.build_with_data(Some("call"), Some(data))
+    }
+}
+
+/// Builder for message transactions (`dataType: message`)
+///
+/// If no step limit is set, the estimate from `estimate_steps` is used.
+///
+/// # Example
+///
+///  ```
+/// # use icon_utils::transaction::MessageTransactionBuilder;
+/// let tx = MessageTransactionBuilder::new()
+///     .from("hxbe258ceb872e08851f1f59694dac2558708ece11")
+///     .to("hx5bfdb090f43a808005ffc27c25b213145e80b7cd")
+///     .message("hello")
+///     .nid(1)
+///     .timestamp(1_700_000_000_000_000)
+///     .build()
+///     .unwrap();
+/// assert_eq!(tx.data.unwrap(), "0x68656c6c6f");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MessageTransactionBuilder {
+    base: TransactionBuilder,
+    message: Vec<u8>,
+}
+
+impl MessageTransactionBuilder {
+    /// Creates an empty builder
+    pub fn new() -> Self {
+        MessageTransactionBuilder::default()
+    }
+
+    base_setters!();
+
+    step_estimation!();
+
+    /// Sets the address receiving the message
+    pub fn to(mut self, to: impl fmt::Display) -> Self {
+        self.base = self.base.to(to);
+        self
+    }
+
+    /// Sets the message to a UTF-8 string
+    pub fn message(self, message: &str) -> Self {
+        self.bytes(message.as_bytes())
+    }
+
+    /// Sets the message to raw bytes
+    pub fn bytes(mut self, bytes: impl Into<Vec<u8>>) -> Self {
+        self.message = bytes.into();
+        self
+    }
+
+    /// Estimates the steps used by the message, from the length of its hex encoded data
+    pub fn estimate_steps(&self) -> u64 {
+        estimate_message_steps(self.message.len())
+    }
+
+    /// Builds the message transaction
+    pub fn build(self) -> Result<IcxTransaction, BuildError> {
+        let mut base = self.base;
+        if base.step_limit.is_none() {
+            base = base.step_limit(estimate_message_steps(self.message.len()));
+        }
+        base.build_with_data(Some("message"), Some(self.message.as_slice().into_param()))
+    }
+}
+
+/// Estimates the steps used by a message transaction carrying `len` bytes, using the mainnet step costs
+///
+/// The data is sent as `0x` prefixed hex, so every byte of the message costs two bytes of input.
+pub fn estimate_message_steps(len: usize) -> u64 {
+    let encoded = 2 + 2 * len as u64;
+    DEFAULT_STEPS + INPUT_STEPS_PER_BYTE * encoded
+}
+
+/// The content type of a SCORE package
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentType {
+    /// A java SCORE, packaged as a `.jar`
+    Java,
+    /// A python SCORE, packaged as a `.zip`
+    Zip,
+}
+
+impl ContentType {
+    /// Returns the `contentType` value for the package
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ContentType::Java => "application/java",
+            ContentType::Zip => "application/zip",
+        }
+    }
+
+    /// Parses a `contentType` value
+    pub fn from_mime(mime: &str) -> Option<Self> {
+        match mime {
+            "application/java" => Some(ContentType::Java),
+            "application/zip" => Some(ContentType::Zip),
+            _ => None,
+        }
+    }
+
+    /// Guesses the content type from a file extension
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()?.to_ascii_lowercase().as_str() {
+            "jar" => Some(ContentType::Java),
+            "zip" => Some(ContentType::Zip),
+            _ => None,
+        }
+    }
+}
+
+/// Builder for SCORE deployments (`dataType: deploy`)
+///
+/// Deploys install a new SCORE by default, use `update` to target an existing SCORE.
+///
+/// # Example
+///
+///  ```no_run
+/// # use icon_utils::transaction::DeployTransactionBuilder;
+/// let tx = DeployTransactionBuilder::new()
+///     .from("hxbe258ceb872e08851f1f59694dac2558708ece11")
+///     .content_file("build/libs/token-optimized.jar")
+///     .unwrap()
+///     .param("_name", "Token")
+///     .step_limit(3_000_000_000)
+///     .nid(1)
+///     .timestamp(1_700_000_000_000_000)
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct DeployTransactionBuilder {
+    base: TransactionBuilder,
+    content: Option<(ContentType, Vec<u8>)>,
+    params: Map<String, Value>,
+}
+
+impl DeployTransactionBuilder {
+    /// Creates an empty builder, installing a new SCORE
+    pub fn new() -> Self {
+        DeployTransactionBuilder::default()
+    }
+
+    base_setters!();
+
+    step_estimation!();
+
+    /// Installs a new SCORE, this is the default
+    pub fn install(mut self) -> Self {
+        self.base = self.base.to(Address::SYSTEM_SCORE);
+        self
+    }
+
+    /// Updates the SCORE at `score` instead of installing a new one
+    pub fn update(mut self, score: impl fmt::Display) -> Self {
+        self.base = self.base.to(score);
+        self
+    }
+
+    /// Sets the SCORE package to deploy
+    pub fn content(mut self, content_type: ContentType, content: impl Into<Vec<u8>>) -> Self {
+        self.content = Some((content_type, content.into()));
+        self
+    }
+
+    /// Reads the SCORE package from a `.jar` or `.zip` file
+    pub fn content_file(self, path: impl AsRef<Path>) -> Result<Self, BuildError> {
+        let path = path.as_ref();
+        let content_type = ContentType::from_path(path)
+            .ok_or_else(|| BuildError::InvalidContent(format!("{} is not a .jar or .zip file", path.display())))?;
+        let content = fs::read(path)?;
+        Ok(self.content(content_type, content))
+    }
+
+    /// Sets a SCORE package packaged with [`DeployContent`]
+    #[cfg(feature = "deploy")]
+    pub fn deploy_content(self, content: DeployContent) -> Self {
+        let (content_type, content) = content.into_parts();
+        self.content(content_type, content)
+    }
+
+    /// Adds a param passed to the SCORE's install or update method
+    pub fn param(mut self, name: &str, value: impl IntoParam) -> Self {
+        self.params.insert(name.to_owned(), value.into_param());
+        self
+    }
+
+    /// Builds the deploy transaction
+    pub fn build(self) -> Result<IcxTransaction, BuildError> {
+        let (content_type, content) = self.content.ok_or(BuildError::MissingField("content"))?;
+        if content.is_empty() {
+            return Err(BuildError::InvalidContent("content is empty".to_owned()));
+        }
+        let mut data = json!({
+            "contentType": content_type.as_str(),
+            "content": content.as_slice().into_param(),
+        });
+        if !self.params.is_empty() {
+            data["params"] = Value::Object(self.params);
+        }
+        let mut base = self.base;
+        if base.to.is_none() {
+            base = base.to(Address::SYSTEM_SCORE);
+        }
+        base.build_with_data(Some("deploy"), Some(data))
+    }
+}
+
+/// An action on a SCORE's fee sharing deposit
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum DepositAction {
+    Add,
+    Withdraw { id: Option<String>, amount: Option<Loop> },
+}
+
+/// Builder for fee sharing deposits (`dataType: deposit`)
+///
+/// # Example
+///
+///  ```
+/// # use icon_utils::{transaction::DepositTransactionBuilder, units::Icx};
+/// let tx = DepositTransactionBuilder::new()
+///     .from("hxbe258ceb872e08851f1f59694dac2558708ece11")
+///     .to("cx88fd7df7ddff82f7cc735c871dc519838cb235bb")
+///     .add(Icx::new(5000))
+///     .step_limit(200_000)
+///     .nid(1)
+///     .timestamp(1_700_000_000_000_000)
+///     .build()
+///     .unwrap();
+/// assert_eq!(tx.data.unwrap()["action"], "add");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct DepositTransactionBuilder {
+    base: TransactionBuilder,
+    action: Option<DepositAction>,
+}
+
+impl DepositTransactionBuilder {
+    /// Creates an empty builder
+    pub fn new() -> Self {
+        DepositTransactionBuilder::default()
+    }
+
+    base_setters!();
+
+    step_estimation!();
+
+    /// Sets the address of the SCORE whose deposit is managed
+    pub fn to(mut self, to: impl fmt::Display) -> Self {
+        self.base = self.base.to(to);
+        self
+    }
+
+    /// Adds `value` to the SCORE's deposit
+    #[allow(clippy::should_implement_trait)]
+    pub fn add(mut self, value: impl Into<Loop>) -> Self {
+        self.base = self.base.value(value);
+        self.action = Some(DepositAction::Add);
+        self
+    }
+
+    /// Withdraws the whole deposit
+    pub fn withdraw(mut self) -> Self {
+        self.action = Some(DepositAction::Withdraw { id: None, amount: None });
+        self
+    }
+
+    /// Withdraws the deposit with the given `0x` prefixed id
+    pub fn withdraw_id(mut self, id: &str) -> Self {
+        self.action = Some(DepositAction::Withdraw { id: Some(id.to_owned()), amount: None });
+        self
+    }
+
+    /// Withdraws `amount` from the deposit
+    pub fn withdraw_amount(mut self, amount: impl Into<Loop>) -> Self {
+        self.action = Some(DepositAction::Withdraw { id: None, amount: Some(amount.into()) });
+        self
+    }
+
+    /// Builds the deposit transaction
+    pub fn build(self) -> Result<IcxTransaction, BuildError> {
+        let data = match self.action.ok_or(BuildError::MissingField("action"))? {
+            DepositAction::Add => {
+                if self.base.value.is_none() {
+                    return Err(BuildError::MissingField("value"));
+                }
+                json!({ "action": "add" })
+            }
+            DepositAction::Withdraw { id, amount } => {
+                let mut data = json!({ "action": "withdraw" });
+                if let Some(id) = id {
+                    data["id"] = Value::String(id);
+                }
+                if let Some(amount) = amount {
+                    data["amount"] = amount.into_param();
+                }
+                data
+            }
+        };
+        self.base.build_with_data(Some("deposit"), Some(data))
+    }
+}
+
+/// The payload of a transaction, decoded according to its `dataType`
+///
+/// It serializes as the `dataType` and `data` fields, so it can be flattened into other structs.
+///
+/// # Example
+///
+///  ```
+/// # use icon_utils::{transaction::{IcxTransaction, TransactionData, TransactionBuilder}, types::HexBytes};
+/// let mut tx = TransactionBuilder::new()
+///     .from("hxbe258ceb872e08851f1f59694dac2558708ece11")
+///     .to("hx5bfdb090f43a808005ffc27c25b213145e80b7cd")
+///     .step_limit(100_000)
+///     .nid(1)
+///     .timestamp(1)
+///     .build()
+///     .unwrap();
+/// tx.set_data(TransactionData::Message(HexBytes(b"hello".to_vec())));
+/// let json = serde_json::to_value(&tx).unwrap();
+/// assert_eq!((json["dataType"].as_str(), json["data"].as_str()), (Some("message"), Some("0x68656c6c6f")));
+///
+/// let restored: IcxTransaction = serde_json::from_value(json).unwrap();
+/// assert_eq!(restored.decode_data().unwrap(), TransactionData::Message(HexBytes(b"hello".to_vec())));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum TransactionData {
+    /// A plain ICX transfer, without data
+    Transfer,
+    /// A SCORE method call
+    Call { method: String, params: Map<String, Value> },
+    /// A SCORE install or update, `content_type` is None for unknown content types
+    Deploy { content_type: Option<ContentType>, content: HexBytes, params: Map<String, Value> },
+    /// A message, as raw bytes
+    Message(HexBytes),
+    /// A fee sharing deposit action
+    Deposit { action: String, id: Option<String>, amount: Option<Loop> },
+    /// The base transaction at the start of each block, issuing rewards
+    Base(Value),
+    /// Any other data type, left undecoded
+    Other { data_type: String, data: Option<Value> },
+}
+
+impl TransactionData {
+    /// Decodes the `data` of a transaction according to its `dataType`
+    pub fn decode(data_type: Option<&str>, data: Option<Value>) -> Result<Self, String> {
+        let field = |data: &Value, name: &str| data.get(name).and_then(Value::as_str).map(str::to_owned);
+        let params = |data: &Value| data.get("params").and_then(Value::as_object).cloned().unwrap_or_default();
+        let data_type = match data_type {
+            Some(data_type) => data_type,
+            None => return Ok(TransactionData::Transfer),
+        };
+        let value = data.clone().unwrap_or(Value::Null);
+        Ok(match data_type {
+            "call" => TransactionData::Call {
+                method: field(&value, "method").ok_or("call data is missing a method")?,
+                params: params(&value),
+            },
+            "deploy" => {
+                let content = field(&value, "content").ok_or("deploy data is missing content")?;
+                TransactionData::Deploy {
+                    content_type: field(&value, "contentType").as_deref().and_then(ContentType::from_mime),
+                    content: content.parse().map_err(|e| format!("invalid deploy content: {}", e))?,
+                    params: params(&value),
+                }
+            }
+            "message" => {
+                let message = value.as_str().unwrap_or_default();
+                // Old messages were sometimes sent as plain text rather than hex
+                TransactionData::Message(message.parse().unwrap_or_else(|_| HexBytes(message.as_bytes().to_vec())))
+            }
+            "deposit" => TransactionData::Deposit {
+                action: field(&value, "action").ok_or("deposit data is missing an action")?,
+                id: field(&value, "id"),
+                amount: field(&value, "amount").map(|amount| Loop::from_hex(&amount)).transpose().map_err(|e| e.to_string())?,
+            },
+            "base" => TransactionData::Base(value),
+            other => TransactionData::Other { data_type: other.to_owned(), data },
+        })
+    }
+
+    /// Returns the `dataType` of the data, None for transfers
+    pub fn data_type(&self) -> Option<&str> {
+        match self {
+            TransactionData::Transfer => None,
+            TransactionData::Call { .. } => Some("call"),
+            TransactionData::Deploy { .. } => Some("deploy"),
+            TransactionData::Message(_) => Some("message"),
+            TransactionData::Deposit { .. } => Some("deposit"),
+            TransactionData::Base(_) => Some("base"),
+            TransactionData::Other { data_type, .. } => Some(data_type),
+        }
+    }
+
+    /// Encodes the data as the `data` field of a transaction, the same way the builders do
+    ///
+    /// Deploys of an unknown content type are encoded as `application/java`.
+    pub fn encode(&self) -> Option<Value> {
+        let with_params = |mut data: Value, params: &Map<String, Value>| {
+            if !params.is_empty() {
+                data["params"] = Value::Object(params.clone());
+            }
+            data
+        };
+        match self {
+            TransactionData::Transfer => None,
+            TransactionData::Call { method, params } => Some(with_params(json!({ "method": method }), params)),
+            TransactionData::Deploy { content_type, content, params } => Some(with_params(
+                json!({
+                    "contentType": content_type.unwrap_or(ContentType::Java).as_str(),
+                    "content": content.to_string(),
+                }),
+                params,
+            )),
+            TransactionData::Message(message) => Some(Value::String(message.to_string())),
+            TransactionData::Deposit { action, id, amount } => {
+                let mut data = json!({ "action": action });
+                if let Some(id) = id {
+                    data["id"] = Value::String(id.clone());
+                }
+                if let Some(amount) = amount {
+                    data["amount"] = amount.clone().into_param();
+                }
+                Some(data)
+            }
+            TransactionData::Base(data) => Some(data.clone()),
+            TransactionData::Other { data, .. } => data.clone(),
+        }
+    }
+}
+
+/// Serializes as the `dataType` and `data` fields of a transaction, so it can be flattened into a transaction struct
+impl Serialize for TransactionData {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeMap;
+        let mut map = serializer.serialize_map(None)?;
+        if let Some(data_type) = self.data_type() {
+            map.serialize_entry("dataType", data_type)?;
+        }
+        if let Some(data) = self.encode() {
+            map.serialize_entry("data", &data)?;
+        }
+        map.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for TransactionData {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Fields {
+            data_type: Option<String>,
+            data: Option<Value>,
+        }
+        let fields = Fields::deserialize(deserializer)?;
+        TransactionData::decode(fields.data_type.as_deref(), fields.data).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Serde helpers for integers encoded as `0x` prefixed hex strings
+pub(crate) mod hex_u64 {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&crate::icon_hex::to_hex_no_pad(u128::from(*value)))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+        use serde::de::Error;
+        let value = String::deserialize(deserializer)?;
+        let parsed = crate::icon_hex::from_hex_int(&value).map_err(Error::custom)?;
+        u64::try_from(parsed).map_err(|_| Error::custom(format!("{} doesn't fit in a u64", value)))
+    }
+}
+
+/// Serde helpers for optional integers encoded as `0x` prefixed hex strings
+pub(crate) mod hex_u64_option {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &Option<u64>, serializer: S) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(value) => super::hex_u64::serialize(value, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<u64>, D::Error> {
+        #[derive(Deserialize)]
+        struct Wrapper(#[serde(with = "super::hex_u64")] u64);
+        Option::<Wrapper>::deserialize(deserializer).map(|v| v.map(|Wrapper(v)| v))
+    }
+}
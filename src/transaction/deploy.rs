@@ -0,0 +1,134 @@
+//! Packaging of java and python SCOREs into the content of deploy transactions.
+use std::fs;
+use std::io::{self, Cursor, Read, Write};
+use std::path::{Path, PathBuf};
+
+use sha3::{Digest, Sha3_256};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, DateTime, ZipArchive, ZipWriter};
+
+use super::{BuildError, ContentType};
+use crate::types::Hash256;
+
+/// A checked SCORE package, ready to be set as the content of a [`DeployTransactionBuilder`](super::DeployTransactionBuilder)
+///
+/// # Example
+///
+///  ```no_run
+/// # use icon_utils::transaction::{DeployContent, DeployTransactionBuilder};
+/// let content = DeployContent::from_python_dir("my_token").unwrap();
+/// println!("code hash {}", content.hash());
+/// let tx = DeployTransactionBuilder::new()
+///     .from("hxbe258ceb872e08851f1f59694dac2558708ece11")
+///     .deploy_content(content)
+///     .step_limit(3_000_000_000)
+///     .nid(1)
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeployContent {
+    content_type: ContentType,
+    content: Vec<u8>,
+}
+
+impl DeployContent {
+    /// Reads a java SCORE from an optimized `.jar`, checking that it names the SCORE class in its manifest
+    pub fn from_java_jar(path: impl AsRef<Path>) -> Result<Self, BuildError> {
+        let content = fs::read(path)?;
+        let mut archive = ZipArchive::new(Cursor::new(&content)).map_err(invalid)?;
+        let mut manifest = String::new();
+        archive
+            .by_name("META-INF/MANIFEST.MF")
+            .map_err(|_| BuildError::InvalidContent("jar has no META-INF/MANIFEST.MF, build it with optimizedJar".to_owned()))?
+            .read_to_string(&mut manifest)?;
+        if !manifest.lines().any(|line| line.starts_with("Main-Class:")) {
+            return Err(BuildError::InvalidContent("jar manifest has no Main-Class, build it with optimizedJar".to_owned()));
+        }
+        if !archive.file_names().any(|name| name.ends_with(".class")) {
+            return Err(BuildError::InvalidContent("jar has no classes".to_owned()));
+        }
+        Ok(DeployContent { content_type: ContentType::Java, content })
+    }
+
+    /// Zips a python SCORE directory, checking that its `package.json` names the main module and SCORE class
+    ///
+    /// Files are added under the directory's name, leaving out hidden files and `__pycache__`. Entries are sorted
+    /// and carry no timestamps, so zipping the same files always gives the same content and hash.
+    pub fn from_python_dir(path: impl AsRef<Path>) -> Result<Self, BuildError> {
+        let dir = fs::canonicalize(path)?;
+        let package = match fs::read(dir.join("package.json")) {
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                return Err(BuildError::InvalidContent(format!("{} has no package.json", dir.display())))
+            }
+            package => package?,
+        };
+        let package: serde_json::Value = serde_json::from_slice(&package)
+            .map_err(|e| BuildError::InvalidContent(format!("invalid package.json: {}", e)))?;
+        if package.get("main_score").and_then(|v| v.as_str()).is_none() {
+            return Err(BuildError::InvalidContent("package.json has no main_score".to_owned()));
+        }
+        if package.get("main_module").or_else(|| package.get("main_file")).and_then(|v| v.as_str()).is_none() {
+            return Err(BuildError::InvalidContent("package.json has no main_module".to_owned()));
+        }
+        let root = dir.file_name().and_then(|name| name.to_str()).unwrap_or("score").to_owned();
+        let mut files = Vec::new();
+        collect_files(&dir, &mut files)?;
+        files.sort();
+
+        let options = SimpleFileOptions::default()
+            .compression_method(CompressionMethod::Deflated)
+            .last_modified_time(DateTime::default());
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        for file in files {
+            let relative = file.strip_prefix(&dir).expect("collected files are inside the directory");
+            let name = relative.components().fold(root.clone(), |name, part| format!("{}/{}", name, part.as_os_str().to_string_lossy()));
+            writer.start_file(name, options).map_err(invalid)?;
+            writer.write_all(&fs::read(&file)?)?;
+        }
+        let content = writer.finish().map_err(invalid)?.into_inner();
+        Ok(DeployContent { content_type: ContentType::Zip, content })
+    }
+
+    /// Returns the `contentType` of the package
+    pub fn content_type(&self) -> ContentType {
+        self.content_type
+    }
+
+    /// Returns the package bytes
+    pub fn content(&self) -> &[u8] {
+        &self.content
+    }
+
+    /// Returns the SHA3-256 hash of the package, which nodes report as the code hash of the deployed SCORE
+    pub fn hash(&self) -> Hash256 {
+        Hash256(Sha3_256::digest(&self.content).into())
+    }
+
+    /// Returns the content type and package bytes
+    pub fn into_parts(self) -> (ContentType, Vec<u8>) {
+        (self.content_type, self.content)
+    }
+}
+
+/// Adds the files under `dir` to `files`, skipping hidden entries and python caches
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), BuildError> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if name.starts_with('.') || name == "__pycache__" {
+            continue;
+        }
+        if entry.file_type()?.is_dir() {
+            collect_files(&entry.path(), files)?;
+        } else {
+            files.push(entry.path());
+        }
+    }
+    Ok(())
+}
+
+fn invalid(error: zip::result::ZipError) -> BuildError {
+    BuildError::InvalidContent(error.to_string())
+}
@@ -0,0 +1,207 @@
+//! Human readable summaries of transactions, for confirmation prompts and review screens before signing.
+use std::fmt;
+
+use serde_json::{Map, Value};
+
+use super::{IcxTransaction, TransactionData, DEFAULT_STEP_PRICE};
+use crate::network::Network;
+use crate::types::{Address, Hash256, Timestamp};
+use crate::units::Loop;
+
+/// What a transaction does and what it can cost, for reviewing it before it's signed or sent
+///
+/// The summary is displayed as one labelled field per line, with the method and params of calls decoded.
+///
+/// # Example
+///
+///  ```
+/// # use icon_utils::transaction::CallTransactionBuilder;
+/// let tx = CallTransactionBuilder::new()
+///     .from("hxbe258ceb872e08851f1f59694dac2558708ece11")
+///     .to("cx88fd7df7ddff82f7cc735c871dc519838cb235bb")
+///     .method("transfer")
+///     .param("_to", "hx5bfdb090f43a808005ffc27c25b213145e80b7cd")
+///     .param("_value", 1u64)
+///     .step_limit(1_000_000)
+///     .nid(1)
+///     .timestamp(1)
+///     .build()
+///     .unwrap();
+/// let summary = tx.describe();
+/// assert_eq!(summary.max_fee.format_icx(), "0.0125");
+/// let lines: Vec<String> = summary.to_string().lines().map(str::to_owned).collect();
+/// assert_eq!(lines, [
+///     "From:      hxbe258ceb872e08851f1f59694dac2558708ece11",
+///     "To:        cx88fd7df7ddff82f7cc735c871dc519838cb235bb",
+///     "Value:     0 ICX",
+///     "Type:      call",
+///     "Method:    transfer",
+///     "Params:",
+///     "  _to:     hx5bfdb090f43a808005ffc27c25b213145e80b7cd",
+///     "  _value:  0x1",
+///     "Max fee:   0.0125 ICX (1000000 steps)",
+///     "Network:   Mainnet (0x1)",
+///     "Timestamp: 0x1",
+/// ]);
+/// ```
+///
+/// Strings with control characters are escaped and quoted, so a param can't add lines or terminal escapes to the
+/// summary.
+///
+///  ```
+/// # use icon_utils::transaction::CallTransactionBuilder;
+/// let tx = CallTransactionBuilder::new()
+///     .from("hxbe258ceb872e08851f1f59694dac2558708ece11")
+///     .to("cx88fd7df7ddff82f7cc735c871dc519838cb235bb")
+///     .method("transfer")
+///     .param("_memo", "hi\nValue:     0 ICX\x1b[2K")
+///     .step_limit(1_000_000)
+///     .nid(1)
+///     .timestamp(1)
+///     .build()
+///     .unwrap();
+/// let summary = tx.describe().to_string();
+/// assert_eq!(summary.lines().filter(|line| line.starts_with("Value:")).count(), 1);
+/// assert!(summary.contains(r#"  _memo:   "hi\nValue:     0 ICX\u{1b}[2K""#));
+/// assert!(!summary.contains('\x1b'));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransactionSummary {
+    pub from: Address,
+    pub to: Address,
+    pub value: Loop,
+    /// The decoded data, [`TransactionData::Other`] if it doesn't match its `dataType`
+    pub data: TransactionData,
+    pub step_limit: u64,
+    /// The step limit multiplied by the step price
+    pub max_fee: Loop,
+    pub nid: u64,
+    pub nonce: Option<u64>,
+    pub timestamp: Timestamp,
+    /// The hash of signed transactions
+    pub hash: Option<Hash256>,
+}
+
+impl IcxTransaction {
+    /// Summarizes the transaction, with the max fee at the mainnet step price
+    pub fn describe(&self) -> TransactionSummary {
+        self.describe_with_step_price(&Loop::from(DEFAULT_STEP_PRICE))
+    }
+
+    /// Summarizes the transaction, with the max fee at `step_price`, such as the one returned by governance
+    pub fn describe_with_step_price(&self, step_price: &Loop) -> TransactionSummary {
+        let data = self.decode_data().unwrap_or_else(|_| TransactionData::Other {
+            data_type: self.data_type.clone().unwrap_or_default(),
+            data: self.data.clone(),
+        });
+        TransactionSummary {
+            from: self.from,
+            to: self.to,
+            value: self.value.clone().unwrap_or_default(),
+            data,
+            step_limit: self.step_limit,
+            max_fee: step_price.clone() * self.step_limit,
+            nid: self.nid,
+            nonce: self.nonce,
+            timestamp: Timestamp(self.timestamp),
+            hash: None,
+        }
+    }
+}
+
+impl fmt::Display for TransactionSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(hash) = &self.hash {
+            field(f, "Hash", hash)?;
+        }
+        field(f, "From", self.from)?;
+        field(f, "To", self.to)?;
+        field(f, "Value", format_args!("{} ICX", self.value.format_icx()))?;
+        match &self.data {
+            TransactionData::Transfer => {}
+            TransactionData::Call { method, params } => {
+                field(f, "Type", "call")?;
+                field(f, "Method", Text(method))?;
+                write_params(f, params)?;
+            }
+            TransactionData::Deploy { content_type, content, params } => {
+                let action = if self.to == Address::SYSTEM_SCORE { "deploy" } else { "update" };
+                field(f, "Type", action)?;
+                field(f, "Content", format_args!("{} bytes of {}", content.0.len(), content_type.map_or("unknown", |c| c.as_str())))?;
+                write_params(f, params)?;
+            }
+            TransactionData::Message(message) => {
+                field(f, "Type", "message")?;
+                match std::str::from_utf8(&message.0) {
+                    Ok(text) if !text.chars().any(char::is_control) => field(f, "Message", format_args!("{:?}", text))?,
+                    _ => field(f, "Message", message)?,
+                }
+            }
+            TransactionData::Deposit { action, id, amount } => {
+                field(f, "Type", "deposit")?;
+                field(f, "Action", Text(action))?;
+                if let Some(id) = id {
+                    field(f, "Id", Text(id))?;
+                }
+                if let Some(amount) = amount {
+                    field(f, "Amount", format_args!("{} ICX", amount.format_icx()))?;
+                }
+            }
+            TransactionData::Base(data) => {
+                field(f, "Type", "base")?;
+                field(f, "Data", data)?;
+            }
+            TransactionData::Other { data_type, data } => {
+                field(f, "Type", Text(data_type))?;
+                if let Some(data) = data {
+                    field(f, "Data", data)?;
+                }
+            }
+        }
+        field(f, "Max fee", format_args!("{} ICX ({} steps)", self.max_fee.format_icx(), self.step_limit))?;
+        match Network::from_nid(self.nid) {
+            Some(network) => field(f, "Network", format_args!("{:?} ({:#x})", network, self.nid))?,
+            None => field(f, "Network", format_args!("{:#x}", self.nid))?,
+        }
+        if let Some(nonce) = self.nonce {
+            field(f, "Nonce", format_args!("{:#x}", nonce))?;
+        }
+        write!(f, "{:<11}{}", "Timestamp:", self.timestamp)
+    }
+}
+
+/// Writes a labelled line of the summary
+fn field(f: &mut fmt::Formatter<'_>, label: &str, value: impl fmt::Display) -> fmt::Result {
+    writeln!(f, "{:<11}{}", format!("{}:", label), value)
+}
+
+/// A string from the transaction, written as it is unless it has control characters, which are escaped and the string
+/// quoted, so a newline or terminal escape in a param can't fake a line of the summary
+struct Text<'a>(&'a str);
+
+impl fmt::Display for Text<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.chars().any(char::is_control) {
+            write!(f, "{:?}", self.0)
+        } else {
+            f.write_str(self.0)
+        }
+    }
+}
+
+/// Writes call or deploy params one per line, strings without their quotes unless they have control characters
+fn write_params(f: &mut fmt::Formatter<'_>, params: &Map<String, Value>) -> fmt::Result {
+    if params.is_empty() {
+        return Ok(());
+    }
+    writeln!(f, "Params:")?;
+    let names: Vec<String> = params.keys().map(|name| format!("{}:", Text(name))).collect();
+    let width = names.iter().map(String::len).max().unwrap_or(0).max(8);
+    for (name, value) in names.iter().zip(params.values()) {
+        match value {
+            Value::String(value) => writeln!(f, "  {:<width$} {}", name, Text(value), width = width)?,
+            value => writeln!(f, "  {:<width$} {}", name, value, width = width)?,
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,154 @@
+//! Tracking of per-address nonces for transactions sent concurrently from one wallet.
+use std::collections::{BTreeMap, HashMap};
+use std::io;
+use std::path::Path;
+use std::sync::Mutex;
+
+#[cfg(feature = "rpc")]
+use crate::rpc::{IconClient, RpcError, Transport};
+#[cfg(feature = "rpc")]
+use crate::types::Hash256;
+
+/// Hands out increasing nonces per address, so in-flight transactions from the same sender never share a nonce
+///
+/// # Example
+///
+///  ```
+/// # use icon_utils::transaction::{NonceManager, TransactionBuilder};
+/// let nonces = NonceManager::new();
+/// let from = "hxbe258ceb872e08851f1f59694dac2558708ece11";
+/// let tx = TransactionBuilder::new()
+///     .from(from)
+///     .to("hx5bfdb090f43a808005ffc27c25b213145e80b7cd")
+///     .step_limit(100_000)
+///     .nid(1)
+///     .nonce(nonces.next(from))
+///     .build()
+///     .unwrap();
+/// assert_eq!(tx.nonce, Some(0));
+/// assert_eq!(nonces.next(from), 1);
+/// ```
+#[derive(Debug, Default)]
+pub struct NonceManager {
+    nonces: Mutex<HashMap<String, u64>>,
+}
+
+impl NonceManager {
+    /// Creates a manager with no tracked addresses, which start at nonce 0
+    pub fn new() -> Self {
+        NonceManager::default()
+    }
+
+    /// Returns the next nonce for `address` and advances it
+    pub fn next(&self, address: &str) -> u64 {
+        let mut nonces = self.lock();
+        let nonce = nonces.entry(address.to_owned()).or_insert(0);
+        let next = *nonce;
+        *nonce += 1;
+        next
+    }
+
+    /// Returns the next nonce for `address` without advancing it
+    pub fn peek(&self, address: &str) -> u64 {
+        self.lock().get(address).copied().unwrap_or(0)
+    }
+
+    /// Sets the next nonce handed out for `address`
+    pub fn set(&self, address: &str, nonce: u64) {
+        self.lock().insert(address.to_owned(), nonce);
+    }
+
+    /// Forgets `address`, so it starts again at nonce 0
+    pub fn reset(&self, address: &str) {
+        self.lock().remove(address);
+    }
+
+    /// Raises the next nonce of `address` to `nonce`, leaving it unchanged if it is already higher
+    pub fn observe(&self, address: &str, nonce: u64) {
+        let mut nonces = self.lock();
+        let next = nonces.entry(address.to_owned()).or_insert(0);
+        *next = (*next).max(nonce);
+    }
+
+    /// Returns the next nonce of every tracked address, for persisting state elsewhere
+    pub fn snapshot(&self) -> BTreeMap<String, u64> {
+        self.lock().iter().map(|(address, nonce)| (address.clone(), *nonce)).collect()
+    }
+
+    /// Creates a manager from a snapshot taken with [`snapshot`](NonceManager::snapshot)
+    pub fn from_snapshot(snapshot: BTreeMap<String, u64>) -> Self {
+        NonceManager { nonces: Mutex::new(snapshot.into_iter().collect()) }
+    }
+
+    /// Writes the next nonce of every tracked address to a JSON file
+    ///
+    /// # Example
+    ///
+    ///  ```no_run
+    /// # use icon_utils::transaction::NonceManager;
+    /// let nonces = NonceManager::load("nonces.json").unwrap_or_default();
+    /// let nonce = nonces.next("hxbe258ceb872e08851f1f59694dac2558708ece11");
+    /// nonces.save("nonces.json").unwrap();
+    /// ```
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let json = serde_json::to_string_pretty(&self.snapshot()).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        std::fs::write(path, json)
+    }
+
+    /// Reads a manager from a JSON file written by [`save`](NonceManager::save)
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let json = std::fs::read_to_string(path)?;
+        let snapshot = serde_json::from_str(&json).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        Ok(NonceManager::from_snapshot(snapshot))
+    }
+
+    /// Fetches transactions sent earlier with `icx_getTransactionByHash`, raising the next nonce of their senders
+    /// above the nonces already confirmed on chain
+    ///
+    /// ICON has no per-account nonce to query, so the hashes of sent transactions have to be kept by the caller,
+    /// for example the ones still pending when the process last stopped. Transactions the node reports as pending,
+    /// executing or not found are skipped, any other error is returned so no nonce is silently left out.
+    ///
+    /// # Example
+    ///
+    ///  ```
+    /// # use icon_utils::{rpc::{IconClient, transport::MockTransport}, transaction::NonceManager};
+    /// # use serde_json::json;
+    /// let transport = MockTransport::new();
+    /// let hash = format!("0x{}", "ab".repeat(32));
+    /// let unknown = format!("0x{}", "cd".repeat(32));
+    /// transport.respond_error("icx_getTransactionByHash", -31004, "NotFound: no transaction");
+    /// transport.respond("icx_getTransactionByHash", json!({
+    ///     "from": "hxbe258ceb872e08851f1f59694dac2558708ece11", "to": "hx5bfdb090f43a808005ffc27c25b213145e80b7cd",
+    ///     "timestamp": "0x1", "nonce": "0x7", "txHash": hash
+    /// }));
+    /// let client = IconClient::with_transport("http://localhost:9000/api/v3", transport);
+    /// let nonces = NonceManager::new();
+    /// nonces.reconcile(&client, &[unknown.parse().unwrap(), hash.parse().unwrap()]).unwrap();
+    /// assert_eq!(nonces.next("hxbe258ceb872e08851f1f59694dac2558708ece11"), 8);
+    ///
+    /// let failing = MockTransport::new();
+    /// failing.respond_error("icx_getTransactionByHash", -31000, "SystemError");
+    /// let client = IconClient::with_transport("http://localhost:9000/api/v3", failing);
+    /// assert!(nonces.reconcile(&client, &[hash.parse().unwrap()]).is_err());
+    /// ```
+    #[cfg(feature = "rpc")]
+    pub fn reconcile<T: Transport>(&self, client: &IconClient<T>, hashes: &[Hash256]) -> Result<(), RpcError> {
+        for hash in hashes {
+            let tx = match client.get_transaction_by_hash(hash) {
+                Ok(tx) => tx,
+                Err(e) if e.is_pending() => continue,
+                Err(e) => return Err(e),
+            };
+            if let Some(nonce) = tx.nonce {
+                self.observe(&tx.from.to_string(), nonce.saturating_add(1));
+            }
+        }
+        Ok(())
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, u64>> {
+        // The map is always left consistent, so a poisoned lock is still usable
+        self.nonces.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
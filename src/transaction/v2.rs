@@ -0,0 +1,211 @@
+//! Legacy version 2 transactions, found in blocks from before ICON moved to version 3.
+use serde_json::{Map, Value};
+use sha3::{Digest, Sha3_256};
+use thiserror::Error;
+
+use crate::types::{Address, Hash256};
+use crate::units::Loop;
+#[cfg(feature = "wallet")]
+use crate::signer::{Signature, Signer};
+#[cfg(feature = "wallet")]
+use crate::wallet::WalletError;
+
+/// The fee every v2 transfer paid, 0.01 ICX
+pub const DEFAULT_V2_FEE: u64 = 10_000_000_000_000_000;
+
+/// Keys left out of the serialized transaction when hashing
+const UNHASHED_KEYS: [&str; 3] = ["method", "tx_hash", "signature"];
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+// Error struct for reading v2 transactions
+pub enum V2Error {
+    #[error("v2 transaction params must be an object")]
+    NotAnObject,
+    #[error("Missing v2 transaction field {0}")]
+    MissingField(&'static str),
+    #[error("Invalid v2 transaction field {field}: {value}")]
+    InvalidField { field: &'static str, value: String },
+}
+
+/// A version 2 ICX transfer
+///
+/// v2 transactions carry their own `tx_hash` and a `fee` instead of a step limit, and are hashed without the
+/// escaping of version 3. The params are kept as they were read, so the hash of a historical transaction is
+/// computed over exactly the strings that were signed.
+///
+/// # Example
+///
+///  ```
+/// # use icon_utils::{transaction::v2::TransactionV2, wallet::Wallet};
+/// # let wallet = Wallet::new("68ee9ca94b71c42ba79375b8677e29a717fb8072bcb17cd5ee288f9b77bc2894").unwrap();
+/// let tx = TransactionV2::new(
+///     wallet.address().parse().unwrap(),
+///     "hx0000000000000000000000000000000000000000".parse().unwrap(),
+///     1_000_000_000_000_000_000u128,
+///     1_516_942_975_500_598,
+/// );
+/// assert!(tx.serialize().starts_with("icx_sendTransaction.fee.0x2386f26fc10000.from.hx"));
+///
+/// assert_eq!(tx.value().unwrap(), Some(1_000_000_000_000_000_000u128.into()));
+///
+/// let signed = tx.sign(&wallet).unwrap();
+/// let stored = signed.to_params();
+/// let loaded = TransactionV2::from_params(stored).unwrap();
+/// assert_eq!(loaded.verify().unwrap().to_string(), wallet.address());
+///
+/// let mut corrupt = loaded.to_params();
+/// corrupt["value"] = "0xnot hex".into();
+/// assert!(TransactionV2::from_params(corrupt).unwrap().value().is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransactionV2 {
+    from: Address,
+    to: Address,
+    params: Map<String, Value>,
+}
+
+impl TransactionV2 {
+    /// Creates an unsigned transfer of `value` loop, paying the default fee
+    pub fn new(from: Address, to: Address, value: impl Into<Loop>, timestamp: u64) -> Self {
+        let mut params = Map::new();
+        params.insert("from".to_owned(), Value::String(from.to_string()));
+        params.insert("to".to_owned(), Value::String(to.to_string()));
+        params.insert("value".to_owned(), Value::String(value.into().to_string()));
+        params.insert("fee".to_owned(), Value::String(Loop::from(DEFAULT_V2_FEE).to_string()));
+        params.insert("timestamp".to_owned(), Value::String(timestamp.to_string()));
+        TransactionV2 { from, to, params }
+    }
+
+    /// Reads a v2 transaction from its params, such as an entry of a v2 block's `confirmed_transaction_list`
+    pub fn from_params(params: Value) -> Result<Self, V2Error> {
+        let Value::Object(params) = params else {
+            return Err(V2Error::NotAnObject);
+        };
+        let from = address_field(&params, "from")?;
+        let to = address_field(&params, "to")?;
+        if !params.contains_key("timestamp") {
+            return Err(V2Error::MissingField("timestamp"));
+        }
+        Ok(TransactionV2 { from, to, params })
+    }
+
+    /// Returns the sender
+    pub fn from(&self) -> &Address {
+        &self.from
+    }
+
+    /// Returns the recipient
+    pub fn to(&self) -> &Address {
+        &self.to
+    }
+
+    /// Returns the ICX sent, None when missing and an error when it isn't a hex amount
+    pub fn value(&self) -> Result<Option<Loop>, V2Error> {
+        self.loop_field("value")
+    }
+
+    /// Returns the fee paid, None when missing and an error when it isn't a hex amount
+    pub fn fee(&self) -> Result<Option<Loop>, V2Error> {
+        self.loop_field("fee")
+    }
+
+    /// Returns the timestamp in microseconds, written in decimal in v2 transactions
+    pub fn timestamp(&self) -> Option<u64> {
+        match self.params.get("timestamp")? {
+            Value::String(timestamp) => timestamp.parse().ok(),
+            timestamp => timestamp.as_u64(),
+        }
+    }
+
+    /// Returns the optional nonce, which v2 transactions carry as a free-form string
+    pub fn nonce(&self) -> Option<&str> {
+        self.params.get("nonce").and_then(Value::as_str)
+    }
+
+    /// Returns the `tx_hash` the transaction declares, written without a `0x` prefix in v2 transactions
+    pub fn declared_hash(&self) -> Option<Hash256> {
+        let hash = self.params.get("tx_hash")?.as_str()?;
+        format!("0x{}", hash.trim_start_matches("0x")).parse().ok()
+    }
+
+    /// Returns the base64 signature, if the transaction is signed
+    pub fn signature(&self) -> Option<&str> {
+        self.params.get("signature").and_then(Value::as_str)
+    }
+
+    /// Returns the params of the transaction
+    pub fn to_params(&self) -> Value {
+        Value::Object(self.params.clone())
+    }
+
+    /// Serializes the transaction for hashing, as `icx_sendTransaction` followed by each sorted key and value
+    ///
+    /// Unlike version 3 values aren't escaped, and `method`, `tx_hash` and `signature` are left out.
+    pub fn serialize(&self) -> String {
+        let mut fields = self.params.iter().filter(|(key, _)| !UNHASHED_KEYS.contains(&key.as_str())).collect::<Vec<_>>();
+        fields.sort_by_key(|(key, _)| *key);
+        let mut serialized = "icx_sendTransaction".to_owned();
+        for (key, value) in fields {
+            serialized.push('.');
+            serialized.push_str(key);
+            serialized.push('.');
+            match value {
+                Value::String(value) => serialized.push_str(value),
+                value => serialized.push_str(&value.to_string()),
+            }
+        }
+        serialized
+    }
+
+    /// Returns the SHA3-256 hash of the serialized transaction
+    pub fn hash(&self) -> Hash256 {
+        Hash256(Sha3_256::digest(self.serialize()).into())
+    }
+
+    /// Signs the transaction, setting its `tx_hash` and `signature`
+    #[cfg(feature = "wallet")]
+    pub fn sign(mut self, signer: &impl Signer) -> Result<Self, WalletError> {
+        let hash = self.hash();
+        let signature = signer.sign_hash(hash.as_bytes())?;
+        self.params.insert("tx_hash".to_owned(), Value::String(hex::encode(hash.as_bytes())));
+        self.params.insert("signature".to_owned(), Value::String(signature.to_base64()));
+        Ok(self)
+    }
+
+    /// Verifies the transaction, returning the address that signed it
+    ///
+    /// The declared `tx_hash`, when present, has to match the params, and the signer recovered from the
+    /// signature has to be the sender.
+    #[cfg(feature = "wallet")]
+    pub fn verify(&self) -> Result<Address, WalletError> {
+        let hash = self.hash();
+        if self.params.contains_key("tx_hash") && self.declared_hash() != Some(hash) {
+            return Err(WalletError::HashMismatch);
+        }
+        let signature = self.signature().ok_or_else(|| WalletError::InvalidSignature("missing".to_owned()))?;
+        let signer = Signature::from_base64(signature)?.recover_address(hash.as_bytes())?;
+        if signer != self.from {
+            return Err(WalletError::SignerMismatch { from: self.from, signer });
+        }
+        Ok(signer)
+    }
+
+    fn loop_field(&self, field: &'static str) -> Result<Option<Loop>, V2Error> {
+        let Some(value) = self.params.get(field) else {
+            return Ok(None);
+        };
+        value
+            .as_str()
+            .and_then(|amount| Loop::from_hex(amount).ok())
+            .map(Some)
+            .ok_or_else(|| V2Error::InvalidField { field, value: value.to_string() })
+    }
+}
+
+fn address_field(params: &Map<String, Value>, field: &'static str) -> Result<Address, V2Error> {
+    let value = params.get(field).ok_or(V2Error::MissingField(field))?;
+    value
+        .as_str()
+        .and_then(|address| address.parse().ok())
+        .ok_or_else(|| V2Error::InvalidField { field, value: value.to_string() })
+}
@@ -0,0 +1,205 @@
+//! Checks on built transactions that catch obviously invalid ones before they are signed and sent.
+use std::time::Duration;
+
+use thiserror::Error;
+
+use super::{current_timestamp, estimate_message_steps, IcxTransaction, TransactionData, DEFAULT_STEPS, VERSION};
+use crate::network::Network;
+use crate::types::Address;
+
+/// The default largest difference allowed between a transaction's timestamp and the local clock
+pub const DEFAULT_MAX_DRIFT: Duration = Duration::from_secs(5 * 60);
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+// Error struct for problems found by `validate`
+pub enum ValidationIssue {
+    #[error("Unsupported transaction version {0}, expected {VERSION}")]
+    UnsupportedVersion(u64),
+    #[error("Step limit {step_limit} is below the minimum of {minimum}")]
+    StepLimitTooLow { step_limit: u64, minimum: u64 },
+    #[error("Step limit {step_limit} is above the maximum of {maximum}")]
+    StepLimitTooHigh { step_limit: u64, maximum: u64 },
+    #[error("Transactions must be sent from an hx address, not {0}")]
+    FromNotEoa(Address),
+    #[error("{data_type} transactions must be sent to a cx address, not {to}")]
+    ToNotContract { data_type: String, to: Address },
+    #[error("Deposits must have a value to add")]
+    MissingValue,
+    #[error("{0} transactions can't carry a value")]
+    UnexpectedValue(String),
+    #[error("Invalid data: {0}")]
+    InvalidData(String),
+    #[error("Unknown data type {0}")]
+    UnknownDataType(String),
+    #[error("Network id {nid:#x} doesn't match the target network {expected:#x}")]
+    WrongNetwork { nid: u64, expected: u64 },
+    #[error("Timestamp is {0:?} in the future")]
+    TimestampInFuture(Duration),
+    #[error("Timestamp is {0:?} in the past")]
+    TimestampTooOld(Duration),
+}
+
+/// Settings for [`validate_with`]
+#[derive(Debug, Clone)]
+pub struct ValidationOptions {
+    network: Option<Network>,
+    max_drift: Duration,
+    max_step_limit: Option<u64>,
+    now: Option<u64>,
+}
+
+impl Default for ValidationOptions {
+    fn default() -> Self {
+        ValidationOptions { network: None, max_drift: DEFAULT_MAX_DRIFT, max_step_limit: None, now: None }
+    }
+}
+
+impl ValidationOptions {
+    /// Creates options that only check the transaction itself and its timestamp
+    pub fn new() -> Self {
+        ValidationOptions::default()
+    }
+
+    /// Checks that the network id matches `network`
+    pub fn network(mut self, network: &Network) -> Self {
+        self.network = Some(network.clone());
+        self
+    }
+
+    /// Sets the largest difference allowed between the timestamp and the current time
+    pub fn max_drift(mut self, max_drift: Duration) -> Self {
+        self.max_drift = max_drift;
+        self
+    }
+
+    /// Sets the highest step limit allowed, such as the value of `getMaxStepLimit`
+    pub fn max_step_limit(mut self, max_step_limit: u64) -> Self {
+        self.max_step_limit = Some(max_step_limit);
+        self
+    }
+
+    /// Sets the current time in microseconds since the unix epoch, instead of reading the local clock
+    pub fn now(mut self, now: u64) -> Self {
+        self.now = Some(now);
+        self
+    }
+}
+
+/// Checks a transaction with the default options, returning every problem found
+///
+/// # Example
+///
+///  ```
+/// # use icon_utils::transaction::{validate, CallTransactionBuilder, ValidationIssue};
+/// let tx = CallTransactionBuilder::new()
+///     .from("hxbe258ceb872e08851f1f59694dac2558708ece11")
+///     .to("hx5bfdb090f43a808005ffc27c25b213145e80b7cd")
+///     .method("transfer")
+///     .step_limit(1_000_000)
+///     .nid(1)
+///     .build()
+///     .unwrap();
+/// assert!(matches!(validate(&tx)[..], [ValidationIssue::ToNotContract { .. }]));
+/// ```
+pub fn validate(tx: &IcxTransaction) -> Vec<ValidationIssue> {
+    validate_with(tx, &ValidationOptions::default())
+}
+
+/// Checks a transaction, returning every problem found
+///
+/// # Example
+///
+///  ```
+/// # use icon_utils::{network::Network, transaction::{validate_with, TransactionBuilder, ValidationIssue, ValidationOptions}};
+/// let tx = TransactionBuilder::new()
+///     .from("hxbe258ceb872e08851f1f59694dac2558708ece11")
+///     .to("hx5bfdb090f43a808005ffc27c25b213145e80b7cd")
+///     .step_limit(100_000)
+///     .network(&Network::Lisbon)
+///     .build()
+///     .unwrap();
+/// let issues = validate_with(&tx, &ValidationOptions::new().network(&Network::Mainnet));
+/// assert_eq!(issues, [ValidationIssue::WrongNetwork { nid: 2, expected: 1 }]);
+/// ```
+pub fn validate_with(tx: &IcxTransaction, options: &ValidationOptions) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+    if tx.version != VERSION {
+        issues.push(ValidationIssue::UnsupportedVersion(tx.version));
+    }
+    if tx.from.is_contract() {
+        issues.push(ValidationIssue::FromNotEoa(tx.from));
+    }
+    if let Some(network) = &options.network {
+        if tx.nid != network.nid() {
+            issues.push(ValidationIssue::WrongNetwork { nid: tx.nid, expected: network.nid() });
+        }
+    }
+    let now = options.now.unwrap_or_else(current_timestamp);
+    let max_drift = options.max_drift.as_micros() as u64;
+    if tx.timestamp > now.saturating_add(max_drift) {
+        issues.push(ValidationIssue::TimestampInFuture(Duration::from_micros(tx.timestamp - now)));
+    } else if now > tx.timestamp.saturating_add(max_drift) {
+        issues.push(ValidationIssue::TimestampTooOld(Duration::from_micros(now - tx.timestamp)));
+    }
+
+    let data = match tx.decode_data() {
+        Ok(data) => data,
+        Err(e) => {
+            issues.push(ValidationIssue::InvalidData(e));
+            return issues;
+        }
+    };
+    let mut minimum = DEFAULT_STEPS;
+    let needs_contract = match &data {
+        TransactionData::Transfer | TransactionData::Base(_) => false,
+        TransactionData::Message(message) => {
+            minimum = estimate_message_steps(message.0.len());
+            false
+        }
+        TransactionData::Call { method, .. } => {
+            if method.is_empty() {
+                issues.push(ValidationIssue::InvalidData("call method is empty".to_owned()));
+            }
+            true
+        }
+        TransactionData::Deploy { content_type, content, .. } => {
+            if content_type.is_none() {
+                issues.push(ValidationIssue::InvalidData("unknown deploy content type".to_owned()));
+            }
+            if content.0.is_empty() {
+                issues.push(ValidationIssue::InvalidData("deploy content is empty".to_owned()));
+            }
+            if tx.value.is_some() {
+                issues.push(ValidationIssue::UnexpectedValue("deploy".to_owned()));
+            }
+            true
+        }
+        TransactionData::Deposit { action, .. } => {
+            match action.as_str() {
+                "add" if tx.value.is_none() => issues.push(ValidationIssue::MissingValue),
+                "add" => {}
+                "withdraw" if tx.value.is_some() => issues.push(ValidationIssue::UnexpectedValue("withdraw".to_owned())),
+                "withdraw" => {}
+                other => issues.push(ValidationIssue::InvalidData(format!("unknown deposit action {}", other))),
+            }
+            true
+        }
+        TransactionData::Other { data_type, .. } => {
+            issues.push(ValidationIssue::UnknownDataType(data_type.clone()));
+            false
+        }
+    };
+    if needs_contract && !tx.to.is_contract() {
+        let data_type = data.data_type().unwrap_or_default().to_owned();
+        issues.push(ValidationIssue::ToNotContract { data_type, to: tx.to });
+    }
+    if tx.step_limit < minimum {
+        issues.push(ValidationIssue::StepLimitTooLow { step_limit: tx.step_limit, minimum });
+    }
+    if let Some(maximum) = options.max_step_limit {
+        if tx.step_limit > maximum {
+            issues.push(ValidationIssue::StepLimitTooHigh { step_limit: tx.step_limit, maximum });
+        }
+    }
+    issues
+}
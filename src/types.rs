@@ -0,0 +1,345 @@
+//! # Icon Types
+//! `types` holds the primitive ICON types shared by transactions and RPC responses.
+use std::fmt;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use thiserror::Error;
+
+use crate::params::IntoParam;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+// Error struct for parsing addresses
+pub enum AddressError {
+    #[error("Address {0} doesn't start with hx or cx")]
+    InvalidPrefix(String),
+    #[error("Address {0} doesn't have 40 hex characters")]
+    InvalidLength(String),
+    #[error("Address {0} isn't valid hex")]
+    InvalidHex(String),
+    #[error("Invalid public key: {0}")]
+    InvalidPublicKey(String),
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+// Error struct for parsing hex values
+pub enum HexError {
+    #[error("Value {0} doesn't start with 0x")]
+    MissingPrefix(String),
+    #[error("Value {0} doesn't have {1} hex characters")]
+    InvalidLength(String, usize),
+    #[error("Value {0} isn't valid hex")]
+    InvalidHex(String),
+    #[error("Value {0} has leading zeros")]
+    LeadingZeros(String),
+    #[error("Value {0} has uppercase hex digits")]
+    Uppercase(String),
+}
+
+/// Returns the hex digits after the `0x` prefix of a value
+fn strip_hex_prefix(value: &str) -> Result<&str, HexError> {
+    value.strip_prefix("0x").ok_or_else(|| HexError::MissingPrefix(value.to_owned()))
+}
+
+/// An ICON address, either an `hx` externally owned account or a `cx` contract
+///
+/// Parsing accepts upper or lower case hex, and the address is always displayed and serialized in lowercase.
+///
+/// # Example
+///
+///  ```
+/// # use icon_utils::types::Address;
+/// let address: Address = "hxBE258CEB872E08851F1F59694DAC2558708ECE11".parse().unwrap();
+/// assert!(address.is_eoa());
+/// assert_eq!(address.to_string(), "hxbe258ceb872e08851f1f59694dac2558708ece11");
+/// assert_eq!(Address::GOVERNANCE_SCORE.to_string(), "cx0000000000000000000000000000000000000001");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Address {
+    /// An `hx` address, owned by a key pair
+    Eoa([u8; 20]),
+    /// A `cx` address of a deployed SCORE
+    Contract([u8; 20]),
+}
+
+impl Address {
+    /// The all zero `hx` address, which nobody holds the key of
+    pub const ZERO: Address = Address::Eoa([0; 20]);
+
+    /// The system SCORE `cx0000000000000000000000000000000000000000`, the target of deploys and chain methods
+    pub const SYSTEM_SCORE: Address = Address::Contract([0; 20]);
+
+    /// The governance SCORE `cx0000000000000000000000000000000000000001`, which holds the step price and step costs
+    pub const GOVERNANCE_SCORE: Address = Address::Contract([0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1]);
+
+    /// Derives the `hx` address of a SEC1 encoded secp256k1 public key, compressed or uncompressed
+    ///
+    /// # Example
+    ///
+    ///  ```
+    /// # use icon_utils::types::Address;
+    /// let pubkey = hex::decode("0341a17003772aaedc72a2902e846206ae72b3848414f1d00deedac1f4c1c328ec").unwrap();
+    /// let address = Address::from_public_key(&pubkey).unwrap();
+    /// assert_eq!(address.to_string(), "hxb874d46c44dd5db9562f2b077a84e45c35dbfc64");
+    /// ```
+    #[cfg(feature = "wallet")]
+    pub fn from_public_key(pubkey: &[u8]) -> Result<Self, AddressError> {
+        let pubkey = k256::ecdsa::VerifyingKey::from_sec1_bytes(pubkey)
+            .map_err(|_| AddressError::InvalidPublicKey(hex::encode(pubkey)))?;
+        Ok(Address::from(&pubkey))
+    }
+
+    /// Returns true for `cx` addresses
+    pub fn is_contract(&self) -> bool {
+        matches!(self, Address::Contract(_))
+    }
+
+    /// Returns true for `hx` addresses, which can sign transactions but have no methods to call
+    pub fn is_eoa(&self) -> bool {
+        matches!(self, Address::Eoa(_))
+    }
+
+    /// Returns true for the system and governance SCOREs
+    pub fn is_system_score(&self) -> bool {
+        *self == Address::SYSTEM_SCORE || *self == Address::GOVERNANCE_SCORE
+    }
+
+    /// Returns the 20 bytes of the address, without the prefix
+    pub fn as_bytes(&self) -> &[u8; 20] {
+        match self {
+            Address::Eoa(bytes) | Address::Contract(bytes) => bytes,
+        }
+    }
+
+    /// Returns the `hx` or `cx` prefix of the address
+    pub fn prefix(&self) -> &'static str {
+        match self {
+            Address::Eoa(_) => "hx",
+            Address::Contract(_) => "cx",
+        }
+    }
+}
+
+impl FromStr for Address {
+    type Err = AddressError;
+    fn from_str(value: &str) -> Result<Self, AddressError> {
+        let (prefix, digits) = (value.get(..2), value.get(2..));
+        let variant: fn([u8; 20]) -> Address = match prefix {
+            Some("hx") => Address::Eoa,
+            Some("cx") => Address::Contract,
+            _ => return Err(AddressError::InvalidPrefix(value.to_owned())),
+        };
+        let digits = digits.unwrap_or_default();
+        if digits.len() != 40 {
+            return Err(AddressError::InvalidLength(value.to_owned()));
+        }
+        let mut bytes = [0u8; 20];
+        hex::decode_to_slice(digits, &mut bytes).map_err(|_| AddressError::InvalidHex(value.to_owned()))?;
+        Ok(variant(bytes))
+    }
+}
+
+#[cfg(feature = "wallet")]
+impl From<&k256::ecdsa::VerifyingKey> for Address {
+    fn from(pubkey: &k256::ecdsa::VerifyingKey) -> Self {
+        Address::Eoa(crate::wallet::pubkey_hash(pubkey))
+    }
+}
+
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.prefix(), hex::encode(self.as_bytes()))
+    }
+}
+
+/// A 32 byte hash, such as a tx or block hash, written as `0x` prefixed hex
+///
+/// # Example
+///
+///  ```
+/// # use icon_utils::types::Hash256;
+/// let hash: Hash256 = "0x2e8e5c6f8a9e4c1b5d3f7a0b2c4d6e8f1a3b5c7d9e0f2a4b6c8d0e2f4a6b8c0d".parse().unwrap();
+/// assert_eq!(hash.as_bytes()[0], 0x2e);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Hash256(pub [u8; 32]);
+
+impl Hash256 {
+    /// Returns the bytes of the hash
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+}
+
+impl From<[u8; 32]> for Hash256 {
+    fn from(bytes: [u8; 32]) -> Self {
+        Hash256(bytes)
+    }
+}
+
+impl FromStr for Hash256 {
+    type Err = HexError;
+    fn from_str(value: &str) -> Result<Self, HexError> {
+        let digits = strip_hex_prefix(value)?;
+        if digits.len() != 64 {
+            return Err(HexError::InvalidLength(value.to_owned(), 64));
+        }
+        let bytes = crate::icon_hex::decode_bytes_0x(value)?;
+        Ok(Hash256(bytes.try_into().expect("64 hex digits decode to 32 bytes")))
+    }
+}
+
+impl fmt::Display for Hash256 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&crate::icon_hex::encode_bytes_0x(&self.0))
+    }
+}
+
+/// Bytes of any length, written as `0x` prefixed hex
+///
+/// # Example
+///
+///  ```
+/// # use icon_utils::types::HexBytes;
+/// let bytes: HexBytes = "0x68656c6c6f".parse().unwrap();
+/// assert_eq!(bytes.0, b"hello");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct HexBytes(pub Vec<u8>);
+
+impl From<Vec<u8>> for HexBytes {
+    fn from(bytes: Vec<u8>) -> Self {
+        HexBytes(bytes)
+    }
+}
+
+impl FromStr for HexBytes {
+    type Err = HexError;
+    fn from_str(value: &str) -> Result<Self, HexError> {
+        crate::icon_hex::decode_bytes_0x(value).map(HexBytes)
+    }
+}
+
+impl fmt::Display for HexBytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&crate::icon_hex::encode_bytes_0x(&self.0))
+    }
+}
+
+/// A transaction timestamp, in microseconds since the unix epoch
+///
+/// Timestamps are written as `0x` prefixed hex in transactions, and convert to and from [`SystemTime`], or
+/// `chrono::DateTime` with the `chrono` feature.
+///
+/// # Example
+///
+///  ```
+/// # use icon_utils::types::Timestamp;
+/// # use std::time::{Duration, UNIX_EPOCH};
+/// let timestamp = Timestamp::from(UNIX_EPOCH + Duration::from_micros(1_700_000_000_000_000));
+/// assert_eq!(timestamp.to_string(), "0x60a24181e4000");
+/// assert_eq!("0x60a24181e4000".parse::<Timestamp>().unwrap(), timestamp);
+/// assert!(Timestamp::now() > timestamp);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Timestamp(pub u64);
+
+impl Timestamp {
+    /// Returns the current time
+    ///
+    /// Nodes reject transactions whose timestamp is too far from their own clock, so take it just before sending.
+    pub fn now() -> Self {
+        Timestamp::from(SystemTime::now())
+    }
+
+    /// Returns the number of microseconds since the unix epoch
+    pub fn as_micros(&self) -> u64 {
+        self.0
+    }
+}
+
+impl From<u64> for Timestamp {
+    fn from(micros: u64) -> Self {
+        Timestamp(micros)
+    }
+}
+
+impl From<Timestamp> for u64 {
+    fn from(timestamp: Timestamp) -> Self {
+        timestamp.0
+    }
+}
+
+/// Times before the unix epoch become zero
+impl From<SystemTime> for Timestamp {
+    fn from(time: SystemTime) -> Self {
+        Timestamp(time.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_micros() as u64))
+    }
+}
+
+impl From<Timestamp> for SystemTime {
+    fn from(timestamp: Timestamp) -> Self {
+        UNIX_EPOCH + Duration::from_micros(timestamp.0)
+    }
+}
+
+/// Times before the unix epoch become zero
+#[cfg(feature = "chrono")]
+impl<Tz: chrono::TimeZone> From<chrono::DateTime<Tz>> for Timestamp {
+    fn from(time: chrono::DateTime<Tz>) -> Self {
+        Timestamp(u64::try_from(time.timestamp_micros()).unwrap_or(0))
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<Timestamp> for chrono::DateTime<chrono::Utc> {
+    fn from(timestamp: Timestamp) -> Self {
+        chrono::DateTime::UNIX_EPOCH + chrono::Duration::microseconds(timestamp.0 as i64)
+    }
+}
+
+impl FromStr for Timestamp {
+    type Err = HexError;
+    fn from_str(value: &str) -> Result<Self, HexError> {
+        let micros = crate::icon_hex::from_hex_int(value)?;
+        u64::try_from(micros).map(Timestamp).map_err(|_| HexError::InvalidHex(value.to_owned()))
+    }
+}
+
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&crate::icon_hex::to_hex_no_pad(u128::from(self.0)))
+    }
+}
+
+/// Implements string serde and `IntoParam` for types with `Display` and `FromStr`
+macro_rules! impl_string_serde {
+    ($($ty:ty),*) => {$(
+        impl Serialize for $ty {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(&self.to_string())
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $ty {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let value = String::deserialize(deserializer)?;
+                value.parse().map_err(serde::de::Error::custom)
+            }
+        }
+
+        impl IntoParam for $ty {
+            fn into_param(self) -> serde_json::Value {
+                serde_json::Value::String(self.to_string())
+            }
+        }
+
+        impl IntoParam for &$ty {
+            fn into_param(self) -> serde_json::Value {
+                serde_json::Value::String(self.to_string())
+            }
+        }
+    )*};
+}
+impl_string_serde!(Address, Hash256, HexBytes, Timestamp);
@@ -0,0 +1,211 @@
+//! # Icon Units
+//! `units` is a module for converting between ICX and loop, the smallest unit of ICX (1 ICX = 10^18 loop).
+use std::fmt;
+use std::ops::{Add, Mul};
+use std::str::FromStr;
+
+use num_bigint::BigUint;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use thiserror::Error;
+
+use crate::params::IntoParam;
+use crate::serializer::BigNum;
+
+/// Number of decimals in one ICX
+pub const DECIMALS: u32 = 18;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+// Error struct for parsing amounts
+pub enum UnitError {
+    #[error("Invalid amount: {0}")]
+    InvalidAmount(String),
+    #[error("Amount {0} has more than 18 decimals")]
+    TooManyDecimals(String),
+}
+
+fn loop_per_icx() -> BigUint {
+    BigUint::from(10u32).pow(DECIMALS)
+}
+
+/// An amount in loop
+///
+/// Serializes as `0x` prefixed hex, so it can be used directly as a transaction value.
+///
+/// # Example
+/// ```
+/// # use icon_utils::units::Loop;
+/// let value = Loop::from(1_500_000_000_000_000_000u128);
+/// assert_eq!(value.format_icx(), "1.5");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Loop(pub BigUint);
+
+impl Loop {
+    /// Converts the amount to ICX
+    pub fn to_icx(&self) -> Icx {
+        Icx(self.clone())
+    }
+
+    /// Formats the amount as a decimal ICX string, without trailing zeros
+    pub fn format_icx(&self) -> String {
+        let scale = loop_per_icx();
+        let whole = &self.0 / &scale;
+        let fraction = &self.0 % &scale;
+        if fraction == BigUint::default() {
+            return whole.to_string();
+        }
+        let fraction = format!("{:0>width$}", fraction.to_string(), width = DECIMALS as usize);
+        format!("{}.{}", whole, fraction.trim_end_matches('0'))
+    }
+
+    /// Parses a `0x` prefixed hex amount, as returned by nodes
+    pub fn from_hex(value: &str) -> Result<Self, UnitError> {
+        BigNum::from_hex(value)
+            .map(|v| Loop(v.0))
+            .ok_or_else(|| UnitError::InvalidAmount(value.to_owned()))
+    }
+
+    /// Subtracts `other`, returning None if the result would be negative
+    pub fn checked_sub(&self, other: &Loop) -> Option<Loop> {
+        if other.0 > self.0 {
+            None
+        } else {
+            Some(Loop(&self.0 - &other.0))
+        }
+    }
+}
+
+impl fmt::Display for Loop {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "0x{:x}", self.0)
+    }
+}
+
+impl From<u64> for Loop {
+    fn from(value: u64) -> Self {
+        Loop(BigUint::from(value))
+    }
+}
+
+impl From<u128> for Loop {
+    fn from(value: u128) -> Self {
+        Loop(BigUint::from(value))
+    }
+}
+
+impl From<BigUint> for Loop {
+    fn from(value: BigUint) -> Self {
+        Loop(value)
+    }
+}
+
+impl From<BigNum> for Loop {
+    fn from(value: BigNum) -> Self {
+        Loop(value.0)
+    }
+}
+
+impl From<Loop> for BigNum {
+    fn from(value: Loop) -> Self {
+        BigNum(value.0)
+    }
+}
+
+impl From<Icx> for Loop {
+    fn from(value: Icx) -> Self {
+        value.0
+    }
+}
+
+impl Add for Loop {
+    type Output = Loop;
+    fn add(self, other: Loop) -> Loop {
+        Loop(self.0 + other.0)
+    }
+}
+
+impl Mul<u64> for Loop {
+    type Output = Loop;
+    fn mul(self, other: u64) -> Loop {
+        Loop(self.0 * other)
+    }
+}
+
+impl Serialize for Loop {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for Loop {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        BigNum::deserialize(deserializer).map(Loop::from)
+    }
+}
+
+impl IntoParam for Loop {
+    fn into_param(self) -> serde_json::Value {
+        serde_json::Value::String(self.to_string())
+    }
+}
+
+/// An amount in ICX, stored exactly as loop
+///
+/// # Example
+/// ```
+/// # use icon_utils::units::Icx;
+/// let value: Icx = "1.5".parse().unwrap();
+/// assert_eq!(value.to_loop().to_string(), "0x14d1120d7b160000");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Icx(Loop);
+
+impl Icx {
+    /// Creates an amount from a whole number of ICX
+    pub fn new(icx: u64) -> Self {
+        Icx(Loop(BigUint::from(icx) * loop_per_icx()))
+    }
+
+    /// Converts the amount to loop
+    pub fn to_loop(&self) -> Loop {
+        self.0.clone()
+    }
+
+    /// Formats the amount as a decimal ICX string, without trailing zeros
+    pub fn format_icx(&self) -> String {
+        self.0.format_icx()
+    }
+}
+
+impl FromStr for Icx {
+    type Err = UnitError;
+    fn from_str(value: &str) -> Result<Self, UnitError> {
+        let invalid = || UnitError::InvalidAmount(value.to_owned());
+        let (whole, fraction) = value.split_once('.').unwrap_or((value, ""));
+        if whole.is_empty() && fraction.is_empty() {
+            return Err(invalid());
+        }
+        if !whole.bytes().chain(fraction.bytes()).all(|b| b.is_ascii_digit()) {
+            return Err(invalid());
+        }
+        if fraction.len() > DECIMALS as usize {
+            return Err(UnitError::TooManyDecimals(value.to_owned()));
+        }
+        let digits = format!("{}{:0<width$}", whole, fraction, width = DECIMALS as usize);
+        BigUint::parse_bytes(digits.as_bytes(), 10)
+            .map(|v| Icx(Loop(v)))
+            .ok_or_else(invalid)
+    }
+}
+
+impl fmt::Display for Icx {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ICX", self.format_icx())
+    }
+}
+
+impl From<Loop> for Icx {
+    fn from(value: Loop) -> Self {
+        Icx(value)
+    }
+}
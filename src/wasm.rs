@@ -0,0 +1,43 @@
+//! # Icon WASM
+//! `wasm` is a module of wasm-bindgen bindings, so browser wallets can use the same serialization and signing code.
+//! Transactions are passed as the JSON of their `icx_sendTransaction` params, and private keys as hex.
+//!
+//! Build with `wasm-pack build --features wasm`, then from JavaScript:
+//!  ```js
+//! import { address, signTransaction } from "icon_utils";
+//! const signed = JSON.parse(signTransaction(privateKey, JSON.stringify(tx)));
+//! ```
+use wasm_bindgen::prelude::*;
+
+use crate::serializer;
+use crate::transaction::IcxTransaction;
+use crate::wallet::Wallet;
+
+/// Returns the serialized form of a transaction, the string that gets hashed and signed
+#[wasm_bindgen]
+pub fn serialize(tx_json: &str) -> Result<String, JsError> {
+    Ok(serializer::serialize_to_string(&parse_transaction(tx_json)?)?)
+}
+
+/// Returns the hx address of a hex private key
+#[wasm_bindgen]
+pub fn address(private_key: &str) -> Result<String, JsError> {
+    Ok(Wallet::new(private_key)?.address())
+}
+
+/// Signs a transaction, returning the JSON of the `icx_sendTransaction` params with the signature added
+#[wasm_bindgen(js_name = signTransaction)]
+pub fn sign_transaction(private_key: &str, tx_json: &str) -> Result<String, JsError> {
+    let signed = Wallet::new(private_key)?.sign_transaction(&parse_transaction(tx_json)?)?;
+    Ok(signed.to_rpc_params().to_string())
+}
+
+/// Returns the `0x` prefixed tx hash of a transaction
+#[wasm_bindgen(js_name = txHash)]
+pub fn tx_hash(tx_json: &str) -> Result<String, JsError> {
+    Ok(crate::icon_hex::encode_bytes_0x(&serializer::tx_hash(&parse_transaction(tx_json)?)?))
+}
+
+fn parse_transaction(tx_json: &str) -> Result<IcxTransaction, JsError> {
+    serde_json::from_str(tx_json).map_err(|e| JsError::new(&format!("Invalid transaction: {}", e)))
+}
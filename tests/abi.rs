@@ -0,0 +1,14 @@
+//! Generated SCORE clients, compiled and run to check the generator emits valid Rust.
+use std::fs;
+
+use icon_utils::abi::{self, AbiEntry};
+
+#[test]
+fn generated_client_compiles() {
+    let api: Vec<AbiEntry> = serde_json::from_str(&fs::read_to_string("tests/abi/api.json").unwrap()).unwrap();
+    let source = abi::generate("Token", &api);
+    let check = fs::read_to_string("tests/abi/check.rs").unwrap();
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("abi_token.rs");
+    fs::write(&path, format!("{}\n{}", source, check)).unwrap();
+    trybuild::TestCases::new().pass(&path);
+}
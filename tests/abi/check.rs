@@ -0,0 +1,17 @@
+fn main() {
+    use icon_utils::params::from_value;
+    use icon_utils::rpc::IconClient;
+
+    let client = IconClient::new("http://localhost:9080/api/v3");
+    let token = Token::new(&client, "cx0000000000000000000000000000000000000001".parse().unwrap());
+    let from: icon_utils::types::Address = "hx0000000000000000000000000000000000000002".parse().unwrap();
+    let tx = token.adjust(-5, vec![-1, 2], None).from(from).step_limit(1).nid(1).timestamp(1).build().unwrap();
+    let data = tx.data.unwrap();
+    assert_eq!(data["params"], serde_json::json!({"_delta": "-0x5", "_steps": ["-0x1", "0x2"]}));
+    let _ = (token.super_("a", "b"), token.r#async("a", "b", "c"), token.r#abstract("a", "b", "c"), token.__());
+    // Read-only calls aren't sent, only their signatures checked
+    let _ = |token: &Token| -> Result<Vec<i128>, icon_utils::rpc::RpcError> { token.self_("a", true) };
+    let _ = |token: &Token| -> Result<i128, icon_utils::rpc::RpcError> { token.balance_of(&from) };
+    let balance: i128 = from_value(serde_json::json!("-0xde0b6b3a7640000")).unwrap();
+    assert_eq!(balance, -1_000_000_000_000_000_000);
+}
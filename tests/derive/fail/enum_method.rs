@@ -0,0 +1,15 @@
+use icon_utils::serializer::Transaction;
+
+#[derive(serde::Serialize, Transaction)]
+#[transaction(method = "icx_sendTransaction")]
+struct TransferTransaction {
+    to: String,
+}
+
+#[derive(Transaction)]
+#[transaction(method = "icx_call")]
+enum AnyTransaction {
+    Transfer(TransferTransaction),
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+use icon_utils::serializer::Transaction;
+
+#[derive(Transaction)]
+enum AnyTransaction {
+    Transfer { to: String },
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+use icon_utils::serializer::Transaction;
+
+#[derive(Transaction)]
+struct GenericTransaction<P> {
+    params: P,
+}
+
+fn main() {}
@@ -0,0 +1,54 @@
+use icon_utils::serializer::{serialize_to_string, Transaction};
+use icon_utils::wallet::Wallet;
+use serde::Serialize;
+
+#[derive(Serialize, Transaction)]
+#[transaction(method = "icx_sendTransaction")]
+struct TransferTransaction {
+    to: String,
+    value: String,
+}
+
+#[derive(Serialize)]
+struct CallParams {
+    to: String,
+}
+
+#[derive(Serialize, Transaction)]
+struct CallTransaction {
+    method: String,
+    params: CallParams,
+}
+
+#[derive(Transaction)]
+enum AnyTransaction {
+    Transfer(TransferTransaction),
+    Call(CallTransaction),
+}
+
+#[derive(Transaction)]
+#[transaction(params = "EitherOfParams")]
+pub enum Either<A, B> {
+    Left(A),
+    Right(B),
+}
+
+fn main() {
+    let queue = vec![
+        AnyTransaction::Transfer(TransferTransaction { to: "hx1".to_owned(), value: "0x1".to_owned() }),
+        AnyTransaction::Call(CallTransaction { method: "icx_call".to_owned(), params: CallParams { to: "cx1".to_owned() } }),
+    ];
+    let serialized: Vec<String> = queue.iter().map(|tx| serialize_to_string(tx).unwrap()).collect();
+    assert_eq!(serialized, ["icx_sendTransaction.params.{to.hx1.value.0x1}", "icx_call.params.{to.cx1}"]);
+
+    let wallet = Wallet::new("68ee9ca94b71c42ba79375b8677e29a717fb8072bcb17cd5ee288f9b77bc2894").unwrap();
+    let signed = wallet.sign_transaction(&queue[1]).unwrap();
+    assert_eq!(signed.method, "icx_call");
+    assert_eq!(signed.params["to"], "cx1");
+
+    let either: Either<TransferTransaction, CallTransaction> =
+        Either::Left(TransferTransaction { to: "hx2".to_owned(), value: "0x2".to_owned() });
+    assert_eq!(serialize_to_string(&either).unwrap(), "icx_sendTransaction.params.{to.hx2.value.0x2}");
+    assert_eq!(serde_json::to_value(EitherOfParams(&either)).unwrap(), serde_json::json!({"to": "hx2", "value": "0x2"}));
+    assert_eq!(serde_json::to_value(AnyTransactionParams(&queue[1])).unwrap(), serde_json::json!({"to": "cx1"}));
+}
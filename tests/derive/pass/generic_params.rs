@@ -0,0 +1,18 @@
+use icon_utils::serializer::{serialize_to_string, Transaction};
+use serde::Serialize;
+
+#[derive(Serialize, Transaction)]
+struct GenericTransaction<P> {
+    method: String,
+    params: P,
+}
+
+#[derive(Serialize)]
+struct TransferParams {
+    to: String,
+}
+
+fn main() {
+    let tx = GenericTransaction { method: "icx_sendTransaction".to_owned(), params: TransferParams { to: "hx1".to_owned() } };
+    assert_eq!(serialize_to_string(&tx).unwrap(), "icx_sendTransaction.params.{to.hx1}");
+}
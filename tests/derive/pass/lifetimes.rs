@@ -0,0 +1,15 @@
+use icon_utils::serializer::{serialize_to_string, Transaction};
+use serde::Serialize;
+
+#[derive(Serialize, Transaction)]
+#[transaction(method = "icx_call")]
+struct CallParams<'a> {
+    to: &'a str,
+    method: &'a str,
+}
+
+fn main() {
+    let to = String::from("cx1");
+    let tx = CallParams { to: &to, method: "balanceOf" };
+    assert_eq!(serialize_to_string(&tx).unwrap(), "icx_call.params.{method.balanceOf.to.cx1}");
+}
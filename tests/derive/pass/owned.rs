@@ -0,0 +1,15 @@
+use icon_utils::serializer::{serialize_to_string, tx_hash, Transaction};
+use serde::Serialize;
+
+#[derive(Serialize, Transaction)]
+#[transaction(method = "icx_sendTransaction")]
+struct TransferParams {
+    to: String,
+}
+
+fn main() {
+    let tx = TransferParams { to: "hx1".to_owned() };
+    assert_eq!(serialize_to_string(&tx).unwrap(), "icx_sendTransaction.params.{to.hx1}");
+    assert_eq!(tx_hash(&tx).unwrap(), tx_hash(&&tx).unwrap());
+    assert_eq!(serialize_to_string(tx).unwrap(), "icx_sendTransaction.params.{to.hx1}");
+}
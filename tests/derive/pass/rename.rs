@@ -0,0 +1,29 @@
+use icon_utils::serializer::{serialize_to_string, Transaction};
+use serde::Serialize;
+
+#[derive(Serialize, Transaction)]
+struct RenamedTransaction<P> {
+    method: String,
+    params: P,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DeployParams {
+    // last_field would sort between content_type and zeta_value by its Rust name, but sorts first renamed
+    zeta_value: String,
+    content_type: String,
+    #[serde(rename = "aardvark")]
+    last_field: String,
+}
+
+fn main() {
+    let tx = RenamedTransaction {
+        method: "icx_sendTransaction".to_owned(),
+        params: DeployParams { zeta_value: "z".to_owned(), content_type: "application/java".to_owned(), last_field: "a".to_owned() },
+    };
+    assert_eq!(
+        serialize_to_string(&tx).unwrap(),
+        "icx_sendTransaction.params.{aardvark.a.contentType.application/java.zetaValue.z}"
+    );
+}
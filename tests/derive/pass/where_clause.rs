@@ -0,0 +1,25 @@
+use icon_utils::serializer::{serialize_to_string, Transaction};
+use serde::Serialize;
+
+#[derive(Serialize, Transaction)]
+#[transaction(method = "icx_sendTransaction")]
+struct BatchParams<'a, T>
+where
+    T: Serialize + Clone,
+{
+    to: &'a str,
+    values: Vec<T>,
+}
+
+#[derive(Serialize, Transaction)]
+struct WrappedTransaction<'a, P: Serialize> {
+    method: String,
+    params: &'a P,
+}
+
+fn main() {
+    let batch = BatchParams { to: "cx1", values: vec![1u64, 2] };
+    assert_eq!(serialize_to_string(&batch).unwrap(), "icx_sendTransaction.params.{to.cx1.values.[0x1.0x2]}");
+    let wrapped = WrappedTransaction { method: "icx_sendTransaction".to_owned(), params: &batch };
+    assert_eq!(serialize_to_string(&wrapped).unwrap(), "icx_sendTransaction.params.{to.cx1.values.[0x1.0x2]}");
+}
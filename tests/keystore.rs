@@ -0,0 +1,129 @@
+//! Keystores with malformed parameters, which have to fail to decrypt rather than panic, and locked wallets.
+use std::thread;
+use std::time::Duration;
+
+use eth_keystore::KdfparamsType;
+use icon_utils::keystore::{KdfParams, Keystore, KeystoreError, LockedWallet};
+use icon_utils::signer::Signer;
+use icon_utils::wallet::{Wallet, WalletError};
+
+const PASSWORD: &str = "password";
+
+fn keystore() -> Keystore {
+    let wallet = Wallet::new("68ee9ca94b71c42ba79375b8677e29a717fb8072bcb17cd5ee288f9b77bc2894").unwrap();
+    Keystore::create(&wallet, PASSWORD, &KdfParams::Pbkdf2 { c: 1024 }).unwrap()
+}
+
+fn decrypt_error(keystore: &Keystore) -> KeystoreError {
+    match keystore.decrypt(PASSWORD) {
+        Err(WalletError::Keystore(error)) => error,
+        other => panic!("expected a keystore error, got {:?}", other.map(|wallet| wallet.address())),
+    }
+}
+
+#[test]
+fn rejects_short_iv() {
+    let mut keystore = keystore();
+    keystore.crypto.cipherparams.iv.truncate(15);
+    assert!(matches!(decrypt_error(&keystore), KeystoreError::InvalidCipherParams(_)));
+}
+
+#[test]
+fn rejects_long_iv() {
+    let mut keystore = keystore();
+    keystore.crypto.cipherparams.iv.push(0);
+    assert!(matches!(decrypt_error(&keystore), KeystoreError::InvalidCipherParams(_)));
+}
+
+#[test]
+fn rejects_short_derived_key() {
+    let json = keystore().to_json().unwrap().replace(r#""dklen":32"#, r#""dklen":16"#);
+    let keystore = Keystore::from_json(&json).unwrap();
+    assert!(matches!(decrypt_error(&keystore), KeystoreError::InvalidKdfParams(_)));
+}
+
+#[test]
+fn rejects_long_derived_key() {
+    let json = keystore().to_json().unwrap().replace(r#""dklen":32"#, r#""dklen":255"#);
+    let keystore = Keystore::from_json(&json).unwrap();
+    assert!(matches!(decrypt_error(&keystore), KeystoreError::InvalidKdfParams(_)));
+}
+
+#[test]
+fn rejects_excessive_pbkdf2_iterations() {
+    for c in [0, (1 << 20) + 1, u32::MAX] {
+        let mut keystore = keystore();
+        let KdfparamsType::Pbkdf2 { c: iterations, .. } = &mut keystore.crypto.kdfparams else { unreachable!() };
+        *iterations = c;
+        assert!(matches!(decrypt_error(&keystore), KeystoreError::InvalidKdfParams(_)));
+    }
+}
+
+#[test]
+fn rejects_excessive_scrypt_params() {
+    let wallet = Wallet::new("68ee9ca94b71c42ba79375b8677e29a717fb8072bcb17cd5ee288f9b77bc2894").unwrap();
+    let limits: [fn(&mut u32, &mut u32, &mut u32); 3] = [|n, _, _| *n = 1 << 30, |_, r, _| *r = 1024, |_, _, p| *p = 1 << 20];
+    for raise in limits {
+        let mut keystore = Keystore::create(&wallet, PASSWORD, &KdfParams::Scrypt { n: 2, r: 8, p: 1 }).unwrap();
+        let KdfparamsType::Scrypt { n, r, p, .. } = &mut keystore.crypto.kdfparams else { unreachable!() };
+        raise(n, r, p);
+        assert!(matches!(decrypt_error(&keystore), KeystoreError::InvalidKdfParams(_)));
+    }
+}
+
+#[test]
+fn rejects_wrong_mac() {
+    let mut keystore = keystore();
+    keystore.crypto.mac[31] ^= 1;
+    assert!(matches!(decrypt_error(&keystore), KeystoreError::WrongPassword));
+    keystore.crypto.mac.truncate(16);
+    assert!(matches!(decrypt_error(&keystore), KeystoreError::WrongPassword));
+}
+
+/// The key is dropped once the wallet is idle, without waiting for it to be used again
+#[test]
+fn locks_when_idle_without_signing() {
+    let locked = LockedWallet::new(keystore()).unwrap().idle_timeout(Duration::from_millis(50));
+    locked.unlock(PASSWORD).unwrap();
+    locked.sign_hash(&[1; 32]).unwrap();
+    assert!(locked.is_unlocked());
+    thread::sleep(Duration::from_millis(300));
+    assert!(!locked.is_unlocked());
+    assert!(matches!(locked.sign_hash(&[1; 32]), Err(WalletError::Locked)));
+}
+
+#[test]
+fn stays_unlocked_while_signing() {
+    let locked = LockedWallet::new(keystore()).unwrap().idle_timeout(Duration::from_millis(200));
+    locked.unlock(PASSWORD).unwrap();
+    for _ in 0..6 {
+        thread::sleep(Duration::from_millis(50));
+        locked.sign_hash(&[1; 32]).unwrap();
+    }
+    locked.lock();
+    assert!(!locked.is_unlocked());
+}
+
+#[test]
+fn unlocking_again_restarts_the_timeout() {
+    let locked = LockedWallet::new(keystore()).unwrap().idle_timeout(Duration::from_millis(200));
+    locked.unlock(PASSWORD).unwrap();
+    thread::sleep(Duration::from_millis(150));
+    locked.unlock(PASSWORD).unwrap();
+    thread::sleep(Duration::from_millis(100));
+    assert!(locked.is_unlocked());
+    thread::sleep(Duration::from_millis(300));
+    assert!(!locked.is_unlocked());
+}
+
+/// A keystore whose address was changed must not unlock, or the wallet would sign for another address than it reports
+#[test]
+fn rejects_tampered_address() {
+    let mut keystore = keystore();
+    keystore.address = "hx0000000000000000000000000000000000000001".to_owned();
+    let locked = LockedWallet::new(keystore).unwrap();
+    assert_eq!(locked.address().to_string(), "hx0000000000000000000000000000000000000001");
+    let error = locked.unlock(PASSWORD).unwrap_err();
+    assert!(matches!(error, WalletError::Keystore(KeystoreError::AddressMismatch { .. })), "{}", error);
+    assert!(!locked.is_unlocked());
+}
@@ -0,0 +1,383 @@
+//! Properties of the serializer over arbitrary params, generated from a fixed stream of pseudo-random bytes.
+//! The `fuzz` crate runs the same checks under libFuzzer.
+use std::collections::BTreeMap;
+use std::iter::Peekable;
+use std::str::Chars;
+
+use arbitrary::{Arbitrary, Unstructured};
+use icon_utils::icon_hex::{from_hex_int, to_hex_no_pad};
+use icon_utils::params::from_value;
+use icon_utils::serializer::{
+    serialize_to_string_with_options, BigNum, FloatMode, NullMode, SerializeError, SerializerOptions, Transaction,
+};
+use icon_utils::types::{Hash256, HexBytes, HexError};
+use icon_utils::units::Loop;
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+const CASES: u64 = 2000;
+const METHOD: &str = "icx_sendTransaction";
+
+/// Characters the serializer escapes with a backslash in strings and keys
+const STRUCTURAL: &[char] = &['\\', '.', '{', '}', '[', ']'];
+
+struct Tx<P> {
+    method: String,
+    params: P,
+}
+
+impl<P> Transaction for Tx<P> {
+    type Params = P;
+    fn params(&self) -> &P {
+        &self.params
+    }
+    fn method(&self) -> &String {
+        &self.method
+    }
+}
+
+#[derive(Debug, Arbitrary, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Params {
+    to: String,
+    value: Option<u128>,
+    step_limit: u64,
+    nonce: i128,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    data: Option<Data>,
+    tags: Vec<String>,
+    extra: BTreeMap<String, Option<String>>,
+    #[serde(flatten)]
+    common: Common,
+    flag: bool,
+    bytes: Vec<u8>,
+    pair: (u8, String),
+    ratio: f32,
+    nothing: (),
+}
+
+#[derive(Debug, Arbitrary, Serialize)]
+struct Common {
+    version: u8,
+    nid: Option<u16>,
+}
+
+#[derive(Debug, Arbitrary, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Data {
+    Transfer,
+    Message(String),
+    Call { method: String, params: BTreeMap<String, String> },
+    Pair(u8, char),
+}
+
+/// Generates the bytes of each case with xorshift, so every run checks the same cases
+fn cases() -> impl Iterator<Item = Vec<u8>> {
+    let mut state = 0x9e37_79b9_7f4a_7c15u64;
+    let mut next = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+    (0..CASES).map(move |_| {
+        let len = (next() % 2048) as usize;
+        (0..len).map(|_| next() as u8).collect()
+    })
+}
+
+/// A JSON value at most `depth` levels deep, with strings drawn towards the characters the serializer treats specially
+fn arbitrary_value(u: &mut Unstructured, depth: usize) -> arbitrary::Result<Value> {
+    let kinds = if depth == 0 { 6 } else { 8 };
+    Ok(match u.choose_index(kinds)? {
+        0 => Value::Null,
+        1 => Value::Bool(u.arbitrary()?),
+        2 => Value::from(u.arbitrary::<i64>()?),
+        3 => Value::from(u.arbitrary::<u64>()?),
+        4 => Value::from(u.arbitrary::<f64>()?),
+        5 => Value::String(arbitrary_string(u)?),
+        6 => Value::Array((0..u.int_in_range(0..=4)?).map(|_| arbitrary_value(u, depth - 1)).collect::<Result<_, _>>()?),
+        _ => {
+            let mut map = Map::new();
+            for _ in 0..u.int_in_range(0..=4)? {
+                map.insert(arbitrary_string(u)?, arbitrary_value(u, depth - 1)?);
+            }
+            Value::Object(map)
+        }
+    })
+}
+
+fn arbitrary_string(u: &mut Unstructured) -> arbitrary::Result<String> {
+    if u.ratio(1, 2)? {
+        return u.arbitrary();
+    }
+    const ALPHABET: &[char] = &['.', '{', '}', '[', ']', '\\', '0', '\0', 'a', 'é', '\u{10ffff}'];
+    (0..u.int_in_range(0..=8)?).map(|_| u.choose(ALPHABET).copied()).collect()
+}
+
+/// Every combination of options the properties are checked with
+fn options() -> Vec<SerializerOptions> {
+    let default = SerializerOptions::default();
+    vec![
+        default,
+        SerializerOptions { sort_fields: false, ..default },
+        SerializerOptions { float_mode: FloatMode::Decimal, ..default },
+        SerializerOptions { null_mode: NullMode::Omit, ..default },
+        SerializerOptions { null_mode: NullMode::Error, ..default },
+        SerializerOptions { max_depth: Some(2), ..default },
+        SerializerOptions { max_output_len: Some(64), float_mode: FloatMode::Decimal, ..default },
+    ]
+}
+
+/// Serializes `params` with every set of options, checking the properties that hold for any input.
+/// If `value` is the JSON of the params, the output has to parse back to it.
+fn check<P: Serialize>(params: P, value: Option<&Value>) {
+    let tx = Tx { method: METHOD.to_owned(), params };
+    for options in options() {
+        let first = serialize_to_string_with_options(&tx, options);
+        let second = serialize_to_string_with_options(&tx, options);
+        match (&first, &second) {
+            (Ok(first), Ok(second)) => assert_eq!(first, second),
+            (Err(first), Err(second)) => assert_eq!(first.to_string(), second.to_string()),
+            _ => panic!("serializing twice gave {:?} and {:?}", first, second),
+        }
+        let Ok(output) = first else { continue };
+        assert!(output.starts_with("icx_sendTransaction.params."), "{}", output);
+        if let Some(max_len) = options.max_output_len {
+            assert!(output.len() <= max_len, "{} is longer than {}", output, max_len);
+        }
+        assert_balanced(&output);
+        let parsed = parse(&output);
+        if let Some(value) = value {
+            assert_eq!(parsed, Node::expected(value, options), "{}", output);
+        }
+    }
+}
+
+/// Asserts every unescaped brace and bracket is closed in the order it was opened
+fn assert_balanced(output: &str) {
+    let mut open = Vec::new();
+    let mut chars = output.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '{' | '[' => open.push(c),
+            '}' => assert_eq!(open.pop(), Some('{'), "{}", output),
+            ']' => assert_eq!(open.pop(), Some('['), "{}", output),
+            _ => {}
+        }
+    }
+    assert!(open.is_empty(), "{}", output);
+}
+
+/// Params read back from the serialized format, with every value a string, null, array or map
+#[derive(Debug, PartialEq)]
+enum Node {
+    Null,
+    Str(String),
+    List(Vec<Node>),
+    Map(Vec<(String, Node)>),
+}
+
+impl Node {
+    /// What `value` should parse back to when serialized with `options`
+    fn expected(value: &Value, options: SerializerOptions) -> Node {
+        match value {
+            Value::Null => Node::Null,
+            Value::Bool(b) => Node::Str(if *b { "0x1" } else { "0x0" }.to_owned()),
+            Value::Number(n) => Node::Str(match (n.as_u64(), n.as_i64()) {
+                (Some(v), _) => to_hex_no_pad(u128::from(v)),
+                (None, Some(v)) => format!("-{}", to_hex_no_pad(u128::from(v.unsigned_abs()))),
+                // Floats are written with Display, which never uses an exponent
+                _ => n.as_f64().unwrap().to_string(),
+            }),
+            Value::String(s) => Node::Str(s.clone()),
+            Value::Array(values) => match values.as_slice() {
+                // `[""]` and `[]` are both written as `[]`
+                [Value::String(s)] if s.is_empty() => Node::List(Vec::new()),
+                values => Node::List(values.iter().map(|value| Node::expected(value, options)).collect()),
+            },
+            Value::Object(map) => Node::Map(
+                map.iter()
+                    .filter(|(_, value)| !(value.is_null() && options.null_mode == NullMode::Omit))
+                    .map(|(key, value)| (key.clone(), Node::expected(value, options)))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+/// Parses serialized params, failing on any structural character that should have been escaped
+fn parse(output: &str) -> Node {
+    let params = output.strip_prefix("icx_sendTransaction.params.").unwrap();
+    let mut chars = params.chars().peekable();
+    let node = parse_node(&mut chars, output);
+    assert_eq!(chars.next(), None, "trailing characters in {}", output);
+    node
+}
+
+fn parse_node(chars: &mut Peekable<Chars>, output: &str) -> Node {
+    let close = match chars.peek() {
+        Some('{') => '}',
+        Some('[') => ']',
+        _ => return parse_scalar(chars, output),
+    };
+    chars.next();
+    let mut entries = Vec::new();
+    let mut values = Vec::new();
+    if chars.peek() != Some(&close) {
+        loop {
+            if close == '}' {
+                let Node::Str(key) = parse_scalar(chars, output) else { panic!("null key in {}", output) };
+                assert_eq!(chars.next(), Some('.'), "key without a value in {}", output);
+                entries.push((key, parse_node(chars, output)));
+            } else {
+                values.push(parse_node(chars, output));
+            }
+            match chars.next() {
+                Some('.') => continue,
+                Some(c) if c == close => break,
+                c => panic!("expected . or {} but found {:?} in {}", close, c, output),
+            }
+        }
+    } else {
+        chars.next();
+    }
+    if close == '}' {
+        Node::Map(entries)
+    } else {
+        Node::List(values)
+    }
+}
+
+fn parse_scalar(chars: &mut Peekable<Chars>, output: &str) -> Node {
+    let mut value = String::new();
+    let mut null = false;
+    while let Some(&c) = chars.peek() {
+        match c {
+            '.' | '}' | ']' => break,
+            '{' | '[' => panic!("unescaped {} in {}", c, output),
+            '\\' => {
+                chars.next();
+                match chars.next() {
+                    Some('0') if value.is_empty() && !null => null = true,
+                    Some(c) if STRUCTURAL.contains(&c) => value.push(c),
+                    c => panic!("unexpected escape {:?} in {}", c, output),
+                }
+                continue;
+            }
+            c => value.push(c),
+        }
+        assert!(!null, "null followed by {:?} in {}", value, output);
+        chars.next();
+    }
+    if null {
+        Node::Null
+    } else {
+        Node::Str(value)
+    }
+}
+
+/// Escapes a string the way the serializer should
+fn escape(value: &str) -> String {
+    value.chars().fold(String::new(), |mut escaped, c| {
+        if STRUCTURAL.contains(&c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+        escaped
+    })
+}
+
+#[test]
+fn arbitrary_values_never_panic() {
+    for bytes in cases() {
+        let mut u = Unstructured::new(&bytes);
+        let Ok(value) = arbitrary_value(&mut u, 6) else { continue };
+        check(&value, Some(&value));
+    }
+}
+
+#[test]
+fn arbitrary_structs_never_panic() {
+    for bytes in cases() {
+        let Ok(params) = Params::arbitrary(&mut Unstructured::new(&bytes)) else { continue };
+        check(&params, None);
+    }
+}
+
+#[test]
+fn sorts_and_separates_arbitrary_maps() {
+    for bytes in cases() {
+        let mut u = Unstructured::new(&bytes);
+        let Ok(map) = BTreeMap::<String, u64>::arbitrary(&mut u) else { continue };
+        let tx = Tx { method: METHOD.to_owned(), params: &map };
+        let output = serialize_to_string_with_options(&tx, SerializerOptions::default()).unwrap();
+        let expected: Vec<String> = map.iter().map(|(key, value)| format!("{}.{}", escape(key), to_hex_no_pad(u128::from(*value)))).collect();
+        assert_eq!(output, format!("{}.params.{{{}}}", METHOD, expected.join(".")));
+    }
+}
+
+#[test]
+fn deep_nesting_terminates() {
+    let mut value = Value::from(1u8);
+    for i in 0..500 {
+        value = match i % 2 {
+            0 => Value::Array(vec![value]),
+            _ => Value::Object(Map::from_iter([("k".to_owned(), value)])),
+        };
+    }
+    let tx = Tx { method: METHOD.to_owned(), params: &value };
+    let output = serialize_to_string_with_options(&tx, SerializerOptions::default()).unwrap();
+    assert_balanced(&output);
+
+    let options = SerializerOptions { max_depth: Some(64), ..SerializerOptions::default() };
+    let error = serialize_to_string_with_options(&tx, options).unwrap_err();
+    assert!(matches!(error.kind(), SerializeError::DepthExceeded { max_depth: 64 }), "{}", error);
+}
+
+#[test]
+fn output_limit_counts_every_value() {
+    let values = [Value::from(1e300f64), Value::from(-1.5e200f64), Value::from("x".repeat(100)), Value::from(vec![u64::MAX; 4])];
+    for value in values {
+        let tx = Tx { method: METHOD.to_owned(), params: &value };
+        let options = SerializerOptions { max_output_len: Some(40), float_mode: FloatMode::Decimal, ..SerializerOptions::default() };
+        let error = serialize_to_string_with_options(&tx, options).unwrap_err();
+        assert!(matches!(error.kind(), SerializeError::OutputTooLong { max_len: 40 }), "{}", error);
+    }
+}
+
+#[test]
+fn integers_and_booleans_are_canonical_hex() {
+    let params = (0u8, 16u64, -1i32, u128::MAX, i128::MIN, true, false);
+    let tx = Tx { method: METHOD.to_owned(), params };
+    let output = serialize_to_string_with_options(&tx, SerializerOptions::default()).unwrap();
+    assert_eq!(
+        output,
+        "icx_sendTransaction.params.[0x0.0x10.-0x1.0xffffffffffffffffffffffffffffffff.-0x80000000000000000000000000000000.0x1.0x0]"
+    );
+}
+
+#[test]
+fn hex_parsers_reject_leading_zeros_and_uppercase() {
+    for value in ["0x01", "0xAB"] {
+        assert!(from_hex_int(value).is_err(), "{}", value);
+        assert_eq!(BigNum::from_hex(value), None, "{}", value);
+        assert!(Loop::from_hex(value).is_err(), "{}", value);
+        assert!(from_value::<u64>(Value::from(value)).is_err(), "{}", value);
+        assert!(from_value::<i64>(Value::from(format!("-{}", value))).is_err(), "{}", value);
+    }
+    assert_eq!(from_hex_int("0x01"), Err(HexError::LeadingZeros("0x01".to_owned())));
+    assert_eq!(from_hex_int("0xAB"), Err(HexError::Uppercase("0xAB".to_owned())));
+    assert_eq!(from_value::<u64>(Value::from("0xab")).unwrap(), 0xab);
+
+    // Bytes keep their leading zeros, only uppercase is rejected
+    let hash = format!("0x00{}", "ab".repeat(31));
+    assert_eq!(hash.parse::<Hash256>().unwrap().to_string(), hash);
+    let uppercase = format!("0x00{}", "AB".repeat(31));
+    assert_eq!(uppercase.parse::<Hash256>(), Err(HexError::Uppercase(uppercase.clone())));
+    assert_eq!("0x01".parse::<HexBytes>().unwrap().0, [1]);
+    assert!("0xAB".parse::<HexBytes>().is_err());
+}
@@ -0,0 +1,315 @@
+//! Signature layout and RFC6979 vectors.
+//!
+//! Each transaction vector names its source. None of them were produced by this crate: the serialized string, hash and
+//! address come from a Python implementation of the JSON-RPC v3 serialization rules hashing with `hashlib`, and the
+//! signature from OpenSSL's deterministic ECDSA, so the tests check this crate against another implementation rather
+//! than its own output. Vectors published by the Java or Python SDK test suites belong in `vectors()` too, with the
+//! SDK and test they were taken from.
+use std::sync::OnceLock;
+
+use icon_utils::serializer::{serialize_value, Transaction};
+use icon_utils::signer::{DeterministicSigner, Signature, SIGNATURE_LEN};
+use icon_utils::wallet::{verify_signed_transaction, SignedTransaction, Wallet};
+use k256::elliptic_curve::PrimeField;
+use k256::Scalar;
+use serde::Serialize;
+use serde_json::{json, Value};
+use sha3::{Digest, Sha3_256};
+
+struct Vector {
+    /// Where the vector comes from
+    source: &'static str,
+    key: &'static str,
+    address: &'static str,
+    params: Value,
+    serialized: &'static str,
+    /// The SHA3-256 hash of `serialized`, the hash that is signed
+    hash: &'static str,
+    signature: &'static str,
+}
+
+/// Serialized and hashed in Python, signed with OpenSSL's RFC6979 ECDSA through pyca/cryptography 48
+const REFERENCE: &str = "Python v3 serializer, hashlib SHA3-256, OpenSSL RFC6979 via pyca/cryptography";
+
+fn vectors() -> Vec<Vector> {
+    vec![
+        Vector {
+            source: REFERENCE,
+            key: "68ee9ca94b71c42ba79375b8677e29a717fb8072bcb17cd5ee288f9b77bc2894",
+            address: "hxb874d46c44dd5db9562f2b077a84e45c35dbfc64",
+            params: json!({
+                "version": "0x3",
+                "from": "hxb874d46c44dd5db9562f2b077a84e45c35dbfc64",
+                "to": "hx5bfdb090f43a808005ffc27c25b213145e80b7cd",
+                "value": "0xde0b6b3a7640000",
+                "stepLimit": "0x12345",
+                "timestamp": "0x563a6cf330136",
+                "nid": "0x3",
+                "nonce": "0x1"
+            }),
+            serialized: "icx_sendTransaction.params.{from.hxb874d46c44dd5db9562f2b077a84e45c35dbfc64.nid.0x3.nonce.0x1.stepLimit.0x12345.timestamp.0x563a6cf330136.to.hx5bfdb090f43a808005ffc27c25b213145e80b7cd.value.0xde0b6b3a7640000.version.0x3}",
+            hash: "0xc0ae21fce27860f23627c8b9161de0f0aaab23f7fbce6f86ac877f0a1df23262",
+            signature: "0LisGfAA1yOXEdXBOPFlkUVUFs2CYCzLvaSg4vzdc1hqdXQZKk6tgQgdbHfl1MCuSChy3kX3/CATZowwRdZmbgE=",
+        },
+        Vector {
+            source: REFERENCE,
+            key: "68ee9ca94b71c42ba79375b8677e29a717fb8072bcb17cd5ee288f9b77bc2894",
+            address: "hxb874d46c44dd5db9562f2b077a84e45c35dbfc64",
+            params: json!({
+                "version": "0x3",
+                "from": "hxb874d46c44dd5db9562f2b077a84e45c35dbfc64",
+                "to": "cxb0776ee37f5b45bfaea8cff1d8232fbb6122ec32",
+                "stepLimit": "0x12345",
+                "timestamp": "0x563a6cf330136",
+                "nid": "0x1",
+                "dataType": "call",
+                "data": { "method": "transfer", "params": { "_to": "hxab2d8215eab14bc6bdd8bfb2c8151257032ecd8b", "_value": "0x1", "_data": null } }
+            }),
+            serialized: "icx_sendTransaction.params.{data.{method.transfer.params.{_data.\\0._to.hxab2d8215eab14bc6bdd8bfb2c8151257032ecd8b._value.0x1}}.dataType.call.from.hxb874d46c44dd5db9562f2b077a84e45c35dbfc64.nid.0x1.stepLimit.0x12345.timestamp.0x563a6cf330136.to.cxb0776ee37f5b45bfaea8cff1d8232fbb6122ec32.version.0x3}",
+            hash: "0x1c6e101d5293f2782a3dfb12e65b0a283767b80859aa64c046daf0289d6791e6",
+            signature: "6OGflDmUBxXxqn/FVSQ/UUU00mX5h8OgNuq8CEineUdiehck11ySN9H3jre+LQEFJfPN5POeqhAVaGl+y8p5egA=",
+        },
+        Vector {
+            source: REFERENCE,
+            key: "592eb276d534e2c41a2d9356c0ab262dc233d87e4dd71ce705ec130a8d27ff0c",
+            address: "hxe7af5fcfd8dfc67530a01a0e403882687528dfcb",
+            params: json!({
+                "version": "0x3",
+                "from": "hxe7af5fcfd8dfc67530a01a0e403882687528dfcb",
+                "to": "hx5bfdb090f43a808005ffc27c25b213145e80b7cd",
+                "value": "0xde0b6b3a7640000",
+                "stepLimit": "0x12345",
+                "timestamp": "0x563a6cf330136",
+                "nid": "0x3",
+                "nonce": "0x1"
+            }),
+            serialized: "icx_sendTransaction.params.{from.hxe7af5fcfd8dfc67530a01a0e403882687528dfcb.nid.0x3.nonce.0x1.stepLimit.0x12345.timestamp.0x563a6cf330136.to.hx5bfdb090f43a808005ffc27c25b213145e80b7cd.value.0xde0b6b3a7640000.version.0x3}",
+            hash: "0x7f60f3d582ba3efdaa1012a7a2d00295fe758f8500e0cf7eb1d7dd3d19bf97e6",
+            signature: "rcq3QVDin2xZ4s3MeUn48+jhYZAT4gs6ANuneaBT6L9XnpcBm5drxyw2MWFoN+vX6IcWtRMZDQJ2RsS6mQ2MwwA=",
+        },
+        Vector {
+            source: REFERENCE,
+            key: "592eb276d534e2c41a2d9356c0ab262dc233d87e4dd71ce705ec130a8d27ff0c",
+            address: "hxe7af5fcfd8dfc67530a01a0e403882687528dfcb",
+            params: json!({
+                "version": "0x3",
+                "from": "hxe7af5fcfd8dfc67530a01a0e403882687528dfcb",
+                "to": "cxb0776ee37f5b45bfaea8cff1d8232fbb6122ec32",
+                "stepLimit": "0x12345",
+                "timestamp": "0x563a6cf330136",
+                "nid": "0x1",
+                "dataType": "call",
+                "data": { "method": "transfer", "params": { "_to": "hxab2d8215eab14bc6bdd8bfb2c8151257032ecd8b", "_value": "0x1", "_data": null } }
+            }),
+            serialized: "icx_sendTransaction.params.{data.{method.transfer.params.{_data.\\0._to.hxab2d8215eab14bc6bdd8bfb2c8151257032ecd8b._value.0x1}}.dataType.call.from.hxe7af5fcfd8dfc67530a01a0e403882687528dfcb.nid.0x1.stepLimit.0x12345.timestamp.0x563a6cf330136.to.cxb0776ee37f5b45bfaea8cff1d8232fbb6122ec32.version.0x3}",
+            hash: "0x344c44ef4af1a968c3a5de6a2380f09269e71d28c807f482764b841d1a10e062",
+            signature: "l2aoG4WY80ogj+n03KiljlhZ9nCMDbzqgBZVJkftT/IyQq4UzOvB+L7Fu1PsolA+EnraumkRG3M4SvL7g6zPxgA=",
+        },
+        Vector {
+            source: REFERENCE,
+            key: "e7a6d9c0b5e3f2a1d4c8b7a6f5e4d3c2b1a0f9e8d7c6b5a4f3e2d1c0b9a8f7e6",
+            address: "hx9041a0a79f330610831ac02d9b4b0f38637d217b",
+            params: json!({
+                "version": "0x3",
+                "from": "hx9041a0a79f330610831ac02d9b4b0f38637d217b",
+                "to": "hx0000000000000000000000000000000000000001",
+                "value": "0xffffffffffffffffffffffffffffffff",
+                "stepLimit": "0x186a0",
+                "timestamp": "0x5e1b3c2d4f6a8",
+                "nid": "0x1",
+                "nonce": "0x0"
+            }),
+            serialized: "icx_sendTransaction.params.{from.hx9041a0a79f330610831ac02d9b4b0f38637d217b.nid.0x1.nonce.0x0.stepLimit.0x186a0.timestamp.0x5e1b3c2d4f6a8.to.hx0000000000000000000000000000000000000001.value.0xffffffffffffffffffffffffffffffff.version.0x3}",
+            hash: "0x122b8df24f42fffc220286ddb1a6c250a2e353d109a8e57840dac588bdc2d82d",
+            signature: "RO4YiFcdpv3cih9TnBP3tKK3Fbpo9+Xpjo7eC3sdSRRVR1pzZdrU3m5N8wsTYoX1w/j15za1nXK9BA5nvVEKcgA=",
+        },
+        Vector {
+            source: REFERENCE,
+            key: "3b9f1c7e2a5d8f0b4c6e9a1d3f5b7c9e0a2c4e6f8b1d3f5a7c9e0b2d4f6a8c1e",
+            address: "hx2ce6b423e5cca452d5b8a8d9ca20c4e3b946ef16",
+            params: json!({
+                "version": "0x3",
+                "from": "hx2ce6b423e5cca452d5b8a8d9ca20c4e3b946ef16",
+                "to": "cx0000000000000000000000000000000000000001",
+                "stepLimit": "0x2faf080",
+                "timestamp": "0x5e1b3c2d4f6a9",
+                "nid": "0x2",
+                "dataType": "call",
+                "data": { "method": "setMemo", "params": { "memo": "a.b{c}[d]\\e", "tags": ["x.y", null, "[z]"] } }
+            }),
+            serialized: r"icx_sendTransaction.params.{data.{method.setMemo.params.{memo.a\.b\{c\}\[d\]\\e.tags.[x\.y.\0.\[z\]]}}.dataType.call.from.hx2ce6b423e5cca452d5b8a8d9ca20c4e3b946ef16.nid.0x2.stepLimit.0x2faf080.timestamp.0x5e1b3c2d4f6a9.to.cx0000000000000000000000000000000000000001.version.0x3}",
+            hash: "0x671968bf82c21aa769fb5fccd94d3edc201b45bd91fa5d7557846792513055c9",
+            signature: "2qn6wCwfJBF67e/M2yGtmEuxE915w8pPhJUVEMYa/WF/ZObKFlNm6acxL6S7lBXNUNYSP2OOiDTPKzwug1aE2AA=",
+        },
+    ]
+}
+
+#[test]
+fn signs_vectors() {
+    for vector in vectors() {
+        let wallet = Wallet::new(vector.key).unwrap();
+        assert_eq!(wallet.address(), vector.address);
+        let serialized = serialize_value("icx_sendTransaction", &vector.params).unwrap();
+        assert_eq!(serialized, vector.serialized, "{}", vector.source);
+        assert_eq!(hex_0x(&Sha3_256::digest(&serialized)), vector.hash, "{}", vector.source);
+        assert_eq!(wallet.sign(&serialized).unwrap().to_base64(), vector.signature, "{}", vector.source);
+    }
+}
+
+#[test]
+fn verifies_vectors() {
+    for vector in vectors() {
+        let mut params = vector.params.clone();
+        params["signature"] = json!(vector.signature);
+        let signed = SignedTransaction::from_rpc_params("icx_sendTransaction", params).unwrap();
+        assert_eq!(signed.tx_hash().to_string(), vector.hash, "{}", vector.source);
+        assert_eq!(verify_signed_transaction(&signed).unwrap().to_string(), vector.address, "{}", vector.source);
+    }
+}
+
+#[test]
+fn encodes_recovery_id_last() {
+    for vector in vectors() {
+        let signature: Signature = vector.signature.parse().unwrap();
+        let bytes = signature.to_bytes();
+        assert_eq!(bytes.len(), SIGNATURE_LEN);
+        assert_eq!(bytes[64], signature.recovery_id());
+        assert!(signature.recovery_id() <= 1);
+        assert_eq!(&bytes[..32], signature.r());
+        assert_eq!(&bytes[32..64], signature.s());
+        assert_eq!(Signature::new(*signature.r(), *signature.s(), signature.recovery_id()).unwrap(), signature);
+    }
+}
+
+#[test]
+fn normalizes_ethereum_recovery_ids() {
+    for vector in vectors() {
+        let signature: Signature = vector.signature.parse().unwrap();
+        let mut bytes = signature.to_bytes();
+        bytes[64] += 27;
+        let normalized = Signature::from_bytes(&bytes).unwrap();
+        assert_eq!(normalized, signature);
+        assert_eq!(normalized.to_base64(), vector.signature);
+    }
+}
+
+#[test]
+fn rejects_invalid_layouts() {
+    let signature: Signature = vectors()[0].signature.parse().unwrap();
+    let bytes = signature.to_bytes();
+    assert!(Signature::from_bytes(&bytes[..64]).is_err());
+    assert!(Signature::from_bytes(&[bytes.as_slice(), &[0]].concat()).is_err());
+    for id in [2, 3, 26, 29, 255] {
+        let mut bytes = bytes;
+        bytes[64] = id;
+        assert!(Signature::from_bytes(&bytes).is_err());
+    }
+}
+
+#[test]
+fn signs_rfc6979_vectors() {
+    // Published secp256k1 RFC6979 vectors for the private key 1, signing the SHA-256 of each message
+    let wallet = Wallet::new("0000000000000000000000000000000000000000000000000000000000000001").unwrap();
+    let vectors = [
+        (
+            // "Satoshi Nakamoto"
+            "a0dc65ffca799873cbea0ac274015b9526505daaaed385155425f7337704883e",
+            "934b1ea10a4b3c1757e2b0c017d0b6143ce3c9a7e6a4a49860d7a6ab210ee3d82442ce9d2b916064108014783e923ec36b49743e2ffa1c4496f01a512aafd9e501",
+        ),
+        (
+            // "All those moments will be lost in time, like tears in rain. Time to die..."
+            "7d1833f54854ac51659521afcd0ec6dca2ce2351429614bfa28a756b1b3c637f",
+            "8600dbd41e348fe5c9465ab92d23e3db8b98b873beecd930736488696438cb6b547fe64427496db33bf66019dacbf0039c04199abb0122918601db38a72cfc2100",
+        ),
+    ];
+    for (hash, expected) in vectors {
+        let hash: [u8; 32] = hex::decode(hash).unwrap().try_into().unwrap();
+        assert_eq!(hex::encode(sign_deterministic(&wallet, &hash).to_bytes()), expected);
+    }
+}
+
+/// Signs the recorded hash of every vector, so the nonces have to match the RFC6979 nonces OpenSSL derived for them,
+/// not only this crate's own serialization of the params
+#[test]
+fn signs_deterministically() {
+    for vector in vectors() {
+        let wallet = Wallet::new(vector.key).unwrap();
+        let hash: [u8; 32] = hex::decode(&vector.hash[2..]).unwrap().try_into().unwrap();
+        let signature = sign_deterministic(&wallet, &hash);
+        assert_eq!(sign_deterministic(&wallet, &hash), signature, "{}", vector.source);
+        assert_eq!(signature.to_base64(), vector.signature, "{}", vector.source);
+        assert!(signature.s() <= &HALF_ORDER, "{}", vector.source);
+    }
+}
+
+/// OpenSSL doesn't normalize s, so where its signature has a high s this crate has to return the order minus it
+#[test]
+fn normalizes_openssl_high_s() {
+    // OpenSSL's RFC6979 signature of the hash of the third vector, as DER decoded by pyca/cryptography
+    let vector = &vectors()[2];
+    let r = "adcab74150e29f6c59e2cdcc7949f8f3e8e1619013e20b3a00dba779a053e8bf";
+    let high_s = "a86168fe64689438d3c9ce9e97c81426d227c6319c2f9339498b99d23728b47e";
+    let signature: Signature = vector.signature.parse().unwrap();
+    assert_eq!(hex::encode(signature.r()), r);
+    let (s, high_s) = (scalar(signature.s()), scalar(&hex::decode(high_s).unwrap().try_into().unwrap()));
+    assert_eq!(s + high_s, Scalar::ZERO);
+}
+
+/// Half the order of secp256k1, the largest s of a normalized signature
+const HALF_ORDER: [u8; 32] = [
+    0x7f, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x5d, 0x57, 0x6e, 0x73,
+    0x57, 0xa4, 0x50, 0x1d, 0xdf, 0xe9, 0x2f, 0x46, 0x68, 0x1b, 0x20, 0xa0,
+];
+
+fn scalar(bytes: &[u8; 32]) -> Scalar {
+    Option::from(Scalar::from_repr((*bytes).into())).unwrap()
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Transfer {
+    from: String,
+    to: String,
+    value: u128,
+    step_limit: u128,
+    nid: u8,
+}
+
+impl Transaction for Transfer {
+    type Params = Transfer;
+    fn params(&self) -> &Transfer {
+        self
+    }
+    fn method(&self) -> &String {
+        static METHOD: OnceLock<String> = OnceLock::new();
+        METHOD.get_or_init(|| "icx_sendTransaction".to_owned())
+    }
+}
+
+/// Integers above `u64::MAX` can't be held by a `serde_json::Value`, so the params must be kept as hex strings
+#[test]
+fn signs_u128_values() {
+    let wallet = Wallet::new(vectors()[0].key).unwrap();
+    let tx = Transfer {
+        from: wallet.address(),
+        to: "hx0000000000000000000000000000000000000001".to_owned(),
+        value: u128::MAX,
+        step_limit: u128::from(u64::MAX) + 1,
+        nid: 1,
+    };
+    let signed = wallet.sign_transaction(&tx).unwrap();
+    assert_eq!(signed.params["value"], "0xffffffffffffffffffffffffffffffff");
+    assert_eq!(signed.params["stepLimit"], "0x10000000000000000");
+    assert_eq!(signed.params["nid"], "0x1");
+    let loaded = SignedTransaction::from_rpc_params("icx_sendTransaction", signed.to_rpc_params()).unwrap();
+    assert_eq!(loaded.hash, signed.hash);
+    assert_eq!(verify_signed_transaction(&loaded).unwrap().to_string(), wallet.address());
+}
+
+fn hex_0x(bytes: &[u8]) -> String {
+    format!("0x{}", hex::encode(bytes))
+}
+
+fn sign_deterministic(signer: &impl DeterministicSigner, hash: &[u8; 32]) -> Signature {
+    signer.sign_hash(hash).unwrap()
+}